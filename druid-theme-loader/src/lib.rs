@@ -33,7 +33,7 @@
 
 mod parse;
 mod widget;
-pub use widget::ThemeLoader;
+pub use widget::{ThemeLoader, RELOAD_THEME, SET_THEME_PATH};
 
 use druid::Env;
 
@@ -10,6 +10,11 @@ use crate::{LoadableTheme, ThemeLoadError};
 
 pub const RELOAD_THEME: Selector<()> = Selector::new("runebender.theme-loader-reload");
 
+/// Load the theme from a different file.
+///
+/// The argument **must** be the `PathBuf` of the new theme file.
+pub const SET_THEME_PATH: Selector<PathBuf> = Selector::new("runebender.theme-loader-set-path");
+
 #[cfg(feature = "notify")]
 const RELOAD_DURATION: Duration = Duration::from_millis(500);
 
@@ -21,6 +26,9 @@ pub struct ThemeLoader<T, W> {
     theme_path: PathBuf,
     theme: T,
     current_env: Option<Env>,
+    /// Watches the theme file; dropping this stops the watcher thread.
+    #[cfg(feature = "notify")]
+    watcher: Option<notify::RecommendedWatcher>,
     inner: W,
 }
 
@@ -37,6 +45,8 @@ impl<T: LoadableTheme, W> ThemeLoader<T, W> {
             theme,
             inner,
             current_env: None,
+            #[cfg(feature = "notify")]
+            watcher: None,
         }
     }
 
@@ -71,6 +81,23 @@ where
                 ctx.request_layout();
                 ctx.set_handled();
             }
+            Event::Command(cmd) if cmd.is(SET_THEME_PATH) => {
+                self.theme_path = cmd.get_unchecked(SET_THEME_PATH).clone();
+                #[cfg(feature = "notify")]
+                {
+                    // stop watching the old file before we watch the new one
+                    self.watcher = None;
+                    self.watcher = start_watcher(
+                        ctx.get_external_handle(),
+                        self.theme_path.clone(),
+                        ctx.widget_id(),
+                    );
+                }
+                self.reload_theme_and_log_errors(env);
+                ctx.request_layout();
+                ctx.request_paint();
+                ctx.set_handled();
+            }
             _ => (),
         }
         let child_env = self.current_env.as_ref().unwrap_or(env);
@@ -83,7 +110,7 @@ where
             #[cfg(feature = "notify")]
             {
                 let event_snk = ctx.get_external_handle();
-                start_watcher(event_snk, self.theme_path.clone(), ctx.widget_id());
+                self.watcher = start_watcher(event_snk, self.theme_path.clone(), ctx.widget_id());
             }
             self.reload_theme_and_log_errors(env);
         }
@@ -116,34 +143,39 @@ where
 }
 
 #[cfg(feature = "notify")]
-fn start_watcher(sink: ExtEventSink, path: PathBuf, target: WidgetId) {
+fn start_watcher(
+    sink: ExtEventSink,
+    path: PathBuf,
+    target: WidgetId,
+) -> Option<notify::RecommendedWatcher> {
     use notify::{DebouncedEvent, RecursiveMode, Watcher};
     use std::sync::mpsc;
-    std::thread::spawn(move || {
-        let (tx, rx) = mpsc::channel();
-        let mut watcher = notify::watcher(tx, RELOAD_DURATION).unwrap();
-        if let Err(e) = watcher.watch(&path, RecursiveMode::NonRecursive) {
-            log::error!(
-                "theme watcher failed to watch path '{}': '{}'",
-                path.to_string_lossy(),
-                e
-            );
-            return;
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::watcher(tx, RELOAD_DURATION) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            log::error!("failed to start theme watcher: '{}'", e);
+            return None;
         }
+    };
+    if let Err(e) = watcher.watch(&path, RecursiveMode::NonRecursive) {
+        log::error!(
+            "theme watcher failed to watch path '{}': '{}'",
+            path.to_string_lossy(),
+            e
+        );
+        return None;
+    }
 
-        loop {
-            match rx.recv() {
-                Ok(DebouncedEvent::Write(_)) => {
-                    if sink.submit_command(RELOAD_THEME, (), target).is_err() {
-                        break;
-                    }
-                }
-                Ok(_) => (),
-                Err(e) => {
-                    log::error!("watch error: {:?}", e);
+    // the channel is closed when the watcher is dropped, which ends the thread.
+    std::thread::spawn(move || {
+        while let Ok(event) = rx.recv() {
+            if let DebouncedEvent::Write(_) = event {
+                if sink.submit_command(RELOAD_THEME, (), target).is_err() {
                     break;
                 }
             }
         }
     });
+    Some(watcher)
 }
@@ -0,0 +1,154 @@
+//! Benchmarks for drawing the glyph grid of a very large font.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use druid::kurbo::{Affine, Rect, Size};
+use norad::glyph::{Component, Contour, ContourPoint, Outline, PointType};
+use norad::{Glyph, GlyphName, Ufo};
+
+use runebender_lib::data::Workspace;
+use runebender_lib::widgets::{GridGeometry, ThumbnailCache};
+
+const GLYPH_COUNT: usize = 10_000;
+const CELL_SIZE: f64 = 128.0;
+const GRID_WIDTH: f64 = 1280.0;
+const VIEWPORT_HEIGHT: f64 = 800.0;
+
+/// A font with `GLYPH_COUNT` glyphs; most have a couple of contours, and
+/// every tenth is a composite of two of the others.
+fn synthetic_font() -> Ufo {
+    let mut ufo = Ufo::new();
+    let layer = ufo.get_default_layer_mut().unwrap();
+    for i in 0..GLYPH_COUNT {
+        let mut glyph = Glyph::new_named(format!("glyph{:05}", i));
+        glyph.codepoints = std::char::from_u32(0x4E00 + i as u32).map(|c| vec![c]);
+        let outline = if i % 10 == 9 {
+            let component = |base: usize, dx: f64| {
+                let base: GlyphName = format!("glyph{:05}", base).into();
+                Component::new(base, Affine::translate((dx, 0.0)).into(), None, None)
+            };
+            Outline {
+                contours: Vec::new(),
+                components: vec![component(i - 9, 0.0), component(i - 8, 300.0)],
+            }
+        } else {
+            let size = 100.0 + (i % 400) as f32;
+            Outline {
+                contours: vec![square(0.0, size), square(size * 0.25, size * 0.5)],
+                components: Vec::new(),
+            }
+        };
+        glyph.outline = Some(outline);
+        layer.insert_glyph(glyph);
+    }
+    ufo
+}
+
+fn square(origin: f32, size: f32) -> Contour {
+    let points = vec![
+        (origin, origin),
+        (origin, origin + size),
+        (origin + size, origin + size),
+        (origin + size, origin),
+    ]
+    .into_iter()
+    .map(|(x, y)| ContourPoint::new(x, y, PointType::Line, false, None, None, None))
+    .collect();
+    Contour::new(points, None, None)
+}
+
+fn workspace() -> Workspace {
+    let mut workspace = Workspace::default();
+    workspace.set_file(synthetic_font(), None);
+    workspace
+}
+
+fn geometry() -> GridGeometry {
+    GridGeometry::new(GRID_WIDTH, Size::new(CELL_SIZE, CELL_SIZE), GLYPH_COUNT)
+}
+
+/// Build the thumbnails for every cell in `viewport`.
+fn draw_viewport(
+    cache: &mut ThumbnailCache,
+    data: &Workspace,
+    names: &[GlyphName],
+    viewport: Rect,
+) {
+    for idx in geometry().visible_range(viewport) {
+        criterion::black_box(cache.get(data, &names[idx]));
+    }
+}
+
+fn glyph_grid(c: &mut Criterion) {
+    let data = workspace();
+    let names = data.filtered_glyph_names();
+    let viewport = Rect::new(0.0, 0.0, GRID_WIDTH, VIEWPORT_HEIGHT);
+
+    c.bench_function("load 10k glyphs", |b| {
+        b.iter_batched(
+            synthetic_font,
+            |ufo| Workspace::default().set_file(ufo, None),
+            BatchSize::LargeInput,
+        )
+    });
+
+    c.bench_function("filtered glyph names", |b| {
+        b.iter(|| data.filtered_glyph_names())
+    });
+
+    c.bench_function("paint first screen, cold", |b| {
+        b.iter_batched(
+            || {
+                let mut cache = ThumbnailCache::new();
+                cache.set_scale(CELL_SIZE, data.units_per_em());
+                cache
+            },
+            |mut cache| draw_viewport(&mut cache, &data, &names, viewport),
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("paint first screen, warm", |b| {
+        let mut cache = ThumbnailCache::new();
+        cache.set_scale(CELL_SIZE, data.units_per_em());
+        draw_viewport(&mut cache, &data, &names, viewport);
+        b.iter(|| draw_viewport(&mut cache, &data, &names, viewport))
+    });
+
+    c.bench_function("scroll through 10k glyphs", |b| {
+        let height = geometry().size().height;
+        b.iter(|| {
+            let mut cache = ThumbnailCache::new();
+            cache.set_scale(CELL_SIZE, data.units_per_em());
+            let mut y = 0.0;
+            while y < height {
+                let viewport = Rect::new(0.0, y, GRID_WIDTH, y + VIEWPORT_HEIGHT);
+                draw_viewport(&mut cache, &data, &names, viewport);
+                y += VIEWPORT_HEIGHT / 2.0;
+            }
+            cache.len()
+        })
+    });
+
+    c.bench_function("repaint after editing one glyph", |b| {
+        b.iter_batched(
+            || {
+                let mut cache = ThumbnailCache::new();
+                cache.set_scale(CELL_SIZE, data.units_per_em());
+                draw_viewport(&mut cache, &data, &names, viewport);
+                let mut edited = data.clone();
+                edited.transform_glyphs(&names[..1], Affine::scale(2.0));
+                (cache, edited)
+            },
+            |(mut cache, edited)| {
+                // only the edited glyph and the composite using it are rebuilt
+                let stale = cache.remove_stale(&edited);
+                draw_viewport(&mut cache, &edited, &names, viewport);
+                stale
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, glyph_grid);
+criterion_main!(benches);
@@ -1,4 +1,8 @@
-//! Build script to generate our glyph name lookup table.
+//! Build script to generate our glyph name and unicode lookup tables.
+//!
+//! The unicode tables are generated from files in the Unicode Character
+//! Database (UnicodeData.txt, Scripts.txt and Blocks.txt); to update them,
+//! replace those files in `resources`.
 
 use std::convert::TryFrom;
 use std::env;
@@ -8,8 +12,14 @@ use std::path::Path;
 use std::str::FromStr;
 
 const OUT_FILE: &str = "glyph_names_codegen.rs";
+const UNICODE_OUT_FILE: &str = "unicode_codegen.rs";
 
 fn main() {
+    write_glyph_names();
+    write_unicode_tables();
+}
+
+fn write_glyph_names() {
     let path = Path::new(&env::var("OUT_DIR").unwrap()).join(OUT_FILE);
     let mut file = BufWriter::new(File::create(&path).unwrap());
     let names = include_str!("../resources/aglfn.txt");
@@ -34,6 +44,142 @@ fn main() {
     .unwrap();
 }
 
+fn write_unicode_tables() {
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join(UNICODE_OUT_FILE);
+    let mut file = BufWriter::new(File::create(&path).unwrap());
+    let data = UnicodeData::parse(include_str!("../resources/UnicodeData.txt"));
+    let scripts = parse_ranges(include_str!("../resources/Scripts.txt"));
+    let blocks = parse_ranges(include_str!("../resources/Blocks.txt"));
+
+    let names = data
+        .names
+        .iter()
+        .map(|(chr, name)| format!("('{}', \"{}\")", chr.escape_unicode(), name))
+        .collect::<Vec<_>>();
+    writeln!(
+        &mut file,
+        "static UNICODE_NAMES: &[(char, &str)] = &[\n{}];\n",
+        names.join(",\n")
+    )
+    .unwrap();
+    write_ranges(&mut file, "NAME_RANGES", &data.name_ranges);
+    write_ranges(&mut file, "GENERAL_CATEGORIES", &data.categories);
+    write_ranges(&mut file, "SCRIPTS", &scripts);
+    write_ranges(&mut file, "BLOCKS", &blocks);
+}
+
+/// Write a sorted table of (first, last, value) ranges.
+fn write_ranges(file: &mut impl Write, name: &str, ranges: &[(u32, u32, String)]) {
+    let formatted = ranges
+        .iter()
+        .map(|(first, last, value)| format!("(0x{:04X}, 0x{:04X}, \"{}\")", first, last, value))
+        .collect::<Vec<_>>();
+    writeln!(
+        file,
+        "static {}: &[(u32, u32, &str)] = &[\n{}];\n",
+        name,
+        formatted.join(",\n")
+    )
+    .unwrap();
+}
+
+/// Add a range to a sorted list, merging it with the previous range if they
+/// are adjacent and have the same value.
+fn push_range(ranges: &mut Vec<(u32, u32, String)>, first: u32, last: u32, value: &str) {
+    match ranges.last_mut() {
+        Some(prev) if prev.1 + 1 == first && prev.2 == value => prev.1 = last,
+        _ => ranges.push((first, last, value.to_string())),
+    }
+}
+
+/// Parse a file in the format of Scripts.txt or Blocks.txt, with lines like
+/// `0041..005A; Latin`.
+///
+/// Underscores in values are replaced with spaces, so `Old_Italic` becomes
+/// `Old Italic`.
+fn parse_ranges(text: &str) -> Vec<(u32, u32, String)> {
+    let mut entries = text
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut split = line.split(';');
+            let range = split.next().unwrap().trim();
+            let value = split.next().unwrap().trim().replace('_', " ");
+            let mut bounds = range.split("..");
+            let first = u32::from_str_radix(bounds.next().unwrap(), 16).unwrap();
+            let last = bounds
+                .next()
+                .map(|last| u32::from_str_radix(last, 16).unwrap())
+                .unwrap_or(first);
+            (first, last, value)
+        })
+        .collect::<Vec<_>>();
+
+    entries.sort_by_key(|(first, _, _)| *first);
+    let mut ranges = Vec::new();
+    for (first, last, value) in entries {
+        push_range(&mut ranges, first, last, &value);
+    }
+    ranges
+}
+
+/// The parts of UnicodeData.txt that we use.
+struct UnicodeData {
+    /// The names of characters that are listed individually.
+    names: Vec<(char, String)>,
+    /// Ranges of characters whose name is a prefix followed by the
+    /// codepoint, such as `CJK UNIFIED IDEOGRAPH-4E00`.
+    name_ranges: Vec<(u32, u32, String)>,
+    categories: Vec<(u32, u32, String)>,
+}
+
+impl UnicodeData {
+    fn parse(text: &str) -> UnicodeData {
+        let mut data = UnicodeData {
+            names: Vec::new(),
+            name_ranges: Vec::new(),
+            categories: Vec::new(),
+        };
+        let mut range_start = None;
+        for line in text.lines().filter(|l| !l.is_empty()) {
+            let fields = line.split(';').collect::<Vec<_>>();
+            let cp = u32::from_str_radix(fields[0], 16).unwrap();
+            let (name, category) = (fields[1], fields[2]);
+
+            // large ranges are listed as a pair of lines, like
+            // `<CJK Ideograph, First>` and `<CJK Ideograph, Last>`
+            if name.ends_with(", First>") {
+                range_start = Some(cp);
+                continue;
+            }
+            if name.ends_with(", Last>") {
+                let first = range_start.take().unwrap();
+                push_range(&mut data.categories, first, cp, category);
+                let prefix = if name.starts_with("<CJK Ideograph") {
+                    Some("CJK UNIFIED IDEOGRAPH-")
+                } else if name.starts_with("<Tangut Ideograph") {
+                    Some("TANGUT IDEOGRAPH-")
+                } else {
+                    None
+                };
+                if let Some(prefix) = prefix {
+                    data.name_ranges.push((first, cp, prefix.to_string()));
+                }
+                continue;
+            }
+
+            push_range(&mut data.categories, cp, cp, category);
+            // control characters and the like have no name
+            if !name.starts_with('<') {
+                let chr = char::try_from(cp).unwrap();
+                data.names.push((chr, name.to_string()));
+            }
+        }
+        data
+    }
+}
+
 struct NameEntry {
     chr: char,
     postscript_name: String,
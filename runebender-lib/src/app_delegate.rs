@@ -1,89 +1,537 @@
 //! The `AppDelegate`.
 
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Arc;
 
 use druid::{
-    AppDelegate, Command, DelegateCtx, Env, Handled, Selector, Target, Widget, WindowDesc, WindowId,
+    AppDelegate, Command, DelegateCtx, Env, FileDialogOptions, Handled, Selector, Target, Widget,
+    WindowDesc, WindowId,
 };
 
-use druid::kurbo::Size;
+use druid::kurbo::{Affine, Size};
 use druid::lens::LensExt;
 use druid::widget::WidgetExt;
 use norad::{GlyphName, Ufo};
 
-use crate::consts;
-use crate::data::{AppState, Workspace};
+use crate::charset::parse_charset_file;
+use crate::construction::parse_recipes;
+use crate::consts::{self, cmd::UnsavedChangesChoice};
+use crate::data::{AppState, ModifiedCache, Notification, Workspace, WorkspaceId};
 use crate::edit_session::EditSession;
-use crate::widgets::{Editor, EditorController, RootWindowController, ScrollZoom};
+use crate::menus::{self, UFO_FILE_TYPE};
+use crate::preferences::Theme;
+use crate::report::FontReport;
+use crate::tools::ToolId;
+use crate::widgets::{
+    add_glyphs_panel, batch_edit_panel, component_picker, coverage_view, delete_glyph_prompt,
+    duplicate_glyphs_panel, font_window, preferences_window, qa_report, unsaved_changes_prompt,
+    Editor, EditorController, ModalHost, RootWindowController, ScrollZoom,
+};
 
 pub const EDIT_GLYPH: Selector<GlyphName> = Selector::new("runebender.open-editor-with-glyph");
 
+/// Save a font and then close, as the user chose in the unsaved changes
+/// prompt; this is sent again to retry if the save fails.
+const SAVE_AND_CLOSE: Selector<CloseAction> = Selector::new("runebender.save-and-close");
+
 #[derive(Debug, Default)]
-pub struct Delegate;
+pub struct Delegate {
+    /// A close that is waiting for the user to decide what to do about
+    /// unsaved changes.
+    pending_close: Option<PendingClose>,
+    /// A close that should happen once the font has been saved somewhere.
+    close_after_save: Option<PendingClose>,
+    /// Fonts whose unsaved changes the user has chosen to discard.
+    discarded: HashSet<WorkspaceId>,
+    preferences_window: Option<WindowId>,
+}
+
+/// Something that would discard unsaved changes.
+#[derive(Debug, Clone, Copy)]
+enum CloseAction {
+    Window(WindowId),
+    Quit,
+}
+
+/// A `CloseAction` waiting on the user's decision about one font.
+#[derive(Debug, Clone, Copy)]
+struct PendingClose {
+    workspace: WorkspaceId,
+    action: CloseAction,
+}
 
 impl AppDelegate<AppState> for Delegate {
     fn command(
         &mut self,
         ctx: &mut DelegateCtx,
-        _target: Target,
+        target: Target,
         cmd: &Command,
         data: &mut AppState,
         _env: &Env,
     ) -> Handled {
-        if let Some(info) = cmd.get(druid::commands::OPEN_FILE) {
-            match Ufo::load(info.path()) {
-                Ok(ufo) => data.workspace.set_file(ufo, info.path().to_owned()),
-                Err(e) => log::error!("failed to open file {:?}: '{:?}'", info.path(), e),
+        if cmd.is(druid::commands::CLOSE_WINDOW) || cmd.is(druid::commands::QUIT_APP) {
+            let action = match target {
+                _ if cmd.is(druid::commands::QUIT_APP) => CloseAction::Quit,
+                Target::Window(id) => CloseAction::Window(id),
+                _ => return Handled::No,
+            };
+            self.check_unsaved_changes(ctx, action, data)
+        } else if let Some(choice) = cmd.get(consts::cmd::UNSAVED_CHANGES_RESPONSE) {
+            let PendingClose { workspace, action } = match self.pending_close.take() {
+                Some(pending) => pending,
+                None => return Handled::Yes,
             };
-            ctx.submit_command(consts::cmd::REBUILD_MENUS);
+            let window = match data
+                .workspaces
+                .get(&workspace)
+                .and_then(|ws| ws.main_window)
+            {
+                Some(window) => window,
+                None => return Handled::Yes,
+            };
+            ctx.submit_command(ModalHost::DISMISS_MODAL.to(window));
+            match choice {
+                UnsavedChangesChoice::Save => self.save_and_close(ctx, data, workspace, action),
+                UnsavedChangesChoice::Discard => {
+                    self.discarded.insert(workspace);
+                    self.close(ctx, action);
+                }
+                UnsavedChangesChoice::Cancel => self.discarded.clear(),
+            }
+            Handled::Yes
+        } else if cmd.is(druid::commands::SAVE_PANEL_CANCELLED) {
+            // the user didn't choose where to save a font, so it stays open
+            self.close_after_save = None;
+            Handled::Yes
+        } else if let Some(notification) = cmd.get(consts::cmd::NOTIFY) {
+            let mut notification = notification.clone();
+            // notifications sent from a font's windows are about that font
+            if let (None, Target::Window(window)) = (notification.workspace, target) {
+                notification.workspace = data.workspace_for_window(window);
+            }
+            data.notify(notification);
+            Handled::Yes
+        } else if cmd.is(druid::commands::NEW_FILE) {
+            let mut workspace = Workspace::default();
+            workspace.set_file(crate::create_blank_font(), None);
+            let id = data.add_workspace(workspace);
+            ctx.new_window(make_font_window(data, id));
+            Handled::Yes
+        } else if let Some(template) = cmd.get(consts::cmd::NEW_FONT_FROM_TEMPLATE) {
+            let mut workspace = Workspace::default();
+            workspace.set_file(crate::util::create_font_from_template(template), None);
+            let id = data.add_workspace(workspace);
+            ctx.new_window(make_font_window(data, id));
+            Handled::Yes
+        } else if let Some(info) = cmd.get(druid::commands::OPEN_FILE) {
+            open_path(ctx, target, data, info.path());
+            Handled::Yes
+        } else if let Some(path) = cmd.get(consts::cmd::OPEN_RECENT_FILE) {
+            open_path(ctx, target, data, path);
+            Handled::Yes
+        } else if cmd.is(consts::cmd::CLEAR_RECENT_FILES) {
+            data.preferences.clear_recent_files();
+            save_preferences(data);
+            Handled::Yes
+        } else if cmd.is(druid::commands::SHOW_PREFERENCES) {
+            match self.preferences_window {
+                Some(window) => ctx.submit_command(druid::commands::SHOW_WINDOW.to(window)),
+                None => {
+                    let theme = data.preferences.theme;
+                    let desc = WindowDesc::new(move || {
+                        crate::theme::wrap_in_theme_loader(theme, preferences_window())
+                    })
+                    .title("Preferences")
+                    .window_size(Size::new(420.0, 640.0));
+                    self.preferences_window = Some(desc.id);
+                    ctx.new_window(desc);
+                }
+            }
+            Handled::Yes
+        } else {
+            // everything else applies to the font that owns the target window
+            match target {
+                Target::Window(window) => match data.workspace_for_window(window) {
+                    Some(id) => self.workspace_command(ctx, window, cmd, data, id),
+                    None => Handled::No,
+                },
+                _ => Handled::No,
+            }
+        }
+    }
+
+    /// The handler for window deletion events.
+    /// This function is called after a window has been removed.
+    fn window_removed(
+        &mut self,
+        id: WindowId,
+        data: &mut AppState,
+        _env: &Env,
+        ctx: &mut DelegateCtx,
+    ) {
+        if self.preferences_window == Some(id) {
+            self.preferences_window = None;
+            // in case the window closed before its changes were saved
+            save_preferences(data);
+            return;
+        }
+
+        let workspace_id = match data.workspace_for_window(id) {
+            Some(workspace_id) => workspace_id,
+            None => {
+                log::info!("window {:?} does not belong to a font", id);
+                return;
+            }
+        };
+
+        if data.workspaces[&workspace_id].main_window == Some(id) {
+            // the grid settings may not have been saved yet
+            save_preferences(data);
+        }
+
+        let workspace = data.workspace_mut(workspace_id).unwrap();
+        if workspace.main_window == Some(id) {
+            // the font is being closed; take its editors with it.
+            workspace.main_window = None;
+            for window in workspace.open_glyphs.values() {
+                ctx.submit_command(druid::commands::CLOSE_WINDOW.to(*window));
+            }
+        } else {
+            let to_remove = workspace
+                .open_glyphs
+                .iter()
+                .find(|(_k, v)| v == &&id)
+                .map(|(k, _v)| k.clone());
+            if let Some(open_glyph) = to_remove {
+                log::info!("removing '{}' from open list", open_glyph);
+                Arc::make_mut(&mut workspace.open_glyphs).remove(&open_glyph);
+            }
+        }
+
+        if workspace.main_window.is_none() && workspace.open_glyphs.is_empty() {
+            log::info!("closing font {:?}", workspace.font.path);
+            Arc::make_mut(&mut data.workspaces).remove(&workspace_id);
+            Arc::make_mut(&mut data.notifications).retain(|n| n.workspace != Some(workspace_id));
+            self.discarded.remove(&workspace_id);
+        }
+    }
+}
+
+impl Delegate {
+    /// Handle a command sent to one of the windows belonging to a font.
+    fn workspace_command(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        window: WindowId,
+        cmd: &Command,
+        data: &mut AppState,
+        workspace_id: WorkspaceId,
+    ) -> Handled {
+        let clipboard = data.glyph_clipboard.clone();
+        let prefs = data.preferences.clone();
+        let workspace = data.workspace_mut(workspace_id).unwrap();
+        let is_main_window = workspace.main_window == Some(window);
+
+        if cmd.is(druid::commands::SAVE_FILE) && workspace.font.path.is_none() {
+            // there's nowhere to save yet; let the user pick a location
+            show_save_panel(ctx, window);
             Handled::Yes
         } else if cmd.is(druid::commands::SAVE_FILE) {
-            if let Err(e) = data.workspace.save() {
-                log::error!("saving failed: '{}'", e);
+            if let Err(e) = workspace.save() {
+                data.notify(
+                    Notification::error("Saving failed")
+                        .with_details(e)
+                        .with_retry(druid::commands::SAVE_FILE.to(window))
+                        .for_workspace(workspace_id),
+                );
             }
             Handled::Yes
+        } else if let Some(action) = cmd.get(SAVE_AND_CLOSE) {
+            self.save_and_close(ctx, data, workspace_id, *action);
+            Handled::Yes
         } else if let Some(info) = cmd.get(druid::commands::SAVE_FILE_AS) {
-            Arc::make_mut(&mut data.workspace.font).path = Some(info.path().into());
-            ctx.submit_command(consts::cmd::REBUILD_MENUS);
-            if let Err(e) = data.workspace.save() {
-                log::error!("saving failed: '{}'", e);
+            match workspace.save_as(info.path()) {
+                Ok(()) => {
+                    ctx.submit_command(consts::cmd::REBUILD_MENUS.to(window));
+                    remember_recent_file(data, info.path());
+                    // we may have been waiting for a location before closing
+                    let pending = self.close_after_save.take();
+                    if let Some(PendingClose { workspace, action }) = pending {
+                        if workspace == workspace_id {
+                            self.close(ctx, action);
+                        }
+                    }
+                }
+                Err(e) => data.notify(
+                    Notification::error(format!("Failed to save to {}", info.path().display()))
+                        .with_details(e)
+                        .with_retry(druid::commands::SAVE_FILE_AS.with(info.clone()).to(window))
+                        .for_workspace(workspace_id),
+                ),
             }
             Handled::Yes
+        } else if cmd.is(druid::commands::COPY) && is_main_window {
+            let copied = workspace.copy_selected_glyphs();
+            data.glyph_clipboard = copied.map(Arc::new);
+            Handled::Yes
+        } else if cmd.is(druid::commands::PASTE) && is_main_window && clipboard.is_some() {
+            let pasted = workspace.paste_glyphs(&clipboard.unwrap());
+            workspace.select_glyphs(&pasted);
+            Handled::Yes
+        } else if cmd.is(druid::commands::UNDO) && is_main_window {
+            // editor windows have their own undo
+            workspace.undo_font_edit();
+            Handled::Yes
+        } else if cmd.is(druid::commands::REDO) && is_main_window {
+            workspace.redo_font_edit();
+            Handled::Yes
         } else if cmd.is(consts::cmd::NEW_GLYPH) {
-            let new_glyph_name = data.workspace.add_new_glyph();
-            data.workspace.selected = Some(new_glyph_name);
+            let new_glyph_name = workspace.add_new_glyph();
+            workspace.select_only(Some(new_glyph_name));
+            Handled::Yes
+        } else if cmd.is(consts::cmd::SHOW_DUPLICATE_GLYPHS) && is_main_window {
+            let count = workspace.selection.len();
+            if count > 0 {
+                let panel = ModalHost::make_modal_command(move || {
+                    duplicate_glyphs_panel(count).lens(AppState::workspace(workspace_id))
+                });
+                ctx.submit_command(panel.to(window));
+            }
+            Handled::Yes
+        } else if let Some(suffix) = cmd.get(consts::cmd::DUPLICATE_GLYPHS) {
+            let names = workspace.glyph_order();
+            let names: Vec<_> = names
+                .into_iter()
+                .filter(|name| workspace.selection.contains(name))
+                .collect();
+            let copies = workspace.duplicate_glyphs(&names, suffix);
+            if !copies.is_empty() {
+                workspace.select_glyphs(&copies);
+            }
+            Handled::Yes
+        } else if cmd.is(consts::cmd::SHOW_ADD_GLYPHS) && is_main_window {
+            let panel = ModalHost::make_modal_command(move || {
+                add_glyphs_panel().lens(AppState::workspace(workspace_id))
+            });
+            ctx.submit_command(panel.to(window));
+            Handled::Yes
+        } else if let Some(glyphs) = cmd.get(consts::cmd::ADD_GLYPHS) {
+            let added = workspace.add_glyphs(glyphs);
+            log::info!("added {} of {} glyphs", added.len(), glyphs.len());
+            if !added.is_empty() {
+                workspace.select_glyphs(&added);
+            }
+            Handled::Yes
+        } else if let Some(info) = cmd.get(consts::cmd::ADD_GLYPHS_FROM_FILE) {
+            let glyphs = std::fs::read_to_string(info.path())
+                .map_err(|e| e.to_string())
+                .and_then(|text| parse_charset_file(&text).map_err(|e| e.to_string()));
+            match glyphs {
+                Ok(glyphs) => ctx.submit_command(consts::cmd::ADD_GLYPHS.with(glyphs).to(window)),
+                Err(e) => data.notify(
+                    Notification::error(format!("Failed to read {}", info.path().display()))
+                        .with_details(e)
+                        .for_workspace(workspace_id),
+                ),
+            }
+            Handled::Yes
+        } else if cmd.is(consts::cmd::SELECT_ALL) && is_main_window {
+            // editor windows select points instead; this selects the glyphs
+            // shown in the grid.
+            let names = workspace.filtered_glyph_names();
+            workspace.select_glyphs(&names);
+            Handled::Yes
+        } else if cmd.is(consts::cmd::DESELECT_ALL) && is_main_window {
+            workspace.select_only(None);
             Handled::Yes
         } else if cmd.is(consts::cmd::DELETE_SELECTED_GLYPH) {
-            data.workspace.delete_selected_glyph();
+            let names = workspace.selected_glyphs();
+            if !names.is_empty() {
+                let dependents = workspace.glyphs_dependents(&names);
+                if dependents.is_empty() {
+                    ctx.submit_command(consts::cmd::DELETE_GLYPHS.with(names).to(window));
+                } else {
+                    let prompt = ModalHost::make_modal_command(move || {
+                        delete_glyph_prompt(names, dependents)
+                            .lens(AppState::workspace(workspace_id))
+                    });
+                    ctx.submit_command(prompt.to(window));
+                }
+            }
+            Handled::Yes
+        } else if let Some(names) = cmd.get(consts::cmd::DELETE_GLYPHS) {
+            // close the glyphs' editors, if they have them
+            for name in names {
+                if let Some(editor) = workspace.open_glyphs.get(name) {
+                    ctx.submit_command(druid::commands::CLOSE_WINDOW.to(*editor));
+                }
+            }
+            workspace.delete_glyphs(names);
+            Handled::Yes
+        } else if cmd.is(consts::cmd::DECOMPOSE_COMPONENTS) && is_main_window {
+            // editor windows handle this themselves
+            let selected = workspace.selected_glyphs();
+            let closed = send_to_editors(
+                ctx,
+                workspace,
+                &selected,
+                consts::cmd::DECOMPOSE_GLYPH.into(),
+            );
+            workspace.decompose_glyphs(&closed);
+            Handled::Yes
+        } else if (cmd.is(consts::cmd::FLIP_HORIZONTAL) || cmd.is(consts::cmd::FLIP_VERTICAL))
+            && is_main_window
+        {
+            let flip = if cmd.is(consts::cmd::FLIP_HORIZONTAL) {
+                Affine::FLIP_X
+            } else {
+                Affine::FLIP_Y
+            };
+            let selected = workspace.selected_glyphs();
+            let cmd = consts::cmd::TRANSFORM_GLYPH.with(flip);
+            let closed = send_to_editors(ctx, workspace, &selected, cmd);
+            workspace.transform_glyphs(&closed, flip);
+            Handled::Yes
+        } else if let Some(affine) = cmd.get(consts::cmd::TRANSFORM_GLYPHS) {
+            let selected = workspace.selected_glyphs();
+            let cmd = consts::cmd::TRANSFORM_GLYPH.with(*affine);
+            let closed = send_to_editors(ctx, workspace, &selected, cmd);
+            workspace.transform_glyphs(&closed, *affine);
+            Handled::Yes
+        } else if let Some(width) = cmd.get(consts::cmd::SET_ADVANCE_WIDTH) {
+            let selected = workspace.selected_glyphs();
+            let cmd = consts::cmd::SET_GLYPH_ADVANCE.with(*width);
+            let closed = send_to_editors(ctx, workspace, &selected, cmd);
+            workspace.set_advance_width(&closed, *width);
+            Handled::Yes
+        } else if let Some(color) = cmd.get(consts::cmd::SET_MARK_COLOR) {
+            let selected = workspace.selected_glyphs();
+            workspace.set_mark_color(&selected, color.as_ref());
+            Handled::Yes
+        } else if let Some(sort) = cmd.get(consts::cmd::SET_GRID_SORT) {
+            workspace.grid_sort = *sort;
+            Handled::Yes
+        } else if cmd.is(consts::cmd::OPEN_SELECTED_GLYPHS) {
+            for name in workspace.selected_glyphs() {
+                ctx.submit_command(EDIT_GLYPH.with(name).to(window));
+            }
+            Handled::Yes
+        } else if cmd.is(consts::cmd::EDIT_SELECTED_GLYPHS) {
+            let count = workspace.selection.len();
+            if count > 0 {
+                let panel = ModalHost::make_modal_command(move || {
+                    batch_edit_panel(count).lens(AppState::workspace(workspace_id))
+                });
+                ctx.submit_command(panel.to(window));
+            }
+            Handled::Yes
+        } else if cmd.is(consts::cmd::ADD_COMPONENT) {
+            let glyph = workspace
+                .open_glyphs
+                .iter()
+                .find(|(_, w)| **w == window)
+                .map(|(name, _)| name.clone());
+            if let Some(glyph) = glyph {
+                let picker = ModalHost::make_modal_command(move || {
+                    component_picker(glyph).lens(AppState::workspace(workspace_id))
+                });
+                ctx.submit_command(picker.to(window));
+            }
+            Handled::Yes
+        } else if cmd.is(consts::cmd::BUILD_STANDARD_COMPOSITES) {
+            let report = workspace.build_standard_composites();
+            data.notify_composites_built(workspace_id, &report);
+            Handled::Yes
+        } else if let Some(info) = cmd.get(consts::cmd::BUILD_COMPOSITES_FROM_FILE) {
+            let recipes = std::fs::read_to_string(info.path())
+                .map_err(|e| e.to_string())
+                .and_then(|text| parse_recipes(&text).map_err(|e| e.to_string()));
+            match recipes {
+                Ok(recipes) => {
+                    let report = workspace.build_composites(&recipes);
+                    data.notify_composites_built(workspace_id, &report);
+                }
+                Err(e) => data.notify(
+                    Notification::error(format!("Failed to read {}", info.path().display()))
+                        .with_details(e)
+                        .for_workspace(workspace_id),
+                ),
+            }
+            Handled::Yes
+        } else if cmd.is(consts::cmd::SHOW_QA_REPORT) {
+            let report = FontReport::for_workspace(workspace);
+            let title = format!("QA Report: {}", report.font);
+            let font_window = workspace.main_window;
+            let theme = prefs.theme;
+            let desc = WindowDesc::new(move || {
+                crate::theme::wrap_in_theme_loader(theme, qa_report(&report, font_window))
+            })
+            .title(title)
+            .window_size(Size::new(480.0, 640.0));
+            ctx.new_window(desc);
+            Handled::Yes
+        } else if cmd.is(consts::cmd::SHOW_COVERAGE) {
+            let title = format!(
+                "Unicode Coverage: {} {}",
+                workspace.info.family_name, workspace.info.style_name
+            );
+            let font_window = workspace.main_window;
+            let theme = prefs.theme;
+            let desc = WindowDesc::new(move || {
+                crate::theme::wrap_in_theme_loader(
+                    theme,
+                    coverage_view(font_window).lens(AppState::workspace(workspace_id)),
+                )
+            })
+            .title(title)
+            .window_size(Size::new(480.0, 640.0));
+            ctx.new_window(desc);
+            Handled::Yes
+        } else if let Some(chars) = cmd.get(consts::cmd::ADD_GLYPHS_FOR_CHARS) {
+            let added = workspace.add_glyphs_for_chars(chars);
+            if !added.is_empty() {
+                workspace.select_glyphs(&added);
+            }
             Handled::Yes
         } else if let Some(consts::cmd::RenameGlyphArgs { old, new }) =
             cmd.get(consts::cmd::RENAME_GLYPH)
         {
-            data.workspace.rename_glyph(old.clone(), new.clone());
+            workspace.rename_glyph(old.clone(), new.clone());
             Handled::Yes
         } else if let Some(payload) = cmd.get(EDIT_GLYPH) {
-            match data.workspace.open_glyphs.get(payload).to_owned() {
+            match workspace.open_glyphs.get(payload).to_owned() {
                 Some(id) => {
                     ctx.submit_command(druid::commands::SHOW_WINDOW.to(*id));
                 }
                 None => {
-                    let session = data.workspace.get_or_create_session(&payload);
+                    let session = workspace.get_or_create_session(payload);
                     let session_id = session.id;
-                    let new_win = WindowDesc::new(move || make_editor(&session))
-                        .title(move |d: &AppState, _: &_| {
-                            d.workspace
-                                .sessions
-                                .get(&session_id)
-                                .map(|s| s.name.to_string())
-                                .unwrap_or_else(|| "Unknown".to_string())
-                        })
-                        .window_size(Size::new(900.0, 800.0))
-                        .menu(crate::menus::make_menu(&data));
+                    let theme = prefs.theme;
+                    let tool = prefs.initial_tool();
+                    let new_win =
+                        WindowDesc::new(move || make_editor(workspace_id, &session, theme, tool))
+                            .title(move |d: &AppState, _: &_| {
+                                d.workspaces
+                                    .get(&workspace_id)
+                                    .and_then(|ws| ws.sessions.get(&session_id).map(|s| (ws, s)))
+                                    .map(|(ws, s)| {
+                                        if ws.glyph_is_modified(&s.name) {
+                                            format!("• {}", s.name)
+                                        } else {
+                                            s.name.to_string()
+                                        }
+                                    })
+                                    .unwrap_or_else(|| "Unknown".to_string())
+                            })
+                            .window_size(prefs.window_size())
+                            .menu(menus::make_menu(workspace, &prefs));
 
                     let id = new_win.id;
                     ctx.new_window(new_win);
 
-                    Arc::make_mut(&mut data.workspace.open_glyphs).insert(payload.clone(), id);
+                    Arc::make_mut(&mut workspace.open_glyphs).insert(payload.clone(), id);
                 }
             }
             Handled::Yes
@@ -92,35 +540,220 @@ impl AppDelegate<AppState> for Delegate {
         }
     }
 
-    /// The handler for window deletion events.
-    /// This function is called after a window has been removed.
-    fn window_removed(
+    /// Called before a window is closed or the app quits; if there are unsaved
+    /// changes that would be lost, ask the user what to do first.
+    fn check_unsaved_changes(
         &mut self,
-        id: WindowId,
+        ctx: &mut DelegateCtx,
+        action: CloseAction,
+        data: &AppState,
+    ) -> Handled {
+        let affected: Vec<WorkspaceId> = match action {
+            // editor windows don't own their sessions; closing them loses nothing.
+            CloseAction::Window(window) => match data.workspace_for_window(window) {
+                Some(id) if data.workspaces[&id].main_window == Some(window) => vec![id],
+                _ => return Handled::No,
+            },
+            CloseAction::Quit => data.workspaces.keys().copied().collect(),
+        };
+
+        // when quitting, we ask about each modified font in turn.
+        let workspace = match affected
+            .into_iter()
+            .find(|id| !self.discarded.contains(id) && data.workspaces[id].is_modified())
+        {
+            Some(id) => id,
+            None => return Handled::No,
+        };
+
+        if self.pending_close.is_none() {
+            if let Some(window) = data.workspaces[&workspace].main_window {
+                let show_prompt = ModalHost::make_modal_command(move || {
+                    unsaved_changes_prompt().lens(AppState::workspace(workspace))
+                });
+                ctx.submit_command(druid::commands::SHOW_WINDOW.to(window));
+                ctx.submit_command(show_prompt.to(window));
+            }
+        }
+        self.pending_close = Some(PendingClose { workspace, action });
+        Handled::Yes
+    }
+
+    /// Save a font, then carry out the close that was waiting on it.
+    ///
+    /// If the save fails, retrying it will also close afterwards.
+    fn save_and_close(
+        &mut self,
+        ctx: &mut DelegateCtx,
         data: &mut AppState,
-        _env: &Env,
-        _ctx: &mut DelegateCtx,
+        workspace: WorkspaceId,
+        action: CloseAction,
     ) {
-        let to_remove = data
-            .workspace
-            .open_glyphs
-            .iter()
-            .find(|(_k, v)| v == &&id)
-            .map(|(k, _v)| k.clone());
-        match to_remove {
-            Some(open_glyph) => {
-                log::info!("removing '{}' from open list", open_glyph);
-                Arc::make_mut(&mut data.workspace.open_glyphs).remove(&open_glyph);
+        let ws = match data.workspace_mut(workspace) {
+            Some(ws) => ws,
+            None => return,
+        };
+        let window = ws.main_window;
+        if let (None, Some(window)) = (&ws.font.path, window) {
+            // there's nowhere to save yet; let the user pick a location and
+            // close again afterwards.
+            self.close_after_save = Some(PendingClose { workspace, action });
+            show_save_panel(ctx, window);
+            return;
+        }
+        match ws.save() {
+            Ok(()) => self.close(ctx, action),
+            Err(e) => {
+                let mut notification = Notification::error("Saving failed")
+                    .with_details(e)
+                    .for_workspace(workspace);
+                if let Some(window) = window {
+                    notification = notification.with_retry(SAVE_AND_CLOSE.with(action).to(window));
+                }
+                data.notify(notification);
+            }
+        }
+    }
+
+    fn close(&mut self, ctx: &mut DelegateCtx, action: CloseAction) {
+        match action {
+            CloseAction::Window(id) => ctx.submit_command(druid::commands::CLOSE_WINDOW.to(id)),
+            CloseAction::Quit => ctx.submit_command(druid::commands::QUIT_APP),
+        }
+    }
+}
+
+/// Create the main window for the font with the given id.
+pub fn make_font_window(data: &mut AppState, id: WorkspaceId) -> WindowDesc<AppState> {
+    let theme = data.preferences.theme;
+    let modified_cache = RefCell::new(ModifiedCache::default());
+    let desc = WindowDesc::new(move || font_window(id, theme))
+        .title(move |d: &AppState, _: &Env| {
+            d.workspaces
+                .get(&id)
+                .map(|ws| {
+                    let is_modified = ws.is_modified_cached(&mut modified_cache.borrow_mut());
+                    let modified = if is_modified { "• " } else { "" };
+                    format!("{}{} {}", modified, ws.info.family_name, ws.info.style_name)
+                })
+                .unwrap_or_default()
+        })
+        .menu(menus::make_menu(&data.workspaces[&id], &data.preferences))
+        .window_size(data.preferences.window_size());
+
+    if let Some(workspace) = data.workspace_mut(id) {
+        workspace.main_window = Some(desc.id);
+    }
+    desc
+}
+
+/// Open the font at `path`, or bring it to the front if it is already open.
+fn open_path(ctx: &mut DelegateCtx, target: Target, data: &mut AppState, path: &Path) {
+    let already_open = data
+        .workspace_for_path(path)
+        .and_then(|id| data.workspaces[&id].main_window);
+    if let Some(window) = already_open {
+        ctx.submit_command(druid::commands::SHOW_WINDOW.to(window));
+        return;
+    }
+    match Ufo::load(path) {
+        Ok(ufo) => {
+            open_font(ctx, target, data, ufo, path);
+            remember_recent_file(data, path);
+        }
+        Err(e) => data.notify(
+            Notification::error(format!("Failed to open {}", path.display()))
+                .with_details(format!("{:?}", e))
+                .with_retry(consts::cmd::OPEN_RECENT_FILE.with(path.to_owned())),
+        ),
+    };
+}
+
+/// Add a font to the recent files list.
+/// Ask the user where to save the font shown in `window`.
+fn show_save_panel(ctx: &mut DelegateCtx, window: WindowId) {
+    ctx.submit_command(
+        druid::commands::SHOW_SAVE_PANEL
+            .with(FileDialogOptions::new().allowed_types(vec![UFO_FILE_TYPE]))
+            .to(window),
+    );
+}
+
+/// Send `cmd` to the editor window of each of `names` that is open, returning
+/// the names of the glyphs that are not.
+///
+/// Editing open glyphs in their editor means the edit can be undone there.
+fn send_to_editors(
+    ctx: &mut DelegateCtx,
+    workspace: &Workspace,
+    names: &[GlyphName],
+    cmd: Command,
+) -> Vec<GlyphName> {
+    let mut closed = Vec::new();
+    for name in names {
+        match workspace.open_glyphs.get(name) {
+            Some(window) => ctx.submit_command(cmd.clone().to(*window)),
+            None => closed.push(name.clone()),
+        }
+    }
+    closed
+}
+
+fn remember_recent_file(data: &mut AppState, path: &Path) {
+    data.preferences.add_recent_file(path);
+    save_preferences(data);
+}
+
+fn save_preferences(data: &mut AppState) {
+    if let Err(e) = data.preferences.save() {
+        data.notify(Notification::error("Failed to save preferences").with_details(e));
+    }
+}
+
+/// Show a newly loaded font.
+///
+/// If the font was opened from the window of an empty, untitled font, it
+/// replaces that font; otherwise it gets a window of its own.
+fn open_font(ctx: &mut DelegateCtx, target: Target, data: &mut AppState, ufo: Ufo, path: &Path) {
+    let replaceable = match target {
+        Target::Window(window) => data.workspace_for_window(window).filter(|id| {
+            let ws = &data.workspaces[id];
+            ws.font.path.is_none() && !ws.is_modified() && ws.open_glyphs.is_empty()
+        }),
+        _ => None,
+    };
+
+    match replaceable {
+        Some(id) => {
+            let workspace = data.workspace_mut(id).unwrap();
+            workspace.set_file(ufo, path.to_owned());
+            if let Some(window) = workspace.main_window {
+                ctx.submit_command(consts::cmd::REBUILD_MENUS.to(window));
             }
-            None => log::info!("window {:?} is not an editor window", id),
+            data.warn_about_broken_components(id);
+        }
+        None => {
+            let mut workspace = Workspace::default();
+            workspace.set_file(ufo, path.to_owned());
+            let id = data.add_workspace(workspace);
+            data.warn_about_broken_components(id);
+            ctx.new_window(make_font_window(data, id));
         }
     }
 }
 
-fn make_editor(session: &Arc<EditSession>) -> impl Widget<AppState> {
+fn make_editor(
+    workspace: WorkspaceId,
+    session: &Arc<EditSession>,
+    theme: Theme,
+    tool: ToolId,
+) -> impl Widget<AppState> {
     crate::theme::wrap_in_theme_loader(
-        EditorController::new(ScrollZoom::new(Editor::new(session.clone())))
-            .lens(AppState::workspace.then(Workspace::editor_state(session.id)))
-            .controller(RootWindowController::default()),
+        theme,
+        ModalHost::new(
+            EditorController::new(ScrollZoom::new(Editor::new(session.clone())), tool)
+                .lens(AppState::workspace(workspace).then(Workspace::editor_state(session.id))),
+        )
+        .controller(RootWindowController::new(workspace)),
     )
 }
@@ -10,6 +10,8 @@ use druid::kurbo::{Affine, BezPath};
 use druid::Data;
 use norad::{Glyph, GlyphName, Ufo};
 
+use crate::component::broken_component_placeholder;
+
 const PRE_CACHE_SIZE: usize = 8;
 
 /// A cache of up to date paths for each glyph.
@@ -146,6 +148,10 @@ impl BezCache {
     where
         F: Fn(&GlyphName) -> Option<&'a Arc<Glyph>> + 'a,
     {
+        // the glyph's components may have changed since we last saw it
+        if let Some(glyph) = glyph_getter(name) {
+            self.components.update(glyph);
+        }
         self.invalidate(name);
         self.rebuild_without_inval(name, glyph_getter)
     }
@@ -155,28 +161,55 @@ impl BezCache {
         name: &GlyphName,
         glyph_getter: &'a F,
     ) -> Option<Arc<BezPath>>
+    where
+        F: Fn(&GlyphName) -> Option<&'a Arc<Glyph>> + 'a,
+    {
+        self.rebuild_resolving(name, glyph_getter, &mut Vec::new())
+    }
+
+    /// Build the bezier for a glyph and its components.
+    ///
+    /// `resolving` is the chain of glyphs whose components we are currently
+    /// building; a component that refers back into this chain is a cycle.
+    /// Cycles and missing bases are drawn as a placeholder.
+    fn rebuild_resolving<'a, F>(
+        &mut self,
+        name: &GlyphName,
+        glyph_getter: &'a F,
+        resolving: &mut Vec<GlyphName>,
+    ) -> Option<Arc<BezPath>>
     where
         F: Fn(&GlyphName) -> Option<&'a Arc<Glyph>> + 'a,
     {
         let glyph = glyph_getter(name)?;
         let mut path = crate::data::path_for_glyph(glyph)?;
 
+        resolving.push(name.clone());
         for comp in glyph
             .outline
             .as_ref()
             .iter()
             .flat_map(|o| o.components.iter())
         {
-            match self.rebuild_without_inval(&comp.base, glyph_getter) {
-                Some(component) => {
-                    let affine: Affine = comp.transform.clone().into();
-                    for comp_elem in (affine * &*component).elements() {
-                        path.push(*comp_elem);
-                    }
-                }
-                None => log::warn!("missing component {} in glyph {}", comp.base, glyph.name),
+            let affine: Affine = comp.transform.into();
+            let component = if resolving.contains(&comp.base) {
+                log::warn!("component cycle: {} in glyph {}", comp.base, glyph.name);
+                None
+            } else if glyph_getter(&comp.base).is_none() {
+                log::warn!("missing component {} in glyph {}", comp.base, glyph.name);
+                None
+            } else {
+                // a base with no outline is fine; it just draws nothing
+                let resolved = self.rebuild_resolving(&comp.base, glyph_getter, resolving);
+                Some(resolved.unwrap_or_default())
+            };
+            let component = component.unwrap_or_else(|| Arc::new(broken_component_placeholder()));
+            for comp_elem in (affine * &*component).elements() {
+                path.push(*comp_elem);
             }
         }
+        resolving.pop();
+
         let path = Arc::new(path);
         self.set(name.clone(), path.clone());
         Some(path)
@@ -214,21 +247,42 @@ impl ComponentMap {
         }
     }
 
+    /// Record the current components of `glyph`.
+    fn update(&mut self, glyph: &Glyph) {
+        let inner = Arc::make_mut(&mut self.inner);
+        for users in inner.values_mut() {
+            users.retain(|user| *user != glyph.name);
+        }
+        for component in glyph.outline.iter().flat_map(|o| o.components.iter()) {
+            let users = inner.entry(component.base.clone()).or_default();
+            if !users.contains(&glyph.name) {
+                users.push(glyph.name.clone());
+            }
+        }
+    }
+
+    /// All of the glyphs that use `name`, directly or indirectly.
     fn glyphs_containing_component<'a>(&'a self, name: &GlyphName) -> Cow<'a, [GlyphName]> {
-        if let Some(glyphs) = self.inner.get(name) {
-            let mut component_children = glyphs
-                .iter()
-                .flat_map(|g| self.inner.get(g))
-                .flat_map(|g| g.iter().cloned())
-                .collect::<Vec<_>>();
-            if component_children.is_empty() {
-                Cow::Borrowed(glyphs.as_slice())
-            } else {
-                component_children.extend_from_slice(glyphs);
-                Cow::Owned(component_children)
+        let direct = match self.inner.get(name) {
+            Some(glyphs) => glyphs,
+            None => return Cow::Owned(Vec::new()),
+        };
+        if direct.iter().all(|g| !self.inner.contains_key(g)) {
+            return Cow::Borrowed(direct.as_slice());
+        }
+
+        // this terminates even if the components form a cycle
+        let mut result: Vec<GlyphName> = Vec::new();
+        let mut to_visit = direct.clone();
+        while let Some(glyph) = to_visit.pop() {
+            if result.contains(&glyph) {
+                continue;
+            }
+            if let Some(users) = self.inner.get(&glyph) {
+                to_visit.extend(users.iter().cloned());
             }
-        } else {
-            Cow::Owned(Vec::new())
+            result.push(glyph);
         }
+        Cow::Owned(result)
     }
 }
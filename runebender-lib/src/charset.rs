@@ -0,0 +1,232 @@
+//! Lists of glyphs to add to a font, typed in or read from character set
+//! and encoding files.
+//!
+//! A character set file names one glyph per line, with its first word:
+//!
+//! ```text
+//! # a comment
+//! A
+//! 0x00E9 LATIN SMALL LETTER E WITH ACUTE
+//! U+0431
+//! a.sc
+//! ```
+//!
+//! This covers plain lists of glyph names, FontLab `.enc` files (where the
+//! rest of the line is an index) and `.nam` files (where it is the
+//! character's name).
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use druid::Data;
+use norad::glyph::GlyphName;
+
+use crate::glyph_names::{codepoints_for_glyph, default_glyph_name, validate_and_standardize_name};
+
+/// A glyph to add to a font.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphSpec {
+    pub name: GlyphName,
+    pub codepoints: Vec<char>,
+}
+
+/// How to read a typed list of glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum ListKind {
+    /// Every character is a glyph, as in `abcäöü`.
+    Characters,
+    /// Words are glyph names or codepoints, as in `a b uni0431 U+00E9`.
+    GlyphNames,
+}
+
+/// A set of glyphs a new font can start with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Template {
+    pub name: &'static str,
+    ranges: &'static [(char, char)],
+}
+
+/// The templates for new fonts; the first is the default.
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "Latin Letters",
+        ranges: &[('a', 'z'), ('A', 'Z')],
+    },
+    Template {
+        name: "Basic Latin",
+        ranges: &[(' ', '~')],
+    },
+    Template {
+        name: "Latin-1",
+        ranges: &[(' ', '~'), ('\u{A0}', 'ÿ')],
+    },
+    Template {
+        name: "Latin Extended-A",
+        ranges: &[(' ', '~'), ('\u{A0}', 'ÿ'), ('Ā', 'ſ')],
+    },
+];
+
+/// An entry in a glyph list that could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The line number, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl GlyphSpec {
+    /// An encoded glyph for `chr`, with its default name.
+    pub fn for_char(chr: char) -> GlyphSpec {
+        GlyphSpec {
+            name: default_glyph_name(chr).into(),
+            codepoints: vec![chr],
+        }
+    }
+}
+
+impl Template {
+    /// The glyphs in this template, in order.
+    pub fn glyphs(&self) -> Vec<GlyphSpec> {
+        self.ranges
+            .iter()
+            .flat_map(|(first, last)| *first..=*last)
+            .map(GlyphSpec::for_char)
+            .collect()
+    }
+}
+
+/// Parse a typed list of glyphs.
+///
+/// Characters may be separated by whitespace; glyph names may be separated
+/// by whitespace or commas.
+pub fn parse_list(text: &str, kind: ListKind) -> Result<Vec<GlyphSpec>, ParseError> {
+    match kind {
+        ListKind::Characters => Ok(text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(GlyphSpec::for_char)
+            .collect()),
+        ListKind::GlyphNames => text
+            .lines()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                line.split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|word| !word.is_empty())
+                    .map(move |word| {
+                        parse_entry(word).map_err(|message| ParseError {
+                            line: i + 1,
+                            message,
+                        })
+                    })
+            })
+            .collect(),
+    }
+}
+
+/// Parse a character set or encoding file, which names one glyph per line.
+///
+/// Blank lines, and lines starting with `#` or `%`, are ignored.
+pub fn parse_charset_file(text: &str) -> Result<Vec<GlyphSpec>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !(line.is_empty() || line.starts_with('#') || line.starts_with('%')))
+        .map(|(line, text)| {
+            let word = text.split_whitespace().next().unwrap_or_default();
+            parse_entry(word).map_err(|message| ParseError { line, message })
+        })
+        .collect()
+}
+
+/// Parse a single glyph: a codepoint like `U+00E9` or `0x00E9`, a single
+/// character, or a glyph name.
+fn parse_entry(word: &str) -> Result<GlyphSpec, String> {
+    let hex = ["U+", "u+", "0x", "0X"]
+        .iter()
+        .find_map(|prefix| word.strip_prefix(prefix));
+    if let Some(hex) = hex {
+        return u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(|cp| char::try_from(cp).ok())
+            .map(GlyphSpec::for_char)
+            .ok_or_else(|| format!("'{}' is not a valid codepoint", word));
+    }
+
+    let mut chars = word.chars();
+    if let (Some(chr), None) = (chars.next(), chars.next()) {
+        return Ok(GlyphSpec::for_char(chr));
+    }
+
+    let name = validate_and_standardize_name(word).map_err(|e| format!("'{}': {}", word, e))?;
+    Ok(GlyphSpec {
+        codepoints: codepoints_for_glyph(&name).unwrap_or_default(),
+        name: name.into(),
+    })
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(glyphs: &[GlyphSpec]) -> Vec<&str> {
+        glyphs.iter().map(|g| &*g.name).collect()
+    }
+
+    #[test]
+    fn typed_lists() {
+        let glyphs = parse_list("aé б", ListKind::Characters).unwrap();
+        assert_eq!(names(&glyphs), vec!["a", "eacute", "uni0431"]);
+        assert_eq!(glyphs[2].codepoints, vec!['б']);
+
+        let glyphs = parse_list("a, a.sc uni0431\nU+00E9 f_i", ListKind::GlyphNames).unwrap();
+        assert_eq!(
+            names(&glyphs),
+            vec!["a", "a.sc", "uni0431", "eacute", "f_i"]
+        );
+        assert_eq!(glyphs[0].codepoints, vec!['a']);
+        assert!(glyphs[1].codepoints.is_empty());
+        assert_eq!(glyphs[2].codepoints, vec!['б']);
+        assert!(glyphs[4].codepoints.is_empty());
+
+        let err = parse_list("a\nb c-d", ListKind::GlyphNames).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(parse_list("U+D800", ListKind::GlyphNames).is_err());
+    }
+
+    #[test]
+    fn charset_files() {
+        let enc = "%%FONTLAB ENCODING: 1; Test\nA 65\n\nuni0431 66\n";
+        let glyphs = parse_charset_file(enc).unwrap();
+        assert_eq!(names(&glyphs), vec!["A", "uni0431"]);
+
+        let nam = "# a namelist
+0x0041 LATIN CAPITAL LETTER A
+0x00E9 LATIN SMALL LETTER E WITH ACUTE
+";
+        let glyphs = parse_charset_file(nam).unwrap();
+        assert_eq!(names(&glyphs), vec!["A", "eacute"]);
+        assert_eq!(glyphs[1].codepoints, vec!['é']);
+
+        assert_eq!(parse_charset_file("A\na-b 12\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn templates() {
+        let letters = TEMPLATES[0].glyphs();
+        assert_eq!(letters.len(), 52);
+        assert_eq!(letters[0], GlyphSpec::for_char('a'));
+        let latin1 = TEMPLATES.iter().find(|t| t.name == "Latin-1").unwrap();
+        let glyphs = latin1.glyphs();
+        assert_eq!(glyphs.len(), 95 + 96);
+        assert!(names(&glyphs).contains(&"space"));
+        assert!(names(&glyphs).contains(&"ydieresis"));
+    }
+}
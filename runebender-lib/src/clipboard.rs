@@ -107,30 +107,21 @@ pub fn make_glyphs_plist(session: &EditSession) -> Option<Vec<u8>> {
     Some(data)
 }
 
-pub fn from_glyphs_plist(data: Vec<u8>) -> Option<Vec<Path>> {
+pub fn from_glyphs_plist(data: Vec<u8>) -> Result<Vec<Path>, String> {
     let cursor = std::io::Cursor::new(data);
     match plist::from_reader(cursor) {
-        Ok(GlyphsPastePlist { paths, .. }) => Some(paths.iter().map(Path::from).collect()),
-        Err(e) => {
-            log::warn!("failed to parse glyphs plist: '{}'", e);
-            None
-        }
+        Ok(GlyphsPastePlist { paths, .. }) => Ok(paths.iter().map(Path::from).collect()),
+        Err(e) => Err(format!("failed to parse glyphs plist: '{}'", e)),
     }
 }
 
-pub fn from_glyphs_plist_string(text: String) -> Option<Vec<Path>> {
+pub fn from_glyphs_plist_string(text: String) -> Result<Vec<Path>, String> {
     let plist = match Plist::parse(&text) {
         Ok(Plist::Dictionary(d)) => d,
-        Ok(other) => {
-            log::warn!("unexpected plist value {:?}", other);
-            return None;
-        }
-        Err(e) => {
-            log::warn!("failed to parse string plist: '{:?}'", e);
-            return None;
-        }
+        Ok(other) => return Err(format!("unexpected plist value {:?}", other)),
+        Err(e) => return Err(format!("failed to parse string plist: '{:?}'", e)),
     };
-    paths_from_plist_dict(plist)
+    paths_from_plist_dict(plist).ok_or_else(|| "glyphs plist has no paths".to_string())
 }
 
 fn paths_from_plist_dict(dict: HashMap<String, Plist>) -> Option<Vec<Path>> {
@@ -195,23 +186,19 @@ pub fn make_pdf_data(session: &EditSession) -> Option<Vec<u8>> {
     }
 }
 
-pub fn from_pdf_data(data: Vec<u8>) -> Option<Vec<Path>> {
+pub fn from_pdf_data(data: Vec<u8>) -> Result<Vec<Path>, String> {
     match Document::load_mem(&data) {
         Ok(doc) => {
             if doc.get_pages().len() > 1 {
                 log::warn!("pasted pdf has multiple pages, we will only look at the first.");
             }
-            let page = doc.page_iter().next()?;
+            let page = doc.page_iter().next().ok_or("pasted pdf has no pages")?;
             let content = doc
                 .get_and_decode_page_content(page)
-                .map_err(|e| log::warn!("failed to decode pdf content: '{}'", e))
-                .ok()?;
-            Some(paths_for_pdf_contents(content))
-        }
-        Err(e) => {
-            log::warn!("failed to load pdf data: '{}'", e);
-            None
+                .map_err(|e| format!("failed to decode pdf content: '{}'", e))?;
+            Ok(paths_for_pdf_contents(content))
         }
+        Err(e) => Err(format!("failed to load pdf data: '{}'", e)),
     }
 }
 
@@ -1,10 +1,14 @@
 //! A glyph embedded in another glyph.
 
-use druid::kurbo::Affine;
-use druid::Data;
-use norad::GlyphName;
+use std::collections::HashSet;
 
-use crate::design_space::DVec2;
+use druid::kurbo::{Affine, BezPath, Point, Rect, Shape, Vec2};
+use druid::{Data, Lens};
+use norad::glyph::{Contour, ContourPoint};
+use norad::{Glyph, GlyphName};
+
+use crate::design_space::{DPoint, DVec2};
+use crate::path::Path;
 use crate::point::EntityId;
 
 #[derive(Debug, Data, Clone)]
@@ -13,6 +17,51 @@ pub struct Component {
     #[data(same_fn = "affine_eq")]
     pub transform: Affine,
     pub id: EntityId,
+    /// The bounding box of the base glyph, before the transform is applied.
+    ///
+    /// This is kept up to date by the editor, and is used for hit testing
+    /// and selection.
+    pub base_bounds: Rect,
+}
+
+/// The transform of a component, broken down into the values we show in
+/// the coordinate panel.
+///
+/// Skew is not represented, and is lost if any of these values are edited.
+#[derive(Debug, Clone, Copy, Data, Lens, PartialEq)]
+pub struct ComponentTransform {
+    pub offset_x: f64,
+    pub offset_y: f64,
+    /// The horizontal scale, as a percentage.
+    pub scale_x: f64,
+    /// The vertical scale, as a percentage; negative if the component
+    /// is flipped.
+    pub scale_y: f64,
+    /// The rotation, in degrees counter-clockwise.
+    pub rotation: f64,
+}
+
+/// The size of the placeholder drawn in place of a broken component.
+const PLACEHOLDER_SIZE: f64 = 400.0;
+const PLACEHOLDER_STROKE: f64 = 40.0;
+
+/// Why a component cannot be drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentProblem {
+    /// The base glyph does not exist.
+    MissingBase,
+    /// The base glyph contains, directly or indirectly, the glyph that
+    /// uses it.
+    Cycle,
+}
+
+/// A component that cannot be drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenComponent {
+    /// The glyph containing the component.
+    pub glyph: GlyphName,
+    pub base: GlyphName,
+    pub problem: ComponentProblem,
 }
 
 fn affine_eq(left: &Affine, right: &Affine) -> bool {
@@ -20,14 +69,24 @@ fn affine_eq(left: &Affine, right: &Affine) -> bool {
 }
 
 impl Component {
+    pub fn new(base: GlyphName) -> Self {
+        Component {
+            base,
+            transform: Affine::default(),
+            id: EntityId::new_for_component(),
+            base_bounds: Rect::ZERO,
+        }
+    }
+
     pub fn from_norad(src: &norad::glyph::Component) -> Self {
         let base = src.base.clone();
         let transform = src.transform.into();
-        let id = EntityId::next();
+        let id = EntityId::new_for_component();
         Component {
             base,
             transform,
             id,
+            base_bounds: Rect::ZERO,
         }
     }
 
@@ -41,4 +100,443 @@ impl Component {
         let [a, b, c, d, t_x, t_y] = self.transform.as_coeffs();
         self.transform = Affine::new([a, b, c, d, t_x + delta.x, t_y + delta.y]);
     }
+
+    /// Apply `affine` to this component, treating `anchor` as the origin.
+    pub(crate) fn transform_around(&mut self, affine: Affine, anchor: DPoint) {
+        let anchor = anchor.to_raw().to_vec2();
+        self.transform =
+            Affine::translate(anchor) * affine * Affine::translate(-anchor) * self.transform;
+    }
+
+    /// The position of the base glyph's origin.
+    pub fn offset(&self) -> Point {
+        let [_, _, _, _, t_x, t_y] = self.transform.as_coeffs();
+        Point::new(t_x, t_y)
+    }
+
+    /// The bounding box of the transformed component, in design space.
+    pub fn bounds(&self) -> Rect {
+        let rect = self.base_bounds;
+        let corners = [
+            Point::new(rect.x0, rect.y0),
+            Point::new(rect.x1, rect.y0),
+            Point::new(rect.x0, rect.y1),
+            Point::new(rect.x1, rect.y1),
+        ];
+        let first = self.transform * corners[0];
+        corners[1..]
+            .iter()
+            .fold(Rect::from_points(first, first), |bbox, pt| {
+                bbox.union_pt(self.transform * *pt)
+            })
+    }
+
+    pub fn decomposed_transform(&self) -> ComponentTransform {
+        let [a, b, c, d, t_x, t_y] = self.transform.as_coeffs();
+        let scale_x = a.hypot(b);
+        let (scale_y, rotation) = if scale_x == 0.0 {
+            (d, 0.0)
+        } else {
+            ((a * d - b * c) / scale_x, b.atan2(a))
+        };
+        ComponentTransform {
+            offset_x: t_x,
+            offset_y: t_y,
+            scale_x: scale_x * 100.0,
+            scale_y: scale_y * 100.0,
+            rotation: rotation.to_degrees(),
+        }
+    }
+
+    /// Replace this component's transform with one built from `parts`.
+    pub(crate) fn set_decomposed_transform(&mut self, parts: &ComponentTransform) {
+        let scale = Vec2::new(parts.scale_x, parts.scale_y) / 100.0;
+        self.transform = Affine::translate((parts.offset_x, parts.offset_y))
+            * Affine::rotate(parts.rotation.to_radians())
+            * Affine::scale_non_uniform(scale.x, scale.y);
+    }
+}
+
+impl Default for ComponentTransform {
+    fn default() -> Self {
+        ComponentTransform {
+            offset_x: 0.0,
+            offset_y: 0.0,
+            scale_x: 100.0,
+            scale_y: 100.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl ComponentProblem {
+    pub fn description(self) -> &'static str {
+        match self {
+            ComponentProblem::MissingBase => "base glyph does not exist",
+            ComponentProblem::Cycle => "base glyph contains this glyph",
+        }
+    }
+}
+
+impl std::fmt::Display for BrokenComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' in '{}': {}",
+            self.base,
+            self.glyph,
+            self.problem.description()
+        )
+    }
+}
+
+/// Check whether `base` can be used as a component of `glyph`.
+///
+/// `get_glyph` should return the current version of a glyph.
+pub(crate) fn check_component<'a>(
+    glyph: &GlyphName,
+    base: &GlyphName,
+    get_glyph: impl Fn(&GlyphName) -> Option<&'a Glyph>,
+) -> Option<ComponentProblem> {
+    if get_glyph(base).is_none() {
+        return Some(ComponentProblem::MissingBase);
+    }
+
+    // walk everything `base` contains, looking for `glyph`.
+    let mut to_visit = vec![base.clone()];
+    let mut visited = HashSet::new();
+    while let Some(name) = to_visit.pop() {
+        if name == *glyph {
+            return Some(ComponentProblem::Cycle);
+        }
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+        if let Some(outline) = get_glyph(&name).and_then(|g| g.outline.as_ref()) {
+            to_visit.extend(outline.components.iter().map(|c| c.base.clone()));
+        }
+    }
+    None
+}
+
+/// The outlines of `base`, including those of its own components, as paths
+/// with `transform` applied.
+///
+/// `get_glyph` should return the current version of a glyph. Nested
+/// components that are missing or that would form a cycle are skipped.
+pub(crate) fn decomposed_paths<'a>(
+    base: &GlyphName,
+    transform: Affine,
+    get_glyph: &dyn Fn(&GlyphName) -> Option<&'a Glyph>,
+) -> Vec<Path> {
+    let mut paths = Vec::new();
+    decompose_into(base, transform, get_glyph, &mut Vec::new(), &mut paths);
+    paths
+}
+
+fn decompose_into<'a>(
+    base: &GlyphName,
+    transform: Affine,
+    get_glyph: &dyn Fn(&GlyphName) -> Option<&'a Glyph>,
+    resolving: &mut Vec<GlyphName>,
+    paths: &mut Vec<Path>,
+) {
+    if resolving.contains(base) {
+        return;
+    }
+    let outline = match get_glyph(base).and_then(|g| g.outline.as_ref()) {
+        Some(outline) => outline,
+        None => return,
+    };
+
+    resolving.push(base.clone());
+    for contour in outline.contours.iter().filter(|c| !c.points.is_empty()) {
+        let mut path = Path::from_norad(&transform_contour(contour, transform));
+        // a flipped contour winds the other way, so we reverse it back
+        if transform.determinant() < 0.0 {
+            path.reverse_contour();
+        }
+        paths.push(path);
+    }
+    for component in outline.components.iter() {
+        let affine = transform * Affine::from(component.transform);
+        decompose_into(&component.base, affine, get_glyph, resolving, paths);
+    }
+    resolving.pop();
+}
+
+fn transform_contour(contour: &Contour, transform: Affine) -> Contour {
+    let points = contour
+        .points
+        .iter()
+        .map(|pt| {
+            let Point { x, y } = transform * Point::new(pt.x as f64, pt.y as f64);
+            ContourPoint::new(
+                x as f32,
+                y as f32,
+                pt.typ.clone(),
+                pt.smooth,
+                pt.name.clone(),
+                None,
+                None,
+            )
+        })
+        .collect();
+    Contour::new(points, None, None)
+}
+
+/// The anchors of `glyph`, including those of its components, in the
+/// glyph's coordinate space.
+///
+/// Anchors on the glyph itself come first, so they take precedence over
+/// anchors with the same name from components.
+pub(crate) fn glyph_anchors<'a>(
+    glyph: &Glyph,
+    get_glyph: &dyn Fn(&GlyphName) -> Option<&'a Glyph>,
+) -> Vec<(String, Point)> {
+    let mut anchors = Vec::new();
+    let mut resolving = vec![glyph.name.clone()];
+    collect_anchors(
+        glyph,
+        Affine::default(),
+        get_glyph,
+        &mut resolving,
+        &mut anchors,
+    );
+    anchors
+}
+
+fn collect_anchors<'a>(
+    glyph: &Glyph,
+    transform: Affine,
+    get_glyph: &dyn Fn(&GlyphName) -> Option<&'a Glyph>,
+    resolving: &mut Vec<GlyphName>,
+    anchors: &mut Vec<(String, Point)>,
+) {
+    for anchor in glyph.anchors.iter().flatten() {
+        if let Some(name) = anchor.name.as_ref() {
+            let pos = transform * Point::new(anchor.x as f64, anchor.y as f64);
+            anchors.push((name.clone(), pos));
+        }
+    }
+    for component in glyph.outline.iter().flat_map(|o| o.components.iter()) {
+        if resolving.contains(&component.base) {
+            continue;
+        }
+        if let Some(base) = get_glyph(&component.base) {
+            resolving.push(component.base.clone());
+            let affine = transform * Affine::from(component.transform);
+            collect_anchors(base, affine, get_glyph, resolving, anchors);
+            resolving.pop();
+        }
+    }
+}
+
+/// The offset at which to place `base` so that one of its attaching anchors
+/// (such as `_top`) lands on the matching anchor (`top`) in `anchors`.
+///
+/// If `anchor` is given only that anchor is considered; otherwise the first
+/// of the base's anchors with a match is used.
+pub(crate) fn attachment_offset(
+    anchors: &[(String, Point)],
+    base: &Glyph,
+    anchor: Option<&str>,
+) -> Option<Vec2> {
+    base.anchors
+        .iter()
+        .flatten()
+        .filter_map(|mark| {
+            let name = mark.name.as_ref()?.strip_prefix('_')?;
+            if anchor.map(|a| a != name).unwrap_or(false) {
+                return None;
+            }
+            let (_, target) = anchors.iter().find(|(n, _)| n == name)?;
+            Some(*target - Point::new(mark.x as f64, mark.y as f64))
+        })
+        .next()
+}
+
+/// The shape drawn in place of a component that can't be resolved: a
+/// crossed-out box.
+pub(crate) fn broken_component_placeholder() -> BezPath {
+    let outer = Rect::new(0.0, 0.0, PLACEHOLDER_SIZE, PLACEHOLDER_SIZE);
+    let inner = outer.inset(-PLACEHOLDER_STROKE);
+    let mut bez = outer.to_path(0.1);
+    // the inner rect winds the other way, to make a hole
+    bez.move_to((inner.x0, inner.y0));
+    bez.line_to((inner.x0, inner.y1));
+    bez.line_to((inner.x1, inner.y1));
+    bez.line_to((inner.x1, inner.y0));
+    bez.close_path();
+
+    // the two diagonals, as counter-clockwise bars
+    let half = PLACEHOLDER_STROKE / 2.0;
+    let diagonals = [
+        ((inner.x0, inner.y0), (inner.x1, inner.y1), (half, -half)),
+        ((inner.x1, inner.y0), (inner.x0, inner.y1), (half, half)),
+    ];
+    for &((x0, y0), (x1, y1), (nx, ny)) in &diagonals {
+        bez.move_to((x0 + nx, y0 + ny));
+        bez.line_to((x1 + nx, y1 + ny));
+        bez.line_to((x1 - nx, y1 - ny));
+        bez.line_to((x0 - nx, y0 - ny));
+        bez.close_path();
+    }
+    bez
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn glyph_with_components(name: &str, bases: &[&str]) -> Glyph {
+        let mut glyph = Glyph::new_named(name);
+        let components = bases
+            .iter()
+            .map(|base| {
+                norad::glyph::Component::new((*base).into(), Affine::default().into(), None, None)
+            })
+            .collect();
+        glyph.outline = Some(norad::glyph::Outline {
+            contours: Vec::new(),
+            components,
+        });
+        glyph
+    }
+
+    fn assert_affine_near(left: Affine, right: Affine) {
+        for (l, r) in left.as_coeffs().iter().zip(right.as_coeffs().iter()) {
+            assert!((l - r).abs() < 1e-9, "{:?} != {:?}", left, right);
+        }
+    }
+
+    #[test]
+    fn decompose_transform() {
+        let mut component = Component::new("A".into());
+        component.transform = Affine::translate((120.0, -40.0))
+            * Affine::rotate(30f64.to_radians())
+            * Affine::scale_non_uniform(0.5, -2.0);
+        let parts = component.decomposed_transform();
+        assert!((parts.offset_x - 120.0).abs() < 1e-9);
+        assert!((parts.offset_y + 40.0).abs() < 1e-9);
+        assert!((parts.scale_x - 50.0).abs() < 1e-9);
+        assert!((parts.scale_y + 200.0).abs() < 1e-9);
+        assert!((parts.rotation - 30.0).abs() < 1e-9);
+
+        let original = component.transform;
+        component.set_decomposed_transform(&parts);
+        assert_affine_near(component.transform, original);
+    }
+
+    #[test]
+    fn transform_bounds() {
+        let mut component = Component::new("A".into());
+        component.base_bounds = Rect::new(0.0, 0.0, 100.0, 200.0);
+        component.transform_around(Affine::scale_non_uniform(-1.0, 1.0), DPoint::new(50.0, 0.0));
+        assert_eq!(component.bounds(), Rect::new(0.0, 0.0, 100.0, 200.0));
+
+        component.nudge(DVec2::from_raw((10.0, 20.0)));
+        component.transform_around(Affine::rotate(90f64.to_radians()), DPoint::new(10.0, 20.0));
+        let bounds = component.bounds();
+        assert!((bounds.x0 - -190.0).abs() < 1e-9);
+        assert!((bounds.y0 - 20.0).abs() < 1e-9);
+        assert!((bounds.width() - 200.0).abs() < 1e-9);
+        assert!((bounds.height() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn decompose_nested() {
+        let mut base = Glyph::new_named("bar");
+        let points = vec![(0.0, 0.0), (0.0, 100.0), (50.0, 100.0), (50.0, 0.0)]
+            .into_iter()
+            .map(|(x, y)| {
+                ContourPoint::new(x, y, norad::glyph::PointType::Line, false, None, None, None)
+            })
+            .collect();
+        base.outline = Some(norad::glyph::Outline {
+            contours: vec![Contour::new(points, None, None)],
+            components: Vec::new(),
+        });
+        let mut glyphs: HashMap<GlyphName, Glyph> = vec![
+            glyph_with_components("double", &["bar", "bar"]),
+            glyph_with_components("loop", &["bar", "loop"]),
+        ]
+        .into_iter()
+        .map(|g| (g.name.clone(), g))
+        .collect();
+        glyphs.insert("bar".into(), base);
+        let get = |name: &GlyphName| glyphs.get(name);
+
+        let paths = decomposed_paths(&"double".into(), Affine::translate((10.0, 0.0)), &get);
+        assert_eq!(paths.len(), 2);
+        let bbox = paths[0].bezier().bounding_box();
+        assert_eq!(bbox, Rect::new(10.0, 0.0, 60.0, 100.0));
+
+        // the cycle is skipped, but the rest of the glyph is kept
+        let paths = decomposed_paths(&"loop".into(), Affine::default(), &get);
+        assert_eq!(paths.len(), 1);
+
+        let flipped = Affine::scale_non_uniform(-1.0, 1.0);
+        let area = |path: &Path| path.bezier().area();
+        let original = decomposed_paths(&"bar".into(), Affine::default(), &get);
+        let paths = decomposed_paths(&"bar".into(), flipped, &get);
+        assert_eq!(area(&original[0]).signum(), area(&paths[0]).signum());
+    }
+
+    #[test]
+    fn anchor_attachment() {
+        let anchor = |name: &str, x: f32, y: f32| {
+            norad::glyph::Anchor::new(x, y, Some(name.to_string()), None, None, None)
+        };
+        let mut a = Glyph::new_named("A");
+        a.anchors = Some(vec![
+            anchor("top", 300.0, 700.0),
+            anchor("bottom", 300.0, 0.0),
+        ]);
+        let mut acute = Glyph::new_named("acute");
+        acute.anchors = Some(vec![anchor("_top", 100.0, 500.0)]);
+        let mut aacute = glyph_with_components("Aacute", &["A"]);
+        aacute.outline.as_mut().unwrap().components[0].transform =
+            Affine::translate((20.0, 0.0)).into();
+        let glyphs: HashMap<GlyphName, Glyph> = vec![a, acute]
+            .into_iter()
+            .map(|g| (g.name.clone(), g))
+            .collect();
+        let get = |name: &GlyphName| glyphs.get(name);
+
+        let anchors = glyph_anchors(&aacute, &get);
+        assert_eq!(anchors.len(), 2);
+        assert_eq!(anchors[0], ("top".to_string(), Point::new(320.0, 700.0)));
+
+        let acute = &glyphs[&GlyphName::from("acute")];
+        let offset = attachment_offset(&anchors, acute, None);
+        assert_eq!(offset, Some(Vec2::new(220.0, 200.0)));
+        assert_eq!(attachment_offset(&anchors, acute, Some("top")), offset);
+        assert_eq!(attachment_offset(&anchors, acute, Some("bottom")), None);
+    }
+
+    #[test]
+    fn cycles() {
+        let glyphs: HashMap<GlyphName, Glyph> = vec![
+            glyph_with_components("A", &[]),
+            glyph_with_components("Aacute", &["A", "acute"]),
+            glyph_with_components("acute", &[]),
+            glyph_with_components("B", &["C"]),
+            glyph_with_components("C", &["B"]),
+        ]
+        .into_iter()
+        .map(|g| (g.name.clone(), g))
+        .collect();
+        let get = |name: &GlyphName| glyphs.get(name);
+        let check = |glyph: &str, base: &str| check_component(&glyph.into(), &base.into(), get);
+
+        assert_eq!(check("Aacute", "A"), None);
+        assert_eq!(check("A", "A"), Some(ComponentProblem::Cycle));
+        assert_eq!(check("A", "Aacute"), Some(ComponentProblem::Cycle));
+        assert_eq!(check("acute", "Aacute"), Some(ComponentProblem::Cycle));
+        assert_eq!(check("A", "B"), None);
+        assert_eq!(check("B", "C"), Some(ComponentProblem::Cycle));
+        assert_eq!(check("A", "nope"), Some(ComponentProblem::MissingBase));
+    }
 }
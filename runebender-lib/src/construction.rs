@@ -0,0 +1,311 @@
+//! Building composite glyphs from recipes.
+//!
+//! A recipe describes a glyph made of components, one per line:
+//!
+//! ```text
+//! Aacute = A + acute@top
+//! Ocircumflexacute = O + circumflex@top + acute@top
+//! ```
+//!
+//! Each mark is placed so that its attaching anchor (`_top`) lines up with
+//! the matching anchor (`top`) in the glyph built so far; if no anchor is
+//! given, the first one that matches is used.
+
+use std::fmt;
+
+use druid::kurbo::Affine;
+use norad::glyph::{Advance, Glyph, GlyphName};
+
+use crate::component::{attachment_offset, glyph_anchors, Component};
+
+/// The recipes we know about, for the accented letters in Latin-1 and
+/// Latin Extended-A.
+const STANDARD_RECIPES: &str = include_str!("../../resources/glyph_recipes.txt");
+
+/// How to build a single composite glyph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe {
+    pub name: GlyphName,
+    pub base: GlyphName,
+    pub marks: Vec<RecipeMark>,
+}
+
+/// A component placed on top of a recipe's base.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeMark {
+    pub glyph: GlyphName,
+    /// The anchor to attach to, such as `top`.
+    pub anchor: Option<String>,
+}
+
+/// A line in a recipe file that could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The line number, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+/// Why a recipe could not be built.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecipeProblem {
+    /// One of the glyphs in the recipe does not exist.
+    MissingGlyph(GlyphName),
+    /// A mark had no anchor matching one in the glyph so far.
+    NoAnchor {
+        mark: GlyphName,
+        anchor: Option<String>,
+    },
+    /// The glyph would contain itself.
+    Cycle,
+    /// The target glyph has contours, and isn't a composite.
+    HasContours,
+    /// The target glyph is open in an editor.
+    GlyphIsOpen,
+}
+
+/// The result of building a set of recipes.
+#[derive(Debug, Default)]
+pub struct ConstructionReport {
+    /// Glyphs that were added to the font.
+    pub created: Vec<GlyphName>,
+    /// Existing composites whose components were replaced.
+    pub updated: Vec<GlyphName>,
+    pub skipped: Vec<(GlyphName, RecipeProblem)>,
+}
+
+impl Recipe {
+    /// All of the glyphs this recipe uses.
+    pub fn parts(&self) -> impl Iterator<Item = &GlyphName> {
+        std::iter::once(&self.base).chain(self.marks.iter().map(|m| &m.glyph))
+    }
+
+    /// The components for this recipe, and the advance they should have.
+    ///
+    /// The advance is taken from the base glyph.
+    pub(crate) fn build<'a>(
+        &self,
+        get_glyph: &dyn Fn(&GlyphName) -> Option<&'a Glyph>,
+    ) -> Result<(Vec<norad::glyph::Component>, Option<Advance>), RecipeProblem> {
+        let base =
+            get_glyph(&self.base).ok_or_else(|| RecipeProblem::MissingGlyph(self.base.clone()))?;
+        let mut anchors = glyph_anchors(base, get_glyph);
+        let mut components = vec![Component::new(self.base.clone()).to_norad()];
+
+        for mark in &self.marks {
+            let glyph = get_glyph(&mark.glyph)
+                .ok_or_else(|| RecipeProblem::MissingGlyph(mark.glyph.clone()))?;
+            let offset =
+                attachment_offset(&anchors, glyph, mark.anchor.as_deref()).ok_or_else(|| {
+                    RecipeProblem::NoAnchor {
+                        mark: mark.glyph.clone(),
+                        anchor: mark.anchor.clone(),
+                    }
+                })?;
+            let mut component = Component::new(mark.glyph.clone());
+            component.transform = Affine::translate(offset);
+            components.push(component.to_norad());
+
+            // the mark's own anchors replace the ones it covers, so that
+            // further marks stack on top of it.
+            for (name, pos) in glyph_anchors(glyph, get_glyph) {
+                if name.starts_with('_') {
+                    continue;
+                }
+                anchors.retain(|(existing, _)| *existing != name);
+                anchors.insert(0, (name, pos + offset));
+            }
+        }
+        Ok((components, base.advance.clone()))
+    }
+}
+
+impl std::str::FromStr for Recipe {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.splitn(2, '=');
+        let (name, parts) = match (split.next(), split.next()) {
+            (Some(name), Some(parts)) => (name.trim(), parts),
+            _ => return Err("expected 'name = base + mark@anchor'".into()),
+        };
+        check_name(name)?;
+        let mut parts = parts.split('+').map(str::trim);
+        let base = parts.next().unwrap_or_default();
+        check_name(base)?;
+        let marks = parts
+            .map(|part| {
+                let mut split = part.splitn(2, '@');
+                let glyph = split.next().unwrap_or_default().trim();
+                check_name(glyph)?;
+                let anchor = match split.next().map(str::trim) {
+                    Some("") => return Err(format!("missing anchor after '{}@'", glyph)),
+                    other => other.map(String::from),
+                };
+                Ok(RecipeMark {
+                    glyph: glyph.into(),
+                    anchor,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Recipe {
+            name: name.into(),
+            base: base.into(),
+            marks,
+        })
+    }
+}
+
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        Err("missing glyph name".into())
+    } else if name.chars().any(|c| c.is_whitespace() || c == '@') {
+        Err(format!("invalid glyph name '{}'", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// Parse a list of recipes, one per line.
+///
+/// Blank lines, and anything after a `#`, are ignored.
+pub fn parse_recipes(text: &str) -> Result<Vec<Recipe>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line, text)| text.parse().map_err(|message| ParseError { line, message }))
+        .collect()
+}
+
+/// The built-in recipes.
+pub fn standard_recipes() -> Vec<Recipe> {
+    parse_recipes(STANDARD_RECIPES).expect("standard recipes are valid")
+}
+
+impl ConstructionReport {
+    pub fn built(&self) -> usize {
+        self.created.len() + self.updated.len()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl fmt::Display for RecipeProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecipeProblem::MissingGlyph(name) => write!(f, "missing glyph '{}'", name),
+            RecipeProblem::NoAnchor {
+                mark,
+                anchor: Some(anchor),
+            } => write!(f, "no '{}' anchor for '{}'", anchor, mark),
+            RecipeProblem::NoAnchor { mark, anchor: None } => {
+                write!(f, "no matching anchor for '{}'", mark)
+            }
+            RecipeProblem::Cycle => write!(f, "glyph would contain itself"),
+            RecipeProblem::HasContours => write!(f, "glyph has contours"),
+            RecipeProblem::GlyphIsOpen => write!(f, "glyph is open in an editor"),
+        }
+    }
+}
+
+impl fmt::Display for ConstructionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, problem) in &self.skipped {
+            writeln!(f, "{}: {}", name, problem)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use druid::kurbo::Point;
+    use std::collections::HashMap;
+
+    fn glyph_with_anchors(name: &str, width: f32, anchors: &[(&str, f32, f32)]) -> Glyph {
+        let mut glyph = Glyph::new_named(name);
+        glyph.advance = Some(Advance { width, height: 0.0 });
+        glyph.anchors = Some(
+            anchors
+                .iter()
+                .map(|(name, x, y)| {
+                    norad::glyph::Anchor::new(*x, *y, Some(name.to_string()), None, None, None)
+                })
+                .collect(),
+        );
+        glyph
+    }
+
+    fn offset(component: &norad::glyph::Component) -> Point {
+        Affine::from(component.transform) * Point::ZERO
+    }
+
+    #[test]
+    fn parse() {
+        let text = "# accents\nAacute = A + acute@top  # comment\n\nAdieresis=A+dieresis\n";
+        let recipes = parse_recipes(text).unwrap();
+        assert_eq!(recipes.len(), 2);
+        assert_eq!(recipes[0].name, GlyphName::from("Aacute"));
+        assert_eq!(recipes[0].base, GlyphName::from("A"));
+        assert_eq!(
+            recipes[0].marks,
+            vec![RecipeMark {
+                glyph: "acute".into(),
+                anchor: Some("top".into())
+            }]
+        );
+        assert_eq!(recipes[1].marks[0].anchor, None);
+
+        assert_eq!(parse_recipes("Aacute A + acute").unwrap_err().line, 1);
+        assert_eq!(parse_recipes("\nAacute = + acute").unwrap_err().line, 2);
+        assert!(parse_recipes("Aacute = A + acute@").is_err());
+        assert!(!standard_recipes().is_empty());
+    }
+
+    #[test]
+    fn build_stacked_marks() {
+        let glyphs: HashMap<GlyphName, Glyph> = vec![
+            glyph_with_anchors("O", 600.0, &[("top", 300.0, 700.0)]),
+            glyph_with_anchors(
+                "circumflex",
+                0.0,
+                &[("_top", 100.0, 500.0), ("top", 100.0, 650.0)],
+            ),
+            glyph_with_anchors("acute", 0.0, &[("_top", 50.0, 500.0)]),
+        ]
+        .into_iter()
+        .map(|g| (g.name.clone(), g))
+        .collect();
+        let get = |name: &GlyphName| glyphs.get(name);
+
+        let recipe: Recipe = "Ocircumflexacute = O + circumflex@top + acute@top"
+            .parse()
+            .unwrap();
+        let (components, advance) = recipe.build(&get).unwrap();
+        assert_eq!(advance.unwrap().width, 600.0);
+        assert_eq!(components.len(), 3);
+        assert_eq!(offset(&components[0]), Point::ZERO);
+        assert_eq!(offset(&components[1]), Point::new(200.0, 200.0));
+        // the acute sits on the circumflex's top anchor, at (300, 850)
+        assert_eq!(offset(&components[2]), Point::new(250.0, 350.0));
+
+        let recipe: Recipe = "Obottom = O + acute@bottom".parse().unwrap();
+        assert!(matches!(
+            recipe.build(&get),
+            Err(RecipeProblem::NoAnchor { .. })
+        ));
+        let recipe: Recipe = "Otilde = O + tilde@top".parse().unwrap();
+        assert_eq!(
+            recipe.build(&get).unwrap_err(),
+            RecipeProblem::MissingGlyph("tilde".into())
+        );
+    }
+}
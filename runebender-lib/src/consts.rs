@@ -8,11 +8,17 @@ pub const GLYPHS_APP_PASTEBOARD_TYPE: FormatId = "Glyphs elements pasteboard typ
 
 /// Commands and Selectors
 pub mod cmd {
-    use druid::kurbo::{Point, Vec2};
-    use druid::Selector;
+    use std::path::PathBuf;
+
+    use druid::kurbo::{Affine, Point, Vec2};
+    use druid::{Color, FileInfo, Selector};
     use norad::GlyphName;
 
+    use crate::charset::{GlyphSpec, Template};
+    use crate::component::ComponentTransform;
+    use crate::data::Notification;
     use crate::design_space::{DPoint, DVec2};
+    use crate::glyph_filter::GridSort;
     use crate::point::EntityId;
     use crate::tools::ToolId;
 
@@ -31,9 +37,108 @@ pub mod cmd {
     /// sent by the 'new glyph' menu item
     pub const NEW_GLYPH: Selector = Selector::new("runebender.new-glyph");
 
-    /// sent by the 'delete glyph' menu item
+    /// sent by the 'duplicate glyphs' menu item, to show the panel for
+    /// duplicating the selected glyphs.
+    pub const SHOW_DUPLICATE_GLYPHS: Selector = Selector::new("runebender.show-duplicate-glyphs");
+
+    /// Sent to duplicate the selected glyphs.
+    ///
+    /// The argument **must** be the suffix for the copies' names, such as
+    /// `alt` or `ss01`.
+    pub const DUPLICATE_GLYPHS: Selector<String> = Selector::new("runebender.duplicate-glyphs");
+
+    /// sent by the 'add glyphs' menu item, to show the panel for adding
+    /// glyphs from a list or a file.
+    pub const SHOW_ADD_GLYPHS: Selector = Selector::new("runebender.show-add-glyphs");
+
+    /// Sent to add empty glyphs to a font, skipping any that already exist.
+    ///
+    /// The argument **must** be the glyphs to add.
+    pub const ADD_GLYPHS: Selector<Vec<GlyphSpec>> = Selector::new("runebender.add-glyphs");
+
+    /// Sent when the user has chosen a character set or encoding file to
+    /// add glyphs from.
+    ///
+    /// The argument **must** be the `FileInfo` from the open panel.
+    pub const ADD_GLYPHS_FROM_FILE: Selector<FileInfo> =
+        Selector::new("runebender.add-glyphs-from-file");
+
+    /// sent by the items in the 'new from template' menu, to create a new
+    /// font with the template's glyphs.
+    pub const NEW_FONT_FROM_TEMPLATE: Selector<Template> =
+        Selector::new("runebender.new-font-from-template");
+
+    /// sent by the 'delete glyph' menu item, to delete the selected glyphs.
+    ///
+    /// If other glyphs use the selected glyphs as components, the user is
+    /// asked before anything is deleted.
     pub const DELETE_SELECTED_GLYPH: Selector = Selector::new("runebender.delete-selected-glyph");
 
+    /// Sent to delete glyphs, decomposing them in any glyphs that use them.
+    ///
+    /// The argument **must** be the names of the glyphs to delete.
+    pub const DELETE_GLYPHS: Selector<Vec<GlyphName>> = Selector::new("runebender.delete-glyphs");
+
+    /// sent by the 'open selected glyphs' menu item, to open an editor for
+    /// each selected glyph.
+    pub const OPEN_SELECTED_GLYPHS: Selector = Selector::new("runebender.open-selected-glyphs");
+
+    /// sent by the 'edit selected glyphs' menu item; shows a panel for
+    /// transforming the selected glyphs or setting their advance.
+    pub const EDIT_SELECTED_GLYPHS: Selector = Selector::new("runebender.edit-selected-glyphs");
+
+    /// Sent to set the mark color of the selected glyphs.
+    ///
+    /// The argument **must** be the new color, or `None` to clear it.
+    pub const SET_MARK_COLOR: Selector<Option<Color>> = Selector::new("runebender.set-mark-color");
+
+    /// Sent to transform the outlines of the selected glyphs, around the
+    /// center of each glyph's bounds.
+    ///
+    /// The argument **must** be an `Affine`.
+    pub const TRANSFORM_GLYPHS: Selector<Affine> = Selector::new("runebender.transform-glyphs");
+
+    /// Sent to set the advance width of the selected glyphs.
+    ///
+    /// The argument **must** be the new width, in design units.
+    pub const SET_ADVANCE_WIDTH: Selector<f64> = Selector::new("runebender.set-advance-width");
+
+    /// Sent to an editor window to transform its whole glyph, around the
+    /// center of the glyph's bounds.
+    ///
+    /// This is how `TRANSFORM_GLYPHS` is applied to open glyphs, so that the
+    /// change goes on the editor's undo stack.
+    ///
+    /// The argument **must** be an `Affine`.
+    pub const TRANSFORM_GLYPH: Selector<Affine> = Selector::new("runebender.transform-glyph");
+
+    /// Sent to an editor window to set the advance width of its glyph.
+    ///
+    /// This is how `SET_ADVANCE_WIDTH` is applied to open glyphs.
+    ///
+    /// The argument **must** be the new width, in design units.
+    pub const SET_GLYPH_ADVANCE: Selector<f64> = Selector::new("runebender.set-glyph-advance");
+
+    /// Sent by the items in the 'sort glyphs' menu, to change the order of
+    /// the glyph grid.
+    ///
+    /// The argument **must** be a `GridSort`.
+    pub const SET_GRID_SORT: Selector<GridSort> = Selector::new("runebender.set-grid-sort");
+
+    /// sent by the 'QA report' menu item, to check the whole font.
+    pub const SHOW_QA_REPORT: Selector = Selector::new("runebender.show-qa-report");
+
+    /// sent by the 'unicode coverage' menu item, to show which blocks and
+    /// scripts the font covers.
+    pub const SHOW_COVERAGE: Selector = Selector::new("runebender.show-coverage");
+
+    /// Sent to a font's main window to add empty, encoded glyphs for some
+    /// characters.
+    ///
+    /// The argument **must** be the characters to add.
+    pub const ADD_GLYPHS_FOR_CHARS: Selector<Vec<char>> =
+        Selector::new("runebender.add-glyphs-for-chars");
+
     /// Sent to the root to rename a glyph.
     ///
     /// The arguments **must** be a `RenameGlyphArgs`
@@ -45,15 +150,55 @@ pub mod cmd {
         pub new: GlyphName,
     }
 
-    /// sent by the 'add component' menu item
+    /// sent by the 'add component' menu item; opens the component picker
+    /// in the current editor window.
     pub const ADD_COMPONENT: Selector = Selector::new("runebender.add-component");
 
+    /// Sent to an editor to add a component to its glyph.
+    ///
+    /// The component is not added if it would make the glyph contain itself.
+    ///
+    /// The argument **must** be the `GlyphName` of the component's base.
+    pub const INSERT_COMPONENT: Selector<GlyphName> = Selector::new("runebender.insert-component");
+
+    /// sent by the 'build standard composites' menu item, to build the
+    /// built-in recipes whose parts exist in the font.
+    pub const BUILD_STANDARD_COMPOSITES: Selector =
+        Selector::new("runebender.build-standard-composites");
+
+    /// Sent when the user has chosen a recipe file to build composites from.
+    ///
+    /// The argument **must** be the `FileInfo` from the open panel.
+    pub const BUILD_COMPOSITES_FROM_FILE: Selector<FileInfo> =
+        Selector::new("runebender.build-composites-from-file");
+
     /// sent by 'align selection' menu item in Paths menu
     pub const ALIGN_SELECTION: Selector = Selector::new("runebender.align-selection");
 
     // sent by 'reverse contours' menu item in Paths menu
     pub const REVERSE_CONTOURS: Selector = Selector::new("runebender.reverse-contours");
 
+    /// sent by 'decompose components' menu item in Paths menu.
+    ///
+    /// In an editor this decomposes the selected components, or all of them
+    /// if none are selected; in the main window it decomposes the components
+    /// of the selected glyphs.
+    pub const DECOMPOSE_COMPONENTS: Selector = Selector::new("runebender.decompose-components");
+
+    /// Sent to an editor window to decompose all of its glyph's components.
+    ///
+    /// This is how the main window decomposes glyphs that are open, so that
+    /// the change goes on the editor's undo stack.
+    pub const DECOMPOSE_GLYPH: Selector = Selector::new("runebender.decompose-glyph");
+
+    /// sent by 'flip horizontally' menu item in Paths menu.
+    ///
+    /// In the main window this flips each of the selected glyphs.
+    pub const FLIP_HORIZONTAL: Selector = Selector::new("runebender.flip-horizontal");
+
+    /// sent by 'flip vertically' menu item in Paths menu
+    pub const FLIP_VERTICAL: Selector = Selector::new("runebender.flip-vertical");
+
     /// Sent when a new tool has been selected.
     ///
     /// The payload must be a `ToolId`.
@@ -102,6 +247,13 @@ pub mod cmd {
     /// Sent from the coord panel when a coordinate is manually edited.
     pub const NUDGE_SELECTION: Selector<DVec2> = Selector::new("runebender.editor-nudge-selection");
 
+    /// Sent from the coord panel when the transform of the selected component
+    /// is manually edited.
+    ///
+    /// The argument **must** be the new `ComponentTransform`.
+    pub const SET_COMPONENT_TRANSFORM: Selector<ComponentTransform> =
+        Selector::new("runebender.editor-set-component-transform");
+
     /// Sent from the sidebearing panel when an edit occurs.
     pub const ADJUST_SIDEBEARING: Selector<AdjustSidebearing> =
         Selector::new("runebender.editor.nudge-it-all");
@@ -114,6 +266,36 @@ pub mod cmd {
         pub is_left: bool,
     }
 
+    /// sent by the items in the 'open recent' menu.
+    ///
+    /// The argument **must** be the `PathBuf` of the font to open.
+    pub const OPEN_RECENT_FILE: Selector<PathBuf> = Selector::new("runebender.open-recent-file");
+
+    /// sent by the 'clear menu' item in the 'open recent' menu.
+    pub const CLEAR_RECENT_FILES: Selector = Selector::new("runebender.clear-recent-files");
+
+    /// Sent to show a notification, such as an error or warning, to the user.
+    ///
+    /// The argument **must** be a `Notification`.
+    pub const NOTIFY: Selector<Notification> = Selector::new("runebender.notify");
+
+    /// Sent by the unsaved changes prompt when the user has made a choice.
+    ///
+    /// The argument **must** be an `UnsavedChangesChoice`.
+    pub const UNSAVED_CHANGES_RESPONSE: Selector<UnsavedChangesChoice> =
+        Selector::new("runebender.unsaved-changes-response");
+
+    /// The possible responses to the unsaved changes prompt.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum UnsavedChangesChoice {
+        /// Save the font, and then continue closing.
+        Save,
+        /// Continue closing without saving.
+        Discard,
+        /// Don't close.
+        Cancel,
+    }
+
     /// Sent from the coord panel when the selection bbox is manually edited.
     pub const SCALE_SELECTION: Selector<ScaleSelectionArgs> =
         Selector::new("runebender.editor-scale-selection");
@@ -0,0 +1,302 @@
+//! Which Unicode blocks and scripts a font covers.
+//!
+//! Coverage is measured against a `Target`, the set of characters we expect
+//! the font to have; by default this is the aglfn, the list of characters
+//! with standard glyph names. Other characters are counted when they are
+//! present, but are never reported as missing.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use crate::charset::{GlyphSpec, Template};
+use crate::data::Workspace;
+use crate::glyph_names;
+use crate::unicode::{self, Block};
+
+/// The name used for characters outside of any block we know about.
+const OTHER_BLOCK: &str = "Other";
+
+/// The general categories of characters that a font is not expected to
+/// draw: controls, surrogates, private use and unassigned characters.
+const NOT_TARGETED: &[&str] = &["Cc", "Cs", "Co", "Cn"];
+
+/// The characters a font is expected to have.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// The characters in the aglfn, which have standard glyph names.
+    Standard,
+    /// The characters in one of the templates for new fonts.
+    Template(Template),
+    /// Every assigned, drawable character in a Unicode block.
+    Block(Block),
+    /// The characters listed in a character set file.
+    Charset {
+        /// The name of the file.
+        name: Arc<str>,
+        chars: Arc<[char]>,
+    },
+}
+
+/// How much of a block or script a font covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Complete,
+    Partial,
+    Missing,
+}
+
+/// The coverage of a single block or script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupCoverage {
+    pub name: &'static str,
+    /// The Unicode block, for blocks; `None` for scripts, and for
+    /// characters outside any block.
+    pub block: Option<Block>,
+    /// The number of characters in the font.
+    pub present: usize,
+    /// The expected characters that are not in the font, in order.
+    pub missing: Vec<char>,
+}
+
+/// The coverage of a font, by block and by script.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    /// Blocks, in codepoint order.
+    pub blocks: Vec<GroupCoverage>,
+    /// Scripts, by name.
+    pub scripts: Vec<GroupCoverage>,
+}
+
+impl Target {
+    /// A target with the encoded glyphs in a character set file; glyphs
+    /// without codepoints are ignored.
+    pub fn charset(name: impl Into<Arc<str>>, glyphs: &[GlyphSpec]) -> Self {
+        let chars: BTreeSet<char> = glyphs
+            .iter()
+            .flat_map(|glyph| glyph.codepoints.iter().copied())
+            .collect();
+        Target::Charset {
+            name: name.into(),
+            chars: chars.into_iter().collect(),
+        }
+    }
+
+    /// A short description, such as "the Latin-1 template".
+    pub fn title(&self) -> String {
+        match self {
+            Target::Standard => "standard characters".into(),
+            Target::Template(template) => format!("the {} template", template.name),
+            Target::Block(block) => format!("the {} block", block.name),
+            Target::Charset { name, .. } => name.to_string(),
+        }
+    }
+
+    /// The expected characters, in order.
+    pub fn chars(&self) -> Vec<char> {
+        let chars: BTreeSet<char> = match self {
+            Target::Standard => glyph_names::aglfn_chars().map(|(c, _)| c).collect(),
+            Target::Template(template) => template
+                .glyphs()
+                .iter()
+                .flat_map(|glyph| glyph.codepoints.iter().copied())
+                .collect(),
+            Target::Block(block) => (block.first..=block.last)
+                .filter_map(std::char::from_u32)
+                .filter(|c| !NOT_TARGETED.contains(&unicode::general_category(*c)))
+                .collect(),
+            Target::Charset { chars, .. } => chars.iter().copied().collect(),
+        };
+        chars.into_iter().collect()
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Target::Standard
+    }
+}
+
+impl GroupCoverage {
+    fn new(name: &'static str, block: Option<Block>) -> Self {
+        GroupCoverage {
+            name,
+            block,
+            present: 0,
+            missing: Vec::new(),
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        if self.missing.is_empty() {
+            Status::Complete
+        } else if self.present == 0 {
+            Status::Missing
+        } else {
+            Status::Partial
+        }
+    }
+}
+
+impl Coverage {
+    /// The coverage of a font with glyphs for `codepoints`, which is
+    /// expected to have the characters in `target`.
+    pub fn new(codepoints: impl IntoIterator<Item = char>, target: &Target) -> Self {
+        let present: BTreeSet<char> = codepoints.into_iter().collect();
+        // blocks are keyed by their first codepoint, so they stay in order
+        let mut blocks = BTreeMap::new();
+        let mut scripts = BTreeMap::new();
+        let block_key = |c: char| match unicode::block(c) {
+            Some(block) => ((block.first, block.name), Some(block)),
+            None => ((u32::MAX, OTHER_BLOCK), None),
+        };
+
+        for &c in &present {
+            let (key, block) = block_key(c);
+            blocks
+                .entry(key)
+                .or_insert_with(|| GroupCoverage::new(key.1, block))
+                .present += 1;
+            let script = unicode::script(c);
+            scripts
+                .entry(script)
+                .or_insert_with(|| GroupCoverage::new(script, None))
+                .present += 1;
+        }
+
+        for c in target.chars().into_iter().filter(|c| !present.contains(c)) {
+            let (key, block) = block_key(c);
+            blocks
+                .entry(key)
+                .or_insert_with(|| GroupCoverage::new(key.1, block))
+                .missing
+                .push(c);
+            let script = unicode::script(c);
+            scripts
+                .entry(script)
+                .or_insert_with(|| GroupCoverage::new(script, None))
+                .missing
+                .push(c);
+        }
+
+        Coverage {
+            blocks: blocks.into_iter().map(|(_, group)| group).collect(),
+            scripts: scripts.into_iter().map(|(_, group)| group).collect(),
+        }
+    }
+
+    /// The coverage of an open font, including any unsaved edits.
+    pub fn for_workspace(workspace: &Workspace, target: &Target) -> Self {
+        let ufo = &workspace.font.ufo;
+        let mut glyphs: BTreeMap<_, _> = ufo
+            .iter_names()
+            .filter_map(|name| ufo.get_glyph(&name).map(|g| (name, g.clone())))
+            .collect();
+        for session in workspace.sessions.values() {
+            glyphs.insert(session.name.clone(), session.glyph.clone());
+        }
+        Coverage::new(
+            glyphs
+                .values()
+                .filter_map(|glyph| glyph.codepoints.as_ref())
+                .flatten()
+                .copied(),
+            target,
+        )
+    }
+
+    /// The number of characters in the font, and the number missing.
+    pub fn totals(&self) -> (usize, usize) {
+        self.blocks
+            .iter()
+            .fold((0, 0), |(present, missing), group| {
+                (present + group.present, missing + group.missing.len())
+            })
+    }
+
+    /// Every expected character missing from the font, in order.
+    pub fn all_missing(&self) -> Vec<char> {
+        self.blocks
+            .iter()
+            .flat_map(|group| group.missing.iter().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_and_scripts() {
+        let font = ('A'..='Z').chain('a'..='z').chain(vec!['é', 'Ж']);
+        let coverage = Coverage::new(font, &Target::Standard);
+        let block = |name: &str| {
+            coverage
+                .blocks
+                .iter()
+                .find(|group| group.name == name)
+                .unwrap()
+        };
+
+        let basic = block("Basic Latin");
+        assert_eq!(basic.present, 52);
+        assert!(basic.missing.contains(&'!'));
+        assert!(!basic.missing.contains(&'A'));
+        assert_eq!(basic.status(), Status::Partial);
+
+        // no aglfn characters are expected in Cyrillic; see `targets`
+        assert_eq!(block("Cyrillic").present, 1);
+        assert!(block("Cyrillic").missing.is_empty());
+        assert_eq!(block("Greek and Coptic").status(), Status::Missing);
+        assert_eq!(coverage.blocks[0].name, "Basic Latin");
+
+        let latin = coverage.scripts.iter().find(|g| g.name == "Latin").unwrap();
+        assert_eq!(latin.present, 53);
+        assert!(latin.missing.contains(&'à'));
+
+        let missing = coverage.all_missing();
+        assert!(missing.windows(2).all(|w| w[0] < w[1]));
+        assert!(missing.contains(&'α'));
+    }
+
+    #[test]
+    fn targets() {
+        let font = ('A'..='Z').chain('a'..='z').chain(vec!['é', 'Ж']);
+
+        // a whole block expects all of its assigned characters
+        let cyrillic = Target::Block(unicode::block('Ж').unwrap());
+        let coverage = Coverage::new(font.clone(), &cyrillic);
+        let block = coverage
+            .blocks
+            .iter()
+            .find(|group| group.name == "Cyrillic")
+            .unwrap();
+        assert_eq!(block.status(), Status::Partial);
+        assert_eq!(block.missing.len(), 255);
+        assert!(block.missing.contains(&'б'));
+        // nothing outside the target is missing
+        assert_eq!(coverage.totals(), (54, 255));
+
+        // controls and private use characters are not expected
+        let basic_latin = Target::Block(unicode::block('A').unwrap());
+        assert_eq!(basic_latin.chars().len(), 95);
+        assert!(!basic_latin.chars().contains(&'\u{7f}'));
+        let private_use = Target::Block(unicode::block('\u{e000}').unwrap());
+        assert!(private_use.chars().is_empty());
+
+        let latin1 = crate::charset::TEMPLATES
+            .iter()
+            .find(|t| t.name == "Latin-1")
+            .unwrap();
+        let coverage = Coverage::new(font.clone(), &Target::Template(*latin1));
+        let missing = coverage.all_missing();
+        assert!(missing.contains(&'!') && missing.contains(&'ÿ'));
+        assert!(!missing.contains(&'é') && !missing.contains(&'Ā'));
+
+        let glyphs = crate::charset::parse_charset_file("A\nб\na.sc\n").unwrap();
+        let target = Target::charset("test.txt", &glyphs);
+        assert_eq!(target.chars(), vec!['A', 'б']);
+        let coverage = Coverage::new(font, &target);
+        assert_eq!(coverage.all_missing(), vec!['б']);
+    }
+}
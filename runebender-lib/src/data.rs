@@ -1,29 +1,87 @@
 //! Application state.
 
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::Arc;
 
-use druid::kurbo::{BezPath, Point, Rect, Shape, Size};
-use druid::{Data, Lens, WindowId};
+use druid::kurbo::{Affine, BezPath, Point, Rect, Shape, Size, Vec2};
+use druid::{Color, Command, Data, Lens, WindowId};
 use norad::glyph::{Contour, ContourPoint, Glyph, GlyphName, PointType};
 use norad::{FontInfo, Ufo};
 
 use crate::bez_cache::BezCache;
+use crate::charset::GlyphSpec;
+use crate::component::{
+    broken_component_placeholder, BrokenComponent, Component, ComponentProblem,
+};
+use crate::construction::{ConstructionReport, Recipe, RecipeProblem};
+use crate::design_space::DPoint;
 use crate::edit_session::{EditSession, SessionId};
+use crate::glyph_filter::{GlyphFilter, GridSort, Query};
+use crate::point::EntityId;
+use crate::preferences::Preferences;
+use crate::qa::{Issue, IssueCache};
+use crate::report::GlyphReportCache;
+use crate::undo::{FontEdit, FontHistory, References};
 
 /// This is by convention.
 const DEFAULT_UNITS_PER_EM: f64 = 1000.;
+/// The lib key for the order glyphs should be shown in.
+pub(crate) const GLYPH_ORDER_KEY: &str = "public.glyphOrder";
 
 /// The top level data structure.
 ///
-/// Currently this just wraps `Workspace`; in the future multiple workspaces
-/// will be possible.
+/// This holds a `Workspace` for each open font.
 #[derive(Clone, Data, Default, Lens)]
 pub struct AppState {
-    pub workspace: Workspace,
+    pub workspaces: Arc<HashMap<WorkspaceId, Workspace>>,
+    /// Errors and warnings that have not yet been dismissed, oldest first.
+    ///
+    /// Each font's window only shows the notifications about that font, and
+    /// those that aren't about any font.
+    pub notifications: Arc<Vec<Notification>>,
+    /// Glyphs copied from a glyph grid, which can be pasted into any font.
+    pub glyph_clipboard: Option<Arc<CopiedGlyphs>>,
+    pub preferences: Preferences,
+}
+
+/// A unique identifier for an open font.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Data)]
+pub struct WorkspaceId(usize);
+
+/// Glyphs copied from a font.
+#[derive(Debug, Clone, Default)]
+pub struct CopiedGlyphs {
+    /// The glyphs that were copied.
+    glyphs: Vec<Arc<Glyph>>,
+    /// Glyphs used as components by the copied glyphs; these are added when
+    /// pasting if they don't already exist.
+    component_bases: Vec<Arc<Glyph>>,
+}
+
+/// A message for the user, such as an error that occurred while saving.
+#[derive(Debug, Clone, Data)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    /// A short description, shown in the status bar.
+    pub message: Arc<str>,
+    /// Additional information, such as the underlying error.
+    pub details: Option<Arc<str>>,
+    /// A command that will retry the operation that failed.
+    #[data(ignore)]
+    pub retry: Option<Command>,
+    /// The font this is about, if any.
+    pub workspace: Option<WorkspaceId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub enum NotificationKind {
+    Warning,
+    Error,
 }
 
 /// A workspace is a single font, corresponding to a UFO file on disk.
@@ -31,8 +89,16 @@ pub struct AppState {
 pub struct Workspace {
     pub font: Arc<FontObject>,
     /// The currently selected glyph (in the main glyph list) if any.
-    //TODO: allow multiple selections
+    ///
+    /// When several glyphs are selected this is the one that was clicked
+    /// most recently; it is the one shown in the sidebar.
     pub selected: Option<GlyphName>,
+    /// All of the glyphs selected in the main glyph list, including `selected`.
+    pub selection: Arc<BTreeSet<GlyphName>>,
+    /// The search and filters applied to the main glyph list.
+    pub grid_filter: GlyphFilter,
+    /// The order of the main glyph list.
+    pub grid_sort: GridSort,
     /// glyphs that are already open in an editor window
     pub open_glyphs: Arc<HashMap<GlyphName, WindowId>>,
     pub sessions: Arc<HashMap<SessionId, Arc<EditSession>>>,
@@ -40,6 +106,30 @@ pub struct Workspace {
     // really just a store of the fully resolved Beziers of all glyphs.
     cache: Arc<BezCache>,
     pub info: SimpleFontInfo,
+    /// The state of the font when it was last loaded or saved.
+    saved: Arc<SavedState>,
+    /// The window showing this font's glyph grid.
+    #[data(ignore)]
+    pub main_window: Option<WindowId>,
+    /// Edits to the font as a whole, which are undone from the main window.
+    #[data(ignore)]
+    history: Arc<FontHistory>,
+}
+
+/// A snapshot of a font as it exists on disk.
+///
+/// This is compared against the current state to determine what has been
+/// modified.
+#[derive(Clone, Default)]
+pub(crate) struct SavedState {
+    /// The location of the font on disk, if it has been saved.
+    pub path: Option<Arc<Path>>,
+    pub glyphs: HashMap<GlyphName, Arc<Glyph>>,
+    pub info: SimpleFontInfo,
+    pub font_info: Option<FontInfo>,
+    pub lib: Option<plist::Dictionary>,
+    pub groups: Option<BTreeMap<String, Vec<GlyphName>>>,
+    pub kerning: Option<BTreeMap<String, BTreeMap<String, f32>>>,
 }
 
 #[derive(Clone, Data)]
@@ -50,17 +140,21 @@ pub struct FontObject {
     placeholder: Arc<BezPath>,
 }
 
-/// The data type for a grid square.
+/// The state of a grid square.
 ///
 /// Unlike GlyphDetail, this doesn't have a reference to the glyph itself,
-/// which is expensive to find in large glyphsets.
-#[derive(Debug, Clone, Data, Lens)]
+/// which is expensive to find in large glyphsets; the outline is drawn from
+/// the grid's thumbnail cache.
+#[derive(Debug, Clone, Data)]
 pub(crate) struct GridGlyph {
     pub name: GlyphName,
-    pub outline: Arc<BezPath>,
     pub is_placeholder: bool,
     pub is_selected: bool,
-    pub upm: f64,
+    pub is_modified: bool,
+    pub mark_color: Option<Color>,
+    pub codepoint: Option<char>,
+    pub advance: f64,
+    pub component_count: usize,
 }
 
 /// Detailed information about a specific glyph.
@@ -75,15 +169,15 @@ pub struct GlyphDetail {
     is_placeholder: bool,
 }
 
-#[derive(Clone, Data, Lens)]
+#[derive(Clone, Data, Lens, PartialEq)]
 pub struct SimpleFontInfo {
-    metrics: FontMetrics,
+    pub(crate) metrics: FontMetrics,
     pub family_name: Arc<str>,
     pub style_name: Arc<str>,
 }
 
 /// Things in `FontInfo` that are relevant while editing or drawing.
-#[derive(Clone, Data, Lens)]
+#[derive(Clone, Data, Lens, PartialEq)]
 pub struct FontMetrics {
     pub units_per_em: f64,
     pub descender: Option<f64>,
@@ -99,6 +193,8 @@ pub struct EditorState {
     pub metrics: FontMetrics,
     pub font: Workspace,
     pub session: Arc<EditSession>,
+    /// The outline issues, shared by everything in the editor window.
+    issue_cache: Rc<RefCell<IssueCache>>,
 }
 
 /// A type constructed by a lens to represent our sidebearings.
@@ -108,15 +204,254 @@ pub struct Sidebearings {
     pub right: f64,
 }
 
+/// The result of reloading files that were changed by another program.
+#[derive(Debug, Default)]
+pub struct ReloadReport {
+    /// Glyphs that were updated, added, or removed to match the disk.
+    pub reloaded: Vec<GlyphName>,
+    /// Glyphs that changed on disk but also have unsaved changes here.
+    ///
+    /// These are left alone; saving will overwrite the version on disk.
+    pub conflicts: Vec<GlyphName>,
+    /// `true` if the font info changed on disk while we had unsaved changes.
+    pub info_conflict: bool,
+    /// The lib, groups or kerning files that changed on disk while we had
+    /// unsaved changes to them.
+    pub plist_conflicts: Vec<&'static str>,
+    /// Descriptions of files that could not be reloaded.
+    pub errors: Vec<String>,
+}
+
+/// The answer to `Workspace::is_modified`, kept until the workspace changes.
+///
+/// Checking for changes compares every glyph with its saved version, which
+/// is too slow to do on every update, as the window title does.
+#[derive(Default)]
+pub struct ModifiedCache {
+    /// The parts of the workspace the answer depends on.
+    checked: Option<(CheckedGlyphs, Arc<SavedState>, SimpleFontInfo)>,
+    is_modified: bool,
+}
+
+/// The parts of a workspace that hold its glyphs.
+type CheckedGlyphs = (Arc<FontObject>, Arc<HashMap<SessionId, Arc<EditSession>>>);
+
+/// The parts of the glyph grid's search that are slow to build, kept
+/// between rebuilds of the grid.
+///
+/// The query is only parsed again when its text changes, and the QA checks
+/// are only rerun for the glyphs that have changed.
+#[derive(Default)]
+pub struct GridFilterCache {
+    /// The query text, and the result of parsing it.
+    query: Option<(String, Result<Query, regex::Error>)>,
+    /// The parts of the QA report for each glyph.
+    report: GlyphReportCache,
+    /// The font and sessions that were checked, and the glyphs with problems.
+    with_errors: Option<(CheckedGlyphs, BTreeSet<String>)>,
+}
+
+impl GridFilterCache {
+    /// Rebuild anything that is out of date for the workspace's filter.
+    fn update(&mut self, workspace: &Workspace) {
+        let text = &workspace.grid_filter.query;
+        if self
+            .query
+            .as_ref()
+            .map(|(parsed, _)| parsed != text)
+            .unwrap_or(true)
+        {
+            self.query = Some((text.clone(), Query::parse(text)));
+        }
+
+        let report_is_stale = match &self.with_errors {
+            Some(((font, sessions), _)) => {
+                !font.same(&workspace.font) || !sessions.same(&workspace.sessions)
+            }
+            None => true,
+        };
+        if workspace.grid_filter.has_errors && report_is_stale {
+            let names = self.report.glyphs_with_errors(workspace);
+            let checked = (workspace.font.clone(), workspace.sessions.clone());
+            self.with_errors = Some((checked, names));
+        }
+    }
+}
+
+impl WorkspaceId {
+    fn next() -> WorkspaceId {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        WorkspaceId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl AppState {
+    /// A lens into the workspace with the given id.
+    pub fn workspace(id: WorkspaceId) -> impl Lens<AppState, Workspace> {
+        lenses::AppWorkspace(id)
+    }
+
+    /// Add a new workspace, returning its id.
+    pub fn add_workspace(&mut self, workspace: Workspace) -> WorkspaceId {
+        let id = WorkspaceId::next();
+        Arc::make_mut(&mut self.workspaces).insert(id, workspace);
+        id
+    }
+
+    /// Returns a mutable reference to the workspace with the given id.
+    pub fn workspace_mut(&mut self, id: WorkspaceId) -> Option<&mut Workspace> {
+        Arc::make_mut(&mut self.workspaces).get_mut(&id)
+    }
+
+    /// Returns the workspace that owns a window, either as its main window
+    /// or as an editor.
+    pub fn workspace_for_window(&self, window: WindowId) -> Option<WorkspaceId> {
+        self.workspaces
+            .iter()
+            .find(|(_, ws)| ws.owns_window(window))
+            .map(|(id, _)| *id)
+    }
+
+    /// Returns the workspace for the font at `path`, if it is open.
+    pub fn workspace_for_path(&self, path: &Path) -> Option<WorkspaceId> {
+        self.workspaces
+            .iter()
+            .find(|(_, ws)| ws.font.path.as_deref() == Some(path))
+            .map(|(id, _)| *id)
+    }
+
+    /// Warn the user if a newly opened font has components that can't be
+    /// drawn.
+    pub fn warn_about_broken_components(&mut self, id: WorkspaceId) {
+        let broken = match self.workspaces.get(&id) {
+            Some(workspace) => workspace.broken_components(),
+            None => return,
+        };
+        if broken.is_empty() {
+            return;
+        }
+        let details = broken
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let message = format!(
+            "{} broken components; see Glyph > QA Report for details",
+            broken.len()
+        );
+        self.notify(
+            Notification::warning(message)
+                .with_details(details)
+                .for_workspace(id),
+        );
+    }
+
+    /// Log the result of building composites, and warn about any recipes
+    /// that were skipped.
+    pub(crate) fn notify_composites_built(&mut self, id: WorkspaceId, report: &ConstructionReport) {
+        log::info!(
+            "built composites: {} created, {} updated",
+            report.created.len(),
+            report.updated.len()
+        );
+        if !report.skipped.is_empty() {
+            let message = format!(
+                "Built {} composites; {} recipes were skipped",
+                report.built(),
+                report.skipped.len()
+            );
+            self.notify(
+                Notification::warning(message)
+                    .with_details(report)
+                    .for_workspace(id),
+            );
+        }
+    }
+
+    /// Show a notification to the user.
+    pub fn notify(&mut self, notification: Notification) {
+        match notification.kind {
+            NotificationKind::Warning => log::warn!("{}", notification),
+            NotificationKind::Error => log::error!("{}", notification),
+        }
+        Arc::make_mut(&mut self.notifications).push(notification);
+    }
+
+    /// The notifications shown in the window of a font, oldest first.
+    pub fn notifications_for(&self, id: WorkspaceId) -> impl Iterator<Item = &Notification> {
+        self.notifications
+            .iter()
+            .filter(move |n| n.is_shown_for(id))
+    }
+
+    /// Remove the most recent notification shown in the window of a font,
+    /// returning it.
+    pub fn dismiss_notification(&mut self, id: WorkspaceId) -> Option<Notification> {
+        let idx = self
+            .notifications
+            .iter()
+            .rposition(|n| n.is_shown_for(id))?;
+        Some(Arc::make_mut(&mut self.notifications).remove(idx))
+    }
+}
+
+impl Notification {
+    pub fn error(message: impl Into<Arc<str>>) -> Self {
+        Notification {
+            kind: NotificationKind::Error,
+            message: message.into(),
+            details: None,
+            retry: None,
+            workspace: None,
+        }
+    }
+
+    pub fn warning(message: impl Into<Arc<str>>) -> Self {
+        Notification {
+            kind: NotificationKind::Warning,
+            ..Notification::error(message)
+        }
+    }
+
+    /// Builder-style method to add details, such as the underlying error.
+    pub fn with_details(mut self, details: impl std::fmt::Display) -> Self {
+        self.details = Some(details.to_string().into());
+        self
+    }
+
+    /// Builder-style method to add a command that retries the failed operation.
+    pub fn with_retry(mut self, retry: impl Into<Command>) -> Self {
+        self.retry = Some(retry.into());
+        self
+    }
+
+    /// Builder-style method to set the font this notification is about.
+    pub fn for_workspace(mut self, id: WorkspaceId) -> Self {
+        self.workspace = Some(id);
+        self
+    }
+
+    /// `true` if this should be shown in the window of the given font.
+    fn is_shown_for(&self, id: WorkspaceId) -> bool {
+        self.workspace.map(|ws| ws == id).unwrap_or(true)
+    }
+}
+
+impl std::fmt::Display for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(details) = &self.details {
+            write!(f, ": '{}'", details)?;
+        }
+        Ok(())
+    }
+}
+
 impl Workspace {
     /// a lens into a particular editor view.
     pub(crate) fn editor_state(id: SessionId) -> impl Lens<Workspace, EditorState> {
-        lenses::EditorState(id)
-    }
-
-    /// a lens for getting a `GridGlyph`.
-    pub(crate) fn glyph_grid(name: GlyphName) -> impl Lens<Workspace, Option<GridGlyph>> {
-        lenses::GridGlyph(name)
+        lenses::EditorState(id, Default::default())
     }
 
     /// A lens or the currently selected glyph
@@ -130,8 +465,10 @@ impl Workspace {
             placeholder: Arc::new(placeholder_outline()),
         };
         self.font = obj.into();
+        self.history = Default::default();
         self.info = SimpleFontInfo::from_font(&self.font);
         self.build_path_cache();
+        self.mark_saved();
     }
 
     fn build_path_cache(&mut self) {
@@ -151,26 +488,545 @@ impl Workspace {
         });
     }
 
+    /// Save the font.
+    ///
+    /// If the font is being saved to the location it was loaded from, only
+    /// the files that have changed are written; otherwise the whole font is
+    /// written, and anything already at that location is moved to a backup.
     pub fn save(&mut self) -> Result<(), Box<dyn Error>> {
+        let path = match self.font.path.clone() {
+            Some(path) => path,
+            None => return Err("save called with no path set".into()),
+        };
+
         let font_obj = Arc::make_mut(&mut self.font);
         font_obj.update_info(&self.info);
-        if let Some(path) = font_obj.path.as_ref() {
-            backup_ufo_at_path(path)?;
+        // flush all open sessions; a session whose glyph was deleted is no
+        // longer in the session map, and is skipped.
+        let open_sessions = &self.sessions;
+        let sessions = self
+            .session_map
+            .values()
+            .flat_map(|id| open_sessions.get(id));
+        for session in sessions {
+            font_obj
+                .ufo
+                .get_default_layer_mut()
+                .unwrap()
+                .insert_glyph(session.glyph.clone());
+        }
+
+        if self.saved.path.as_ref() == Some(&path) && path.exists() {
+            let changed: Vec<_> = self.iter_modified_glyphs().collect();
+            log::info!("saving {} changed glyphs to {:?}", changed.len(), path);
+            crate::save::save_changes(&self.font.ufo, &path, &changed, &self.saved)?;
+        } else {
+            backup_ufo_at_path(&path)?;
             log::info!("saving to {:?}", path);
-            // flush all open sessions
-            for session in self.sessions.values() {
-                let glyph = session.to_norad_glyph();
-                font_obj
+            self.font.ufo.save(&path)?;
+        }
+        self.mark_saved();
+        Ok(())
+    }
+
+    /// Save the font to a new location.
+    ///
+    /// The font only moves to `path` if the save succeeds, so that a failed
+    /// save doesn't redirect later saves.
+    pub fn save_as(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let previous = self.font_mut().path.replace(path.into());
+        let result = self.save();
+        if result.is_err() {
+            self.font_mut().path = previous;
+        }
+        result
+    }
+
+    /// Record the current state of the font as being the state on disk.
+    fn mark_saved(&mut self) {
+        let ufo = &self.font.ufo;
+        let glyphs = ufo
+            .iter_names()
+            .filter_map(|name| ufo.get_glyph(&name).map(|glyph| (name, glyph.clone())))
+            .collect();
+        self.saved = Arc::new(SavedState {
+            path: self.font.path.clone(),
+            glyphs,
+            info: self.info.clone(),
+            font_info: ufo.font_info.clone(),
+            lib: ufo.lib.clone(),
+            groups: ufo.groups.clone(),
+            kerning: ufo.kerning.clone(),
+        });
+    }
+
+    /// Returns the most recent version of a glyph; this is the glyph in the
+    /// editor, if the glyph is open, and otherwise the glyph in the font.
+    fn current_glyph(&self, name: &GlyphName) -> Option<&Arc<Glyph>> {
+        self.session_map
+            .get(name)
+            .and_then(|id| self.sessions.get(id))
+            .map(|session| &session.glyph)
+            .or_else(|| self.font.ufo.get_glyph(name))
+    }
+
+    /// Returns `true` if this glyph differs from the last saved version.
+    ///
+    /// A glyph that has been added or removed since the last save is
+    /// considered modified.
+    pub fn glyph_is_modified(&self, name: &GlyphName) -> bool {
+        match (self.current_glyph(name), self.saved.glyphs.get(name)) {
+            (Some(current), Some(saved)) => !Arc::ptr_eq(current, saved) && current != saved,
+            (None, None) => false,
+            _ => true,
+        }
+    }
+
+    /// Iterate the names of all glyphs that have been added, removed, or
+    /// changed since the last save.
+    pub fn iter_modified_glyphs(&self) -> impl Iterator<Item = GlyphName> + '_ {
+        let removed = self
+            .saved
+            .glyphs
+            .keys()
+            .filter(move |name| self.font.ufo.get_glyph(*name).is_none())
+            .cloned();
+        self.font
+            .ufo
+            .iter_names()
+            .filter(move |name| self.glyph_is_modified(name))
+            .chain(removed)
+    }
+
+    /// Returns `true` if there are changes that have not been saved.
+    pub fn is_modified(&self) -> bool {
+        self.info != self.saved.info
+            || self.font.ufo.lib != self.saved.lib
+            || self.font.ufo.groups != self.saved.groups
+            || self.font.ufo.kerning != self.saved.kerning
+            || self.iter_modified_glyphs().next().is_some()
+    }
+
+    /// Like `is_modified`, but reusing the answer in `cache` until the font,
+    /// its open glyphs, its info or its saved state change.
+    pub fn is_modified_cached(&self, cache: &mut ModifiedCache) -> bool {
+        let is_current = match &cache.checked {
+            Some(((font, sessions), saved, info)) => {
+                Arc::ptr_eq(font, &self.font)
+                    && Arc::ptr_eq(sessions, &self.sessions)
+                    && Arc::ptr_eq(saved, &self.saved)
+                    && info == &self.info
+            }
+            None => false,
+        };
+        if !is_current {
+            cache.is_modified = self.is_modified();
+            cache.checked = Some((
+                (self.font.clone(), self.sessions.clone()),
+                self.saved.clone(),
+                self.info.clone(),
+            ));
+        }
+        cache.is_modified
+    }
+
+    /// Update the workspace after the files at `paths` were modified on disk.
+    ///
+    /// Glyphs are only reloaded if they don't have unsaved changes; otherwise
+    /// they are reported as conflicts. Only the default layer is reloaded;
+    /// we don't edit other layers, such as `glyphs.background`, so changes
+    /// to them are ignored.
+    pub fn reload_changed_files(&mut self, paths: &[PathBuf]) -> ReloadReport {
+        let mut report = ReloadReport::default();
+        let root = match self.font.path.clone() {
+            Some(path) => path,
+            None => return report,
+        };
+
+        let mut reload_contents = false;
+        let mut reload_info = false;
+        let mut reload_plists = false;
+        for path in paths {
+            let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+            let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+            let in_default_layer = path
+                .parent()
+                .and_then(Path::file_name)
+                .map(|dir| dir == "glyphs")
+                .unwrap_or(false);
+            match (file_name, extension) {
+                ("contents.plist", _) | (_, "glif") if !in_default_layer => (),
+                ("contents.plist", _) => reload_contents = true,
+                // a removed glyph; we'll notice when we reread contents.plist
+                (_, "glif") if !path.exists() => reload_contents = true,
+                (_, "glif") => match Glyph::load(path) {
+                    Ok(glyph) => self.reload_glyph(glyph, &mut report),
+                    Err(e) => report
+                        .errors
+                        .push(format!("failed to reload glyph {:?}: '{:?}'", path, e)),
+                },
+                ("fontinfo.plist", _) => reload_info = true,
+                ("lib.plist", _) | ("groups.plist", _) | ("kerning.plist", _) => {
+                    reload_plists = true
+                }
+                _ => (),
+            }
+        }
+
+        if reload_contents {
+            if let Err(e) = self.reload_glyph_contents(&root, &mut report) {
+                report
+                    .errors
+                    .push(format!("failed to reload glyph list: '{}'", e));
+            }
+        }
+        if reload_info {
+            if let Err(e) = self.reload_font_info(&root, &mut report) {
+                report
+                    .errors
+                    .push(format!("failed to reload fontinfo.plist: '{}'", e));
+            }
+        }
+        if reload_plists {
+            if let Err(e) = self.reload_plists(&root, &mut report) {
+                report
+                    .errors
+                    .push(format!("failed to reload font plists: '{}'", e));
+            }
+        }
+        report
+    }
+
+    /// Replace a glyph with a version loaded from disk, unless there are
+    /// unsaved changes.
+    fn reload_glyph(&mut self, glyph: Glyph, report: &mut ReloadReport) {
+        let name = glyph.name.clone();
+        if self.saved.glyphs.get(&name).map(|g| **g == glyph) == Some(true) {
+            // this is the version we already know about, probably because we
+            // just saved it.
+            return;
+        }
+        if self.glyph_is_modified(&name) {
+            report.conflicts.push(name);
+            return;
+        }
+
+        let glyph = Arc::new(glyph);
+        Arc::make_mut(&mut self.saved)
+            .glyphs
+            .insert(name.clone(), glyph.clone());
+        self.font_mut()
+            .ufo
+            .get_default_layer_mut()
+            .unwrap()
+            .insert_glyph(glyph);
+        self.reload_session(&name);
+        report.reloaded.push(name);
+    }
+
+    /// Add and remove glyphs to match the glyph list on disk.
+    fn reload_glyph_contents(
+        &mut self,
+        root: &Path,
+        report: &mut ReloadReport,
+    ) -> Result<(), Box<dyn Error>> {
+        let glyph_dir = root.join("glyphs");
+        let contents = plist::Value::from_file(glyph_dir.join("contents.plist"))?
+            .into_dictionary()
+            .ok_or("contents.plist is not a dictionary")?;
+
+        let removed: Vec<GlyphName> = self
+            .font
+            .ufo
+            .iter_names()
+            .filter(|name| !contents.contains_key(name))
+            .collect();
+        for name in removed {
+            // glyphs added here since the last save were never on disk.
+            if !self.saved.glyphs.contains_key(&name) {
+                continue;
+            }
+            if self.glyph_is_modified(&name) {
+                report.conflicts.push(name);
+                continue;
+            }
+            Arc::make_mut(&mut self.saved).glyphs.remove(&name);
+            self.font_mut()
+                .ufo
+                .get_default_layer_mut()
+                .unwrap()
+                .remove_glyph(&name);
+            self.invalidate_path(&name);
+            report.reloaded.push(name);
+        }
+
+        for (name, file_name) in contents.iter() {
+            if self.font.ufo.get_glyph(name.as_str()).is_some() {
+                continue;
+            }
+            let file_name = match file_name.as_string() {
+                Some(file_name) => file_name,
+                None => continue,
+            };
+            match Glyph::load(glyph_dir.join(file_name)) {
+                Ok(glyph) => self.reload_glyph(glyph, report),
+                Err(e) => report
+                    .errors
+                    .push(format!("failed to load new glyph '{}': '{:?}'", name, e)),
+            }
+        }
+        Ok(())
+    }
+
+    fn reload_font_info(
+        &mut self,
+        root: &Path,
+        report: &mut ReloadReport,
+    ) -> Result<(), Box<dyn Error>> {
+        let font_info: FontInfo = plist::from_file(root.join("fontinfo.plist"))?;
+        if self.font.ufo.font_info.as_ref() == Some(&font_info) {
+            return Ok(());
+        }
+        if self.info != self.saved.info {
+            report.info_conflict = true;
+            return Ok(());
+        }
+        self.font_mut().ufo.font_info = Some(font_info.clone());
+        self.info = SimpleFontInfo::from_font(&self.font);
+        let saved = Arc::make_mut(&mut self.saved);
+        saved.info = self.info.clone();
+        saved.font_info = Some(font_info);
+        Ok(())
+    }
+
+    /// Reload the lib, groups and kerning from disk.
+    ///
+    /// As with glyphs, each of these is only replaced if we don't have
+    /// unsaved changes to it, such as a new glyph order; otherwise its file
+    /// is reported as a conflict.
+    fn reload_plists(
+        &mut self,
+        root: &Path,
+        report: &mut ReloadReport,
+    ) -> Result<(), Box<dyn Error>> {
+        let on_disk = FontPlists::load(root)?;
+        let Workspace { font, saved, .. } = self;
+        let ufo = &mut Arc::make_mut(font).ufo;
+        let saved = Arc::make_mut(saved);
+        if !reload_plist(&mut ufo.lib, &mut saved.lib, on_disk.lib) {
+            report.plist_conflicts.push("lib.plist");
+        }
+        if !reload_plist(&mut ufo.groups, &mut saved.groups, on_disk.groups) {
+            report.plist_conflicts.push("groups.plist");
+        }
+        if !reload_plist(&mut ufo.kerning, &mut saved.kerning, on_disk.kerning) {
+            report.plist_conflicts.push("kerning.plist");
+        }
+        Ok(())
+    }
+
+    /// If there is an open session for this glyph, update it to match the
+    /// font, and rebuild the affected beziers.
+    fn reload_session(&mut self, name: &GlyphName) {
+        if let Some(id) = self.session_map.get(name).copied() {
+            let mut session = self.sessions.get(&id).cloned().unwrap();
+            Arc::make_mut(&mut session).reload(self);
+            Arc::make_mut(&mut self.sessions).insert(id, session);
+        }
+        self.invalidate_path(name);
+    }
+
+    /// Returns `true` if this window is this workspace's main window or one
+    /// of its editor windows.
+    pub fn owns_window(&self, window: WindowId) -> bool {
+        self.main_window == Some(window) || self.open_glyphs.values().any(|id| *id == window)
+    }
+
+    /// Copy the selected glyphs, along with any glyphs they use as components.
+    pub fn copy_selected_glyphs(&self) -> Option<CopiedGlyphs> {
+        let glyphs: Vec<Arc<Glyph>> = self
+            .selection
+            .iter()
+            .filter_map(|name| self.current_glyph(name).cloned())
+            .collect();
+        if glyphs.is_empty() {
+            return None;
+        }
+        let mut component_bases = Vec::new();
+        let mut to_visit: Vec<GlyphName> = glyphs.iter().flat_map(|g| component_names(g)).collect();
+        while let Some(name) = to_visit.pop() {
+            if self.selection.contains(&name)
+                || component_bases.iter().any(|g: &Arc<Glyph>| g.name == name)
+            {
+                continue;
+            }
+            if let Some(base) = self.current_glyph(&name) {
+                to_visit.extend(component_names(base));
+                component_bases.push(base.clone());
+            }
+        }
+        Some(CopiedGlyphs {
+            glyphs,
+            component_bases,
+        })
+    }
+
+    /// Add copied glyphs to this font.
+    ///
+    /// If a glyph with the same name already exists, the pasted glyph is
+    /// given a new name. Pasted glyphs that are renamed, or whose codepoints
+    /// are already encoded, are not encoded. Component bases are only added
+    /// if they are missing. Returns the names of the pasted glyphs.
+    pub fn paste_glyphs(&mut self, copied: &CopiedGlyphs) -> Vec<GlyphName> {
+        let mut encoded = self.encoded_chars();
+        let mut pasted = Vec::new();
+        for glyph in &copied.glyphs {
+            let name = self.unique_glyph_name(&glyph.name, "copy");
+            let mut glyph = glyph.clone();
+            let is_encoded = glyph
+                .codepoints
+                .iter()
+                .flatten()
+                .any(|c| encoded.contains(c));
+            if name != glyph.name || is_encoded {
+                let glyph = Arc::make_mut(&mut glyph);
+                glyph.name = name.clone();
+                // codepoints should be unique in a font.
+                glyph.codepoints = None;
+            }
+            encoded.extend(glyph.codepoints.iter().flatten().copied());
+            self.font_mut()
+                .ufo
+                .get_default_layer_mut()
+                .unwrap()
+                .insert_glyph(glyph);
+            pasted.push(name);
+        }
+
+        for base in &copied.component_bases {
+            if self.font.ufo.get_glyph(&base.name).is_none() {
+                self.font_mut()
                     .ufo
                     .get_default_layer_mut()
                     .unwrap()
-                    .insert_glyph(glyph);
+                    .insert_glyph(base.clone());
             }
-            font_obj.ufo.save(&path)?;
-        } else {
-            log::error!("save called with no path set");
         }
-        Ok(())
+        self.build_path_cache();
+        pasted
+    }
+
+    /// Duplicate the named glyphs, with their outlines, components, anchors,
+    /// guides and metrics, naming each copy with `suffix`, so that `a`
+    /// becomes `a.ss01`.
+    ///
+    /// Copies are not encoded. A component whose base also has a copy with
+    /// this suffix, either made here or already in the font, uses that copy
+    /// instead; duplicating `A` and `Aacute` together gives an `Aacute.ss01`
+    /// built from `A.ss01`. If the font has a glyph order, each copy follows
+    /// its original. Returns the names of the copies.
+    pub fn duplicate_glyphs(&mut self, names: &[GlyphName], suffix: &str) -> Vec<GlyphName> {
+        let suffix = suffix.trim_start_matches('.');
+        let mut copies: Vec<(GlyphName, GlyphName)> = Vec::new();
+        for name in names {
+            let mut glyph = match self.current_glyph(name) {
+                Some(glyph) => glyph.clone(),
+                None => continue,
+            };
+            let copy_name = self.unique_glyph_name(name, suffix);
+            {
+                let glyph = Arc::make_mut(&mut glyph);
+                glyph.name = copy_name.clone();
+                // codepoints should be unique in a font.
+                glyph.codepoints = None;
+            }
+            self.font_mut()
+                .ufo
+                .get_default_layer_mut()
+                .unwrap()
+                .insert_glyph(glyph);
+            copies.push((name.clone(), copy_name));
+        }
+
+        for (_, copy_name) in &copies {
+            let bases: Vec<_> = self
+                .font
+                .ufo
+                .get_glyph(copy_name)
+                .map(|glyph| component_names(glyph).collect())
+                .unwrap_or_default();
+            let new_bases: Vec<_> = bases
+                .iter()
+                .map(|base| {
+                    copies
+                        .iter()
+                        .find(|(name, _)| name == base)
+                        .map(|(_, copy)| copy.clone())
+                        .or_else(|| {
+                            let candidate = format!("{}.{}", base, suffix);
+                            self.font
+                                .ufo
+                                .get_glyph(candidate.as_str())
+                                .map(|_| candidate.into())
+                        })
+                        .unwrap_or_else(|| base.clone())
+                })
+                .collect();
+            if new_bases != bases {
+                let glyph = glyph_mut(&mut self.font_mut().ufo, copy_name).unwrap();
+                let outline = glyph.outline.as_mut().unwrap();
+                for (component, base) in outline.components.iter_mut().zip(new_bases) {
+                    component.base = base;
+                }
+            }
+        }
+
+        if self.has_glyph_order() && !copies.is_empty() {
+            let order = self
+                .glyph_order()
+                .into_iter()
+                .filter(|name| !copies.iter().any(|(_, copy)| copy == name))
+                .flat_map(|name| {
+                    let copy = copies
+                        .iter()
+                        .find(|(original, _)| *original == name)
+                        .map(|(_, copy)| copy.clone());
+                    std::iter::once(name).chain(copy)
+                })
+                .collect();
+            self.set_glyph_order(order);
+        }
+        self.build_path_cache();
+        copies.into_iter().map(|(_, copy)| copy).collect()
+    }
+
+    /// The first stylistic set suffix, like `ss01`, that no glyph uses.
+    pub fn next_stylistic_set(&self) -> String {
+        let used = |set: &str| {
+            self.font
+                .ufo
+                .iter_names()
+                .any(|name| name.split('.').skip(1).any(|part| part == set))
+        };
+        (1..)
+            .map(|n| format!("ss{:02}", n))
+            .find(|set| !used(set))
+            .unwrap()
+    }
+
+    /// Returns `name` if no glyph with that name exists, and otherwise adds
+    /// a suffix (and possibly a counter) until the name is unique.
+    fn unique_glyph_name(&self, name: &GlyphName, suffix: &str) -> GlyphName {
+        if self.font.ufo.get_glyph(name).is_none() {
+            return name.clone();
+        }
+        let mut candidate = format!("{}.{}", name, suffix);
+        let mut counter = 0;
+        while self.font.ufo.get_glyph(candidate.as_str()).is_some() {
+            counter += 1;
+            candidate = format!("{}.{}.{}", name, suffix, counter);
+        }
+        candidate.into()
     }
 
     pub fn get_or_create_session(&mut self, glyph_name: &GlyphName) -> Arc<EditSession> {
@@ -191,6 +1047,280 @@ impl Workspace {
         self.cache.get(name)
     }
 
+    /// Returns `true` if the resolved outlines may differ from those in `other`.
+    pub(crate) fn beziers_changed(&self, other: &Workspace) -> bool {
+        !self.cache.same(&other.cache)
+    }
+
+    /// The current version of a glyph, as a plain reference, for the
+    /// functions in `component` that resolve glyphs by name.
+    fn resolve_glyph(&self, name: &GlyphName) -> Option<&Glyph> {
+        self.current_glyph(name).map(|glyph| &**glyph)
+    }
+
+    /// Check whether `base` can be used as a component in `glyph`.
+    pub fn component_problem(
+        &self,
+        glyph: &GlyphName,
+        base: &GlyphName,
+    ) -> Option<ComponentProblem> {
+        crate::component::check_component(glyph, base, |name| self.resolve_glyph(name))
+    }
+
+    /// The bounds of `base` when it is used as a component.
+    ///
+    /// If the base is missing this is the bounds of the placeholder we draw
+    /// in its place.
+    pub(crate) fn component_base_bounds(&self, base: &GlyphName) -> Rect {
+        match self.get_bezier(base) {
+            Some(bez) => bez.bounding_box(),
+            None => broken_component_placeholder().bounding_box(),
+        }
+    }
+
+    /// The outline of `component`, as paths, with its transform applied.
+    pub(crate) fn decompose_component(&self, component: &Component) -> Vec<crate::path::Path> {
+        crate::component::decomposed_paths(&component.base, component.transform, &|name| {
+            self.resolve_glyph(name)
+        })
+    }
+
+    /// Where to place `base` as a component of `glyph`, so that its attaching
+    /// anchor lines up with the matching anchor in `glyph`.
+    ///
+    /// Returns `None` if there are no matching anchors.
+    pub(crate) fn component_placement(
+        &self,
+        glyph: &Glyph,
+        base: &GlyphName,
+        anchor: Option<&str>,
+    ) -> Option<Vec2> {
+        let base = self.current_glyph(base)?;
+        let anchors = crate::component::glyph_anchors(glyph, &|name| self.resolve_glyph(name));
+        crate::component::attachment_offset(&anchors, base, anchor)
+    }
+
+    /// Create or update composite glyphs from `recipes`, in order.
+    ///
+    /// Glyphs that already have contours, or that are open in an editor,
+    /// are left alone.
+    pub fn build_composites(&mut self, recipes: &[Recipe]) -> ConstructionReport {
+        let mut report = ConstructionReport::default();
+        for recipe in recipes {
+            let name = &recipe.name;
+            let existing = self.font.ufo.get_glyph(name).cloned();
+            let problem = if self.session_map.contains_key(name) {
+                Some(RecipeProblem::GlyphIsOpen)
+            } else if existing
+                .as_ref()
+                .and_then(|g| g.outline.as_ref())
+                .map(|ol| !ol.contours.is_empty())
+                .unwrap_or(false)
+            {
+                Some(RecipeProblem::HasContours)
+            } else if recipe
+                .parts()
+                .any(|part| self.component_problem(name, part) == Some(ComponentProblem::Cycle))
+            {
+                Some(RecipeProblem::Cycle)
+            } else {
+                None
+            };
+            let built = match problem {
+                Some(problem) => Err(problem),
+                None => recipe.build(&|name| self.resolve_glyph(name)),
+            };
+            let (components, advance) = match built {
+                Ok(built) => built,
+                Err(problem) => {
+                    report.skipped.push((name.clone(), problem));
+                    continue;
+                }
+            };
+
+            let is_new = existing.is_none();
+            let mut glyph = existing.unwrap_or_else(|| Arc::new(Glyph::new_named(name.clone())));
+            {
+                let glyph = Arc::make_mut(&mut glyph);
+                glyph.outline = Some(norad::glyph::Outline {
+                    contours: Vec::new(),
+                    components,
+                });
+                glyph.advance = advance;
+                if glyph.codepoints.is_none() {
+                    glyph.codepoints = crate::glyph_names::codepoints_for_glyph(name);
+                }
+            }
+            self.font_mut()
+                .ufo
+                .get_default_layer_mut()
+                .unwrap()
+                .insert_glyph(glyph);
+            if is_new {
+                report.created.push(name.clone());
+            } else {
+                report.updated.push(name.clone());
+            }
+        }
+        if report.built() > 0 {
+            self.build_path_cache();
+        }
+        report
+    }
+
+    /// Build each of the standard recipes whose parts all exist in this font.
+    pub fn build_standard_composites(&mut self) -> ConstructionReport {
+        let recipes: Vec<_> = crate::construction::standard_recipes()
+            .into_iter()
+            .filter(|recipe| {
+                recipe
+                    .parts()
+                    .all(|part| self.current_glyph(part).is_some())
+            })
+            .collect();
+        self.build_composites(&recipes)
+    }
+
+    /// Replace the components in each of the named glyphs with their outlines.
+    ///
+    /// Components that can't be drawn are left in place. Returns the number
+    /// of components that were decomposed.
+    ///
+    /// Glyphs that are open in an editor should be decomposed there instead,
+    /// with `DECOMPOSE_GLYPH`, so that the change can be undone.
+    pub fn decompose_glyphs(&mut self, names: &[GlyphName]) -> usize {
+        names
+            .iter()
+            .map(|name| self.decompose_glyph(name, None))
+            .sum()
+    }
+
+    /// Decompose the components in `name`; if `base` is given, only the
+    /// components using that glyph are decomposed.
+    fn decompose_glyph(&mut self, name: &GlyphName, base: Option<&GlyphName>) -> usize {
+        let matches = |c: &GlyphName| base.map(|b| b == c).unwrap_or(true);
+        // open glyphs are decomposed in their session, so the editor sees the change
+        if let Some(id) = self.session_map.get(name).copied() {
+            let mut session = self.sessions.get(&id).cloned().unwrap();
+            let ids: Vec<_> = session
+                .components
+                .iter()
+                .filter(|c| matches(&c.base))
+                .map(|c| c.id)
+                .collect();
+            let decomposed = Arc::make_mut(&mut session).decompose_components(&ids, self);
+            if decomposed > 0 {
+                Arc::make_mut(&mut session).rebuild_glyph();
+                Arc::make_mut(&mut self.sessions).insert(id, session);
+                self.invalidate_path(name);
+            }
+            return decomposed;
+        }
+
+        let glyph = match self.font.ufo.get_glyph(name) {
+            Some(glyph) => glyph.clone(),
+            None => return 0,
+        };
+        let outline = match glyph.outline.as_ref() {
+            Some(outline) if !outline.components.is_empty() => outline,
+            _ => return 0,
+        };
+        let mut contours = outline.contours.clone();
+        let mut components = Vec::new();
+        for norad_comp in outline.components.iter() {
+            if !matches(&norad_comp.base)
+                || self.component_problem(name, &norad_comp.base).is_some()
+            {
+                components.push(norad_comp.clone());
+                continue;
+            }
+            let component = Component::from_norad(norad_comp);
+            let paths = self.decompose_component(&component);
+            contours.extend(paths.iter().map(crate::path::Path::to_norad));
+        }
+        let count = outline.components.len() - components.len();
+        if count == 0 {
+            return 0;
+        }
+
+        let mut glyph = glyph;
+        Arc::make_mut(&mut glyph).outline = Some(norad::glyph::Outline {
+            contours,
+            components,
+        });
+        self.font_mut()
+            .ufo
+            .get_default_layer_mut()
+            .unwrap()
+            .insert_glyph(glyph);
+        self.invalidate_path(name);
+        count
+    }
+
+    /// The glyphs that use `name` directly as a component.
+    pub fn glyph_dependents(&self, name: &GlyphName) -> Vec<GlyphName> {
+        let mut dependents: Vec<_> = self
+            .font
+            .ufo
+            .iter_names()
+            .filter(|user| {
+                self.current_glyph(user)
+                    .and_then(|g| g.outline.as_ref())
+                    .map(|ol| ol.components.iter().any(|c| c.base == *name))
+                    .unwrap_or(false)
+            })
+            .collect();
+        dependents.sort();
+        dependents
+    }
+
+    /// Delete a glyph, after decomposing it in the glyphs that use it.
+    ///
+    /// The glyph is also removed from groups, kerning and the glyph order.
+    pub fn delete_glyph(&mut self, name: &GlyphName) -> Option<Arc<Glyph>> {
+        self.font.ufo.get_glyph(name)?;
+        for user in self.glyph_dependents(name) {
+            self.decompose_glyph(&user, Some(name));
+        }
+
+        let font = self.font_mut();
+        let removed = font.ufo.get_default_layer_mut().unwrap().remove_glyph(name);
+        remove_references(&mut font.ufo, name);
+        if self.selected.as_ref() == Some(name) {
+            self.selected = None;
+        }
+        if self.selection.contains(name) {
+            Arc::make_mut(&mut self.selection).remove(name);
+        }
+        // the session is left in place until its editor window closes.
+        Arc::make_mut(&mut self.session_map).remove(name);
+        self.build_path_cache();
+        removed
+    }
+
+    /// All of the components in the font that can't be drawn.
+    pub fn broken_components(&self) -> Vec<BrokenComponent> {
+        let mut names: Vec<_> = self.font.ufo.iter_names().collect();
+        names.sort();
+        let mut broken = Vec::new();
+        for name in names {
+            let glyph = match self.current_glyph(&name) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            for component in glyph.outline.iter().flat_map(|ol| ol.components.iter()) {
+                if let Some(problem) = self.component_problem(&name, &component.base) {
+                    broken.push(BrokenComponent {
+                        glyph: name.clone(),
+                        base: component.base.clone(),
+                        problem,
+                    });
+                }
+            }
+        }
+        broken
+    }
+
     /// After a glyph is edited this rebuilds the affected beziers.
     pub(crate) fn invalidate_path(&mut self, name: &GlyphName) {
         let Workspace {
@@ -211,6 +1341,276 @@ impl Workspace {
         }
     }
 
+    /// Select a single glyph, or clear the selection.
+    pub fn select_only(&mut self, name: Option<GlyphName>) {
+        self.selection = Arc::new(name.iter().cloned().collect());
+        self.selected = name;
+    }
+
+    /// Select `names`, with the first as the primary selection; an empty
+    /// list clears the selection.
+    pub fn select_glyphs(&mut self, names: &[GlyphName]) {
+        self.selection = Arc::new(names.iter().cloned().collect());
+        self.selected = names.first().cloned();
+    }
+
+    /// The names of all the selected glyphs.
+    pub fn selected_glyphs(&self) -> Vec<GlyphName> {
+        self.selection.iter().cloned().collect()
+    }
+
+    /// The names of all glyphs, in the order given by the font's
+    /// `public.glyphOrder`.
+    ///
+    /// Glyphs missing from the glyph order come after the others.
+    pub fn glyph_order(&self) -> Vec<GlyphName> {
+        let ufo = &self.font.ufo;
+        let listed = ufo
+            .lib
+            .as_ref()
+            .and_then(|lib| lib.get(GLYPH_ORDER_KEY))
+            .and_then(plist::Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut seen = BTreeSet::new();
+        let mut order: Vec<GlyphName> = listed
+            .iter()
+            .filter_map(plist::Value::as_string)
+            .filter(|name| ufo.get_glyph(*name).is_some())
+            .map(GlyphName::from)
+            .filter(|name| seen.insert(name.clone()))
+            .collect();
+        order.extend(ufo.iter_names().filter(|name| !seen.contains(name)));
+        order
+    }
+
+    /// Move the named glyphs, keeping their relative order, so that they
+    /// come before `before` in the glyph order, or at the end if it is `None`.
+    ///
+    /// The new order is stored in the font's `public.glyphOrder`.
+    pub fn move_glyphs(&mut self, names: &[GlyphName], before: Option<&GlyphName>) {
+        let (moving, mut order): (Vec<_>, Vec<_>) = self
+            .glyph_order()
+            .into_iter()
+            .partition(|name| names.contains(name));
+        let idx = before
+            .and_then(|before| order.iter().position(|name| name == before))
+            .unwrap_or(order.len());
+        order.splice(idx..idx, moving);
+        self.set_glyph_order(order);
+    }
+
+    /// Returns `true` if the font has a `public.glyphOrder`.
+    fn has_glyph_order(&self) -> bool {
+        self.font
+            .ufo
+            .lib
+            .as_ref()
+            .map(|lib| lib.contains_key(GLYPH_ORDER_KEY))
+            .unwrap_or(false)
+    }
+
+    /// Store `order` in the font's `public.glyphOrder`.
+    fn set_glyph_order(&mut self, order: Vec<GlyphName>) {
+        let order = order
+            .into_iter()
+            .map(|name| plist::Value::String(name.to_string()))
+            .collect();
+        self.font_mut()
+            .ufo
+            .lib
+            .get_or_insert_with(plist::Dictionary::new)
+            .insert(GLYPH_ORDER_KEY.into(), plist::Value::Array(order));
+    }
+
+    /// The glyphs shown in the glyph grid, after applying `grid_filter` and
+    /// `grid_sort`.
+    ///
+    /// If the search is not valid, such as a malformed regular expression,
+    /// no glyphs are shown. Unencoded glyphs are sorted by the character
+    /// their name stands for, so that `a.sc` comes after `a`.
+    pub fn filtered_glyph_names(&self) -> Vec<GlyphName> {
+        self.filtered_glyph_names_cached(&mut GridFilterCache::default())
+    }
+
+    /// Like `filtered_glyph_names`, but reusing the parsed search and the
+    /// QA report in `cache` while they are still current.
+    pub fn filtered_glyph_names_cached(&self, cache: &mut GridFilterCache) -> Vec<GlyphName> {
+        let names = self.glyph_order().into_iter();
+        let names: Vec<_> = if self.grid_filter.is_empty() {
+            names.collect()
+        } else {
+            cache.update(self);
+            self.filter_glyphs(names, cache)
+        };
+        if self.grid_sort == GridSort::GlyphOrder {
+            return names;
+        }
+
+        let mut glyphs: Vec<_> = names
+            .into_iter()
+            .map(|name| {
+                let codepoint = self
+                    .current_glyph(&name)
+                    .and_then(|glyph| glyph.codepoints.as_ref())
+                    .and_then(|cps| cps.first().copied())
+                    .or_else(|| crate::glyph_names::sort_char_for_glyph(&name));
+                (name, codepoint)
+            })
+            .collect();
+        self.grid_sort.sort(&mut glyphs);
+        glyphs.into_iter().map(|(name, _)| name).collect()
+    }
+
+    fn filter_glyphs(
+        &self,
+        names: impl Iterator<Item = GlyphName>,
+        cache: &GridFilterCache,
+    ) -> Vec<GlyphName> {
+        let filter = &self.grid_filter;
+        let query = match cache.query.as_ref().map(|(_, query)| query) {
+            Some(Ok(query)) => query,
+            Some(Err(e)) => {
+                log::debug!("invalid glyph search: {}", e);
+                return Vec::new();
+            }
+            None => return Vec::new(),
+        };
+        let with_errors = cache.with_errors.as_ref().map(|(_, names)| names);
+
+        names
+            .filter(|name| {
+                let glyph = match self.current_glyph(name) {
+                    Some(glyph) => glyph,
+                    None => return false,
+                };
+                let codepoints = glyph.codepoints.as_deref().unwrap_or_default();
+                let (contours, components) = glyph
+                    .outline
+                    .as_ref()
+                    .map(|ol| (ol.contours.len(), ol.components.len()))
+                    .unwrap_or_default();
+                let tags = if query.uses_tags() {
+                    crate::glyph_lib::tags(glyph)
+                } else {
+                    Vec::new()
+                };
+                query.matches(name, codepoints, &tags)
+                    && (!filter.empty || contours + components == 0)
+                    && (!filter.composites || components > 0)
+                    && (!filter.has_errors
+                        || with_errors.map(|e| e.contains(&**name)).unwrap_or(false))
+                    && (!filter.modified || self.glyph_is_modified(name))
+            })
+            .collect()
+    }
+
+    /// The state of a single cell in the glyph grid.
+    pub(crate) fn grid_glyph(&self, name: &GlyphName) -> GridGlyph {
+        let glyph = self.current_glyph(name);
+        GridGlyph {
+            name: name.clone(),
+            is_placeholder: self.get_bezier(name).is_none(),
+            is_selected: self.selection.contains(name),
+            is_modified: self.glyph_is_modified(name),
+            mark_color: glyph.and_then(|glyph| crate::glyph_lib::mark_color(glyph)),
+            codepoint: glyph
+                .and_then(|glyph| glyph.codepoints.as_ref())
+                .and_then(|cps| cps.first().copied()),
+            advance: glyph
+                .and_then(|glyph| glyph.advance.as_ref())
+                .map(|advance| advance.width as f64)
+                .unwrap_or_default(),
+            component_count: glyph
+                .and_then(|glyph| glyph.outline.as_ref())
+                .map(|outline| outline.components.len())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Modify the named glyph's metadata, such as its advance or lib, both
+    /// in the font and in its editor, if it is open.
+    fn edit_glyph_metadata(&mut self, name: &GlyphName, f: impl Fn(&mut Glyph)) {
+        if let Some(id) = self.session_map.get(name).copied() {
+            let sessions = Arc::make_mut(&mut self.sessions);
+            if let Some(session) = sessions.get_mut(&id) {
+                f(Arc::make_mut(&mut Arc::make_mut(session).glyph));
+            }
+        }
+        if let Some(glyph) = glyph_mut(&mut self.font_mut().ufo, name) {
+            f(glyph);
+        }
+    }
+
+    /// The glyphs outside of `names` that use any of them as a component.
+    pub fn glyphs_dependents(&self, names: &[GlyphName]) -> Vec<GlyphName> {
+        let dependents: BTreeSet<_> = names
+            .iter()
+            .flat_map(|name| self.glyph_dependents(name))
+            .filter(|user| !names.contains(user))
+            .collect();
+        dependents.into_iter().collect()
+    }
+
+    /// Delete each of the named glyphs; see `delete_glyph`.
+    pub fn delete_glyphs(&mut self, names: &[GlyphName]) -> usize {
+        names
+            .iter()
+            .filter(|name| self.delete_glyph(name).is_some())
+            .count()
+    }
+
+    /// Set the advance width of each of the named glyphs.
+    ///
+    /// Glyphs that are open in an editor should be changed there instead,
+    /// with `SET_GLYPH_ADVANCE`, so that the change can be undone.
+    pub fn set_advance_width(&mut self, names: &[GlyphName], width: f64) {
+        for name in names {
+            self.edit_glyph_metadata(name, |glyph| {
+                let mut advance = glyph.advance.clone().unwrap_or_default();
+                advance.width = width as f32;
+                glyph.advance = Some(advance);
+            });
+        }
+    }
+
+    /// Set or clear the mark color of each of the named glyphs.
+    pub fn set_mark_color(&mut self, names: &[GlyphName], color: Option<&Color>) {
+        for name in names {
+            self.edit_glyph_metadata(name, |glyph| crate::glyph_lib::set_mark_color(glyph, color));
+        }
+    }
+
+    /// Apply `affine` to the outlines, components and anchors of each of
+    /// the named glyphs.
+    ///
+    /// The transform is relative to the center of each glyph's bounds, so
+    /// that scaling or rotating leaves the glyph in place. Glyphs that are
+    /// open in an editor should be transformed there instead, with
+    /// `TRANSFORM_GLYPH`, so that the change can be undone.
+    pub fn transform_glyphs(&mut self, names: &[GlyphName], affine: Affine) {
+        for name in names {
+            let center = match self.get_bezier(name) {
+                Some(bez) => bez.bounding_box().center(),
+                None => continue,
+            };
+            let around_center =
+                Affine::translate(center.to_vec2()) * affine * Affine::translate(-center.to_vec2());
+
+            if let Some(id) = self.session_map.get(name).copied() {
+                let sessions = Arc::make_mut(&mut self.sessions);
+                if let Some(session) = sessions.get_mut(&id) {
+                    let session = Arc::make_mut(session);
+                    session.transform_all(affine, DPoint::from_raw(center));
+                    session.rebuild_glyph();
+                }
+            } else if let Some(glyph) = glyph_mut(&mut self.font_mut().ufo, name) {
+                transform_glyph(glyph, around_center);
+            }
+            self.invalidate_path(name);
+        }
+    }
+
     /// Returns the upm for this font.
     ///
     /// This is needed to correctly scale the points in the glyph.
@@ -242,64 +1642,271 @@ impl Workspace {
         name
     }
 
-    pub fn delete_selected_glyph(&mut self) -> Option<Arc<Glyph>> {
-        self.selected.take().and_then(|name| {
+    /// Add an empty glyph for each of `chars`, named with its default glyph
+    /// name and encoded with its codepoint.
+    ///
+    /// Characters that are already encoded, or whose name is already taken,
+    /// are skipped. Returns the names of the new glyphs.
+    pub fn add_glyphs_for_chars(&mut self, chars: &[char]) -> Vec<GlyphName> {
+        let glyphs: Vec<_> = chars.iter().copied().map(GlyphSpec::for_char).collect();
+        self.add_glyphs(&glyphs)
+    }
+
+    /// Every character that a glyph in the font is encoded with.
+    fn encoded_chars(&self) -> HashSet<char> {
+        self.font
+            .ufo
+            .iter_names()
+            .filter_map(|name| self.font.ufo.get_glyph(&name))
+            .filter_map(|glyph| glyph.codepoints.as_ref())
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Add an empty glyph for each of `glyphs`.
+    ///
+    /// Glyphs whose name is already taken, or with a codepoint that is
+    /// already encoded, are skipped. If the font has a glyph order, the new
+    /// glyphs are added to the end of it. Returns the names of the new glyphs.
+    pub fn add_glyphs(&mut self, glyphs: &[GlyphSpec]) -> Vec<GlyphName> {
+        let mut encoded = self.encoded_chars();
+
+        let mut added = Vec::new();
+        for spec in glyphs {
+            if spec.codepoints.iter().any(|c| encoded.contains(c))
+                || added.contains(&spec.name)
+                || self.font.ufo.get_glyph(&spec.name).is_some()
+            {
+                continue;
+            }
+            let mut glyph = norad::Glyph::new_named(spec.name.clone());
+            if !spec.codepoints.is_empty() {
+                glyph.codepoints = Some(spec.codepoints.clone());
+                encoded.extend(spec.codepoints.iter().copied());
+            }
             self.font_mut()
                 .ufo
                 .get_default_layer_mut()
                 .unwrap()
-                .remove_glyph(&name)
-        })
+                .insert_glyph(glyph);
+            added.push(spec.name.clone());
+        }
+        if added.is_empty() {
+            return added;
+        }
+
+        if self.has_glyph_order() {
+            let mut order = self.glyph_order();
+            order.retain(|name| !added.contains(name));
+            order.extend(added.iter().cloned());
+            self.set_glyph_order(order);
+        }
+        self.build_path_cache();
+        added
     }
 
     /// Rename a glyph everywhere it might be.
+    ///
+    /// This includes components in other glyphs, groups, kerning and the
+    /// glyph order; the whole rename can be undone with `undo_font_edit`.
     pub fn rename_glyph(&mut self, old_name: GlyphName, new_name: GlyphName) {
-        let font = self.font_mut();
-        let mut glyph = match font
+        if !self.can_rename(&old_name, &new_name) {
+            return;
+        }
+        let old_codepoints = self
+            .font
             .ufo
-            .get_default_layer_mut()
+            .get_glyph(&old_name)
             .unwrap()
-            .remove_glyph(&old_name)
-        {
-            Some(g) => g,
-            None => {
-                log::warn!("attempted to rename missing glyph '{}'", old_name);
-                return;
+            .codepoints
+            .clone();
+        let new_codepoints = crate::glyph_names::codepoints_for_glyph(&new_name);
+        let old_references = self.references();
+        self.rename_glyph_impl(&old_name, &new_name, new_codepoints.clone());
+        let new_references = self.references();
+        Arc::make_mut(&mut self.history).push(FontEdit::Rename {
+            old: old_name,
+            new: new_name,
+            old_codepoints,
+            new_codepoints,
+            old_references,
+            new_references,
+        });
+    }
+
+    /// `true` if there is a glyph named `old_name`, and no glyph named
+    /// `new_name`; otherwise a warning is logged.
+    fn can_rename(&self, old_name: &GlyphName, new_name: &GlyphName) -> bool {
+        if self.font.ufo.get_glyph(old_name).is_none() {
+            log::warn!("attempted to rename missing glyph '{}'", old_name);
+            false
+        } else if self.font.ufo.get_glyph(new_name).is_some() {
+            log::warn!("cannot rename '{}': '{}' exists", old_name, new_name);
+            false
+        } else {
+            true
+        }
+    }
+
+    /// The groups, kerning and glyph order, which a rename rewrites.
+    fn references(&self) -> References {
+        let ufo = &self.font.ufo;
+        References {
+            groups: ufo.groups.clone(),
+            kerning: ufo.kerning.clone(),
+            glyph_order: ufo
+                .lib
+                .as_ref()
+                .and_then(|lib| lib.get(GLYPH_ORDER_KEY))
+                .cloned(),
+        }
+    }
+
+    /// Replace each of the groups, kerning and glyph order with its version
+    /// in `restored`, if it had not changed from `expected` before the rename
+    /// was reversed, when it was `previous`.
+    ///
+    /// Anything that had changed, such as kerning for a deleted glyph being
+    /// removed, is left with its references renamed back.
+    fn restore_references(
+        &mut self,
+        previous: &References,
+        expected: &References,
+        restored: &References,
+    ) {
+        let ufo = &mut self.font_mut().ufo;
+        if previous.groups == expected.groups {
+            ufo.groups = restored.groups.clone();
+        }
+        if previous.kerning == expected.kerning {
+            ufo.kerning = restored.kerning.clone();
+        }
+        if previous.glyph_order == expected.glyph_order {
+            match restored.glyph_order.clone() {
+                Some(order) => {
+                    ufo.lib
+                        .get_or_insert_with(plist::Dictionary::new)
+                        .insert(GLYPH_ORDER_KEY.into(), order);
+                }
+                None => {
+                    if let Some(lib) = ufo.lib.as_mut() {
+                        lib.remove(GLYPH_ORDER_KEY);
+                    }
+                }
             }
+        }
+    }
+
+    fn rename_glyph_impl(
+        &mut self,
+        old_name: &GlyphName,
+        new_name: &GlyphName,
+        codepoints: Option<Vec<char>>,
+    ) {
+        let font = self.font_mut();
+        let layer = font.ufo.get_default_layer_mut().unwrap();
+        let mut glyph = match layer.remove_glyph(old_name) {
+            Some(g) => g,
+            None => return,
         };
 
         {
             let glyph = Arc::make_mut(&mut glyph);
-            glyph.codepoints = crate::glyph_names::codepoints_for_glyph(&new_name);
+            glyph.codepoints = codepoints.clone();
             glyph.name = new_name.clone();
         }
-
-        font.ufo
-            .get_default_layer_mut()
-            .unwrap()
-            .insert_glyph(glyph);
+        layer.insert_glyph(glyph);
+        rename_references(&mut font.ufo, old_name, new_name);
 
         // and if this is the selected glyph, change that too;
-        if self.selected.as_ref() == Some(&old_name) {
+        if self.selected.as_ref() == Some(old_name) {
             self.selected = Some(new_name.clone())
         }
+        if self.selection.contains(old_name) {
+            let selection = Arc::make_mut(&mut self.selection);
+            selection.remove(old_name);
+            selection.insert(new_name.clone());
+        }
 
         // if this glyph is open, rename that too;
-        if self.session_map.contains_key(&old_name) {
+        if self.session_map.contains_key(old_name) {
             let session_map = Arc::make_mut(&mut self.session_map);
-            let session_id = session_map.remove(&old_name).unwrap();
+            let session_id = session_map.remove(old_name).unwrap();
             session_map.insert(new_name.clone(), session_id);
 
             let sessions = Arc::make_mut(&mut self.sessions);
-            let mut session = sessions.get_mut(&session_id).unwrap();
-            Arc::make_mut(&mut session).rename(new_name.clone());
+            let session = sessions.get_mut(&session_id).unwrap();
+            Arc::make_mut(session).rename(new_name.clone(), codepoints);
+        }
+
+        // open glyphs that use this one as a component
+        for session in Arc::make_mut(&mut self.sessions).values_mut() {
+            if session.components.iter().any(|c| c.base == *old_name) {
+                Arc::make_mut(session).rename_component_base(old_name, new_name);
+            }
         }
 
-        if self.open_glyphs.contains_key(&old_name) {
+        if self.open_glyphs.contains_key(old_name) {
             let open = Arc::make_mut(&mut self.open_glyphs);
-            let window = open.remove(&old_name).unwrap();
-            open.insert(new_name, window);
+            let window = open.remove(old_name).unwrap();
+            open.insert(new_name.clone(), window);
+        }
+        self.build_path_cache();
+    }
+
+    /// Undo the most recent edit to the font as a whole, such as a rename.
+    ///
+    /// Returns `false` if there was nothing to undo, or if the edit can't be
+    /// undone because the font has changed; the history is then left alone.
+    pub fn undo_font_edit(&mut self) -> bool {
+        match self.history.next_undo().cloned() {
+            Some(FontEdit::Rename {
+                old,
+                new,
+                old_codepoints,
+                old_references,
+                new_references,
+                ..
+            }) => {
+                if !self.can_rename(&new, &old) {
+                    return false;
+                }
+                let previous = self.references();
+                self.rename_glyph_impl(&new, &old, old_codepoints);
+                self.restore_references(&previous, &new_references, &old_references);
+            }
+            None => return false,
+        }
+        Arc::make_mut(&mut self.history).undo();
+        true
+    }
+
+    /// Redo the most recently undone edit to the font.
+    ///
+    /// Returns `false` if there was nothing to redo, or if the edit can't be
+    /// redone because the font has changed; the history is then left alone.
+    pub fn redo_font_edit(&mut self) -> bool {
+        match self.history.next_redo().cloned() {
+            Some(FontEdit::Rename {
+                old,
+                new,
+                new_codepoints,
+                old_references,
+                new_references,
+                ..
+            }) => {
+                if !self.can_rename(&old, &new) {
+                    return false;
+                }
+                let previous = self.references();
+                self.rename_glyph_impl(&old, &new, new_codepoints);
+                self.restore_references(&previous, &old_references, &new_references);
+            }
+            None => return false,
         }
+        Arc::make_mut(&mut self.history).redo();
+        true
     }
 
     pub fn update_glyph_metadata(&mut self, changed: &Arc<Glyph>) {
@@ -311,8 +1918,9 @@ impl Workspace {
             session.update_glyph_metadata(changed);
         }
         // update the UFO;
-        if let Some(glyph) = self.font_mut().ufo.get_glyph_mut(&changed.name) {
+        if let Some(glyph) = glyph_mut(&mut self.font_mut().ufo, &changed.name) {
             glyph.advance = changed.advance.clone();
+            glyph.lib = changed.lib.clone();
         }
     }
 
@@ -329,6 +1937,12 @@ impl GlyphDetail {
     /// A lens for the glyph's advance.
     pub const advance: lenses::Advance = lenses::Advance;
 
+    /// A lens for the glyph's mark color.
+    pub const mark_color: lenses::MarkColor = lenses::MarkColor;
+
+    /// A lens for the glyph's tags, separated by commas.
+    pub const tags: lenses::Tags = lenses::Tags;
+
     /// A lens for the glyph's name.
     pub const glyph_name: lenses::GlyphName = lenses::GlyphName;
 
@@ -390,6 +2004,73 @@ impl EditorState {
         Arc::make_mut(&mut self.session)
     }
 
+    /// The problems found in the session's outlines.
+    ///
+    /// The checks are only rerun when the paths have changed.
+    pub fn issues(&self) -> Arc<Vec<Issue>> {
+        self.issue_cache.borrow_mut().issues(&self.session.paths)
+    }
+
+    /// Decompose the selected components, or all components if none are
+    /// selected. Returns the number of components that were decomposed.
+    pub(crate) fn decompose_components(&mut self) -> usize {
+        let ids: Vec<EntityId> = self
+            .session
+            .selection
+            .iter()
+            .copied()
+            .filter(|id| id.is_component())
+            .collect();
+        if ids.is_empty() {
+            return self.decompose_all_components();
+        }
+        let EditorState { font, session, .. } = self;
+        Arc::make_mut(session).decompose_components(&ids, font)
+    }
+
+    /// Decompose all of the session's components. Returns the number of
+    /// components that were decomposed.
+    pub(crate) fn decompose_all_components(&mut self) -> usize {
+        let ids: Vec<EntityId> = self.session.components.iter().map(|c| c.id).collect();
+        if ids.is_empty() {
+            return 0;
+        }
+        let EditorState { font, session, .. } = self;
+        Arc::make_mut(session).decompose_components(&ids, font)
+    }
+
+    /// Apply `affine` to the whole glyph, around the center of its bounds.
+    pub(crate) fn transform_glyph(&mut self, affine: Affine) {
+        if let Some(bez) = self.font.get_bezier(&self.session.name) {
+            let center = bez.bounding_box().center();
+            self.session_mut()
+                .transform_all(affine, DPoint::from_raw(center));
+        }
+    }
+
+    /// Update the cached bounds of the session's components, if their base
+    /// glyphs have changed.
+    pub(crate) fn refresh_component_bounds(&mut self) {
+        let bounds: Vec<Rect> = self
+            .session
+            .components
+            .iter()
+            .map(|comp| self.font.component_base_bounds(&comp.base))
+            .collect();
+        let is_stale = self
+            .session
+            .components
+            .iter()
+            .zip(bounds.iter())
+            .any(|(comp, bounds)| comp.base_bounds != *bounds);
+        if is_stale {
+            let components = self.session_mut().components_mut();
+            for (comp, bounds) in components.iter_mut().zip(bounds) {
+                comp.base_bounds = bounds;
+            }
+        }
+    }
+
     fn compute_sidebearings(&self) -> Sidebearings {
         let content_region = self
             .font
@@ -407,6 +2088,11 @@ impl EditorState {
 }
 
 impl FontObject {
+    /// The outline drawn for glyphs that have no contours or components.
+    pub(crate) fn placeholder(&self) -> &Arc<BezPath> {
+        &self.placeholder
+    }
+
     /// Update the actual `FontInfo` from the generated `SimpleFontInfo`
     #[allow(clippy::float_cmp)]
     fn update_info(&mut self, info: &SimpleFontInfo) {
@@ -443,6 +2129,66 @@ impl FontObject {
     }
 }
 
+/// The lib, groups and kerning of a UFO.
+struct FontPlists {
+    lib: Option<plist::Dictionary>,
+    groups: Option<BTreeMap<String, Vec<GlyphName>>>,
+    kerning: Option<BTreeMap<String, BTreeMap<String, f32>>>,
+}
+
+impl FontPlists {
+    /// Read the plists from the UFO at `root`; missing files are `None`.
+    fn load(root: &Path) -> Result<Self, Box<dyn Error>> {
+        let lib_path = root.join("lib.plist");
+        let lib = if lib_path.exists() {
+            plist::Value::from_file(lib_path)?.into_dictionary()
+        } else {
+            None
+        };
+
+        let groups_path = root.join("groups.plist");
+        let groups = if groups_path.exists() {
+            let groups: BTreeMap<String, Vec<String>> = plist::from_file(groups_path)?;
+            let groups = groups
+                .into_iter()
+                .map(|(group, names)| (group, names.iter().map(|s| s.as_str().into()).collect()))
+                .collect();
+            Some(groups)
+        } else {
+            None
+        };
+
+        let kerning_path = root.join("kerning.plist");
+        let kerning = if kerning_path.exists() {
+            Some(plist::from_file(kerning_path)?)
+        } else {
+            None
+        };
+        Ok(FontPlists {
+            lib,
+            groups,
+            kerning,
+        })
+    }
+}
+
+/// Replace our copy of a plist, and the saved copy, with the version on disk,
+/// unless our copy has unsaved changes.
+///
+/// Returns `false` if there are unsaved changes that differ from the disk.
+fn reload_plist<T: Clone + PartialEq>(
+    current: &mut Option<T>,
+    saved: &mut Option<T>,
+    on_disk: Option<T>,
+) -> bool {
+    if *current != on_disk && current != saved {
+        return false;
+    }
+    *current = on_disk.clone();
+    *saved = on_disk;
+    true
+}
+
 use std::convert::TryInto;
 
 impl Default for FontObject {
@@ -528,21 +2274,29 @@ impl Default for FontMetrics {
 }
 
 mod lenses {
+    use std::cell::RefCell;
+    use std::rc::Rc;
     use std::sync::Arc;
 
-    use druid::{Data, Lens};
+    use druid::{Color, Data, Lens};
     use norad::GlyphName as GlyphName_;
 
+    use crate::glyph_lib;
+    use crate::qa::IssueCache;
+
     use super::{
-        EditorState as EditorState_, GlyphDetail, GridGlyph as GridGlyph_, SessionId,
-        Sidebearings as Sidebearings_, Workspace,
+        AppState, EditorState as EditorState_, GlyphDetail, SessionId,
+        Sidebearings as Sidebearings_, Workspace, WorkspaceId,
     };
 
-    /// Workspace -> EditorState
-    pub struct EditorState(pub SessionId);
+    /// AppState -> Workspace
+    pub struct AppWorkspace(pub WorkspaceId);
 
-    /// Workspace -> GridGlyph
-    pub struct GridGlyph(pub GlyphName_);
+    /// Workspace -> EditorState
+    ///
+    /// This also holds the editor's issue cache, which outlives the
+    /// `EditorState`s we construct.
+    pub struct EditorState(pub SessionId, pub Rc<RefCell<IssueCache>>);
 
     /// Workspace -> GlyphPlus
     pub struct SelectedGlyph;
@@ -558,8 +2312,41 @@ mod lenses {
 
     pub struct Advance;
 
+    /// GlyphDetail -> Option<Color>
+    pub struct MarkColor;
+
+    /// GlyphDetail -> String
+    pub struct Tags;
+
     pub struct Sidebearings;
 
+    impl Lens<AppState, Workspace> for AppWorkspace {
+        fn with<V, F: FnOnce(&Workspace) -> V>(&self, data: &AppState, f: F) -> V {
+            match data.workspaces.get(&self.0) {
+                Some(workspace) => f(workspace),
+                // this can happen briefly while a font's windows are closing
+                None => f(&Workspace::default()),
+            }
+        }
+
+        fn with_mut<V, F: FnOnce(&mut Workspace) -> V>(&self, data: &mut AppState, f: F) -> V {
+            let mut workspace = match data.workspaces.get(&self.0) {
+                Some(workspace) => workspace.clone(),
+                None => return f(&mut Workspace::default()),
+            };
+            let v = f(&mut workspace);
+            if !data
+                .workspaces
+                .get(&self.0)
+                .map(|ws| ws.same(&workspace))
+                .unwrap_or(true)
+            {
+                Arc::make_mut(&mut data.workspaces).insert(self.0, workspace);
+            }
+            v
+        }
+    }
+
     impl Lens<Workspace, EditorState_> for EditorState {
         fn with<V, F: FnOnce(&EditorState_) -> V>(&self, data: &Workspace, f: F) -> V {
             let metrics = data.info.metrics.clone();
@@ -568,6 +2355,7 @@ mod lenses {
                 font: data.clone(),
                 metrics,
                 session,
+                issue_cache: self.1.clone(),
             };
             f(&glyph)
         }
@@ -580,6 +2368,7 @@ mod lenses {
                 font: data.clone(),
                 metrics,
                 session,
+                issue_cache: self.1.clone(),
             };
             let v = f(&mut glyph);
             if !data
@@ -706,45 +2495,6 @@ mod lenses {
         }
     }
 
-    impl Lens<Workspace, Option<GridGlyph_>> for GridGlyph {
-        fn with<V, F: FnOnce(&Option<GridGlyph_>) -> V>(&self, data: &Workspace, f: F) -> V {
-            let outline = data.get_bezier(&self.0);
-
-            let is_selected = data.selected.as_ref() == Some(&self.0);
-            let glyph = Some(GridGlyph_ {
-                name: self.0.clone(),
-                is_placeholder: outline.is_none(),
-                outline: outline.unwrap_or_else(|| data.font.placeholder.clone()),
-                upm: data.units_per_em(),
-                is_selected,
-            });
-            f(&glyph)
-        }
-
-        fn with_mut<V, F: FnOnce(&mut Option<GridGlyph_>) -> V>(
-            &self,
-            data: &mut Workspace,
-            f: F,
-        ) -> V {
-            let outline = data.get_bezier(&self.0);
-            let is_selected = data.selected.as_ref() == Some(&self.0);
-            let mut glyph = Some(GridGlyph_ {
-                name: self.0.clone(),
-                is_placeholder: outline.is_none(),
-                outline: outline.unwrap_or_else(|| data.font.placeholder.clone()),
-                upm: data.units_per_em(),
-                is_selected,
-            });
-            let r = f(&mut glyph);
-            // we track selections by having the grid item set this flag,
-            // and then we propogate that up to the workspace here.
-            if glyph.as_ref().map(|g| g.is_selected).unwrap_or(false) {
-                data.selected = Some(self.0.clone());
-            }
-            r
-        }
-    }
-
     impl Lens<GlyphDetail, Option<char>> for Codepoint {
         fn with<V, F: FnOnce(&Option<char>) -> V>(&self, data: &GlyphDetail, f: F) -> V {
             let c = data.get_codepoint();
@@ -795,6 +2545,43 @@ mod lenses {
         }
     }
 
+    impl Lens<GlyphDetail, Option<Color>> for MarkColor {
+        fn with<V, F: FnOnce(&Option<Color>) -> V>(&self, data: &GlyphDetail, f: F) -> V {
+            f(&glyph_lib::mark_color(&data.glyph))
+        }
+
+        fn with_mut<V, F: FnOnce(&mut Option<Color>) -> V>(
+            &self,
+            data: &mut GlyphDetail,
+            f: F,
+        ) -> V {
+            let color = glyph_lib::mark_color(&data.glyph);
+            let mut color2 = color.clone();
+            let result = f(&mut color2);
+            if !color2.same(&color) {
+                glyph_lib::set_mark_color(Arc::make_mut(&mut data.glyph), color2.as_ref());
+            }
+            result
+        }
+    }
+
+    impl Lens<GlyphDetail, String> for Tags {
+        fn with<V, F: FnOnce(&String) -> V>(&self, data: &GlyphDetail, f: F) -> V {
+            f(&glyph_lib::tags(&data.glyph).join(", "))
+        }
+
+        fn with_mut<V, F: FnOnce(&mut String) -> V>(&self, data: &mut GlyphDetail, f: F) -> V {
+            let tags = glyph_lib::tags(&data.glyph);
+            let mut text = tags.join(", ");
+            let result = f(&mut text);
+            let tags2 = glyph_lib::parse_tags(&text);
+            if tags2 != tags {
+                glyph_lib::set_tags(Arc::make_mut(&mut data.glyph), &tags2);
+            }
+            result
+        }
+    }
+
     impl Lens<GlyphDetail, GlyphName_> for GlyphName {
         fn with<V, F: FnOnce(&GlyphName_) -> V>(&self, data: &GlyphDetail, f: F) -> V {
             f(&data.glyph.name)
@@ -809,6 +2596,165 @@ mod lenses {
     }
 }
 
+/// Get a glyph in the default layer to modify.
+///
+/// Glyphs are shared with the saved state, and `Ufo::get_glyph_mut` returns
+/// `None` for a shared glyph; this copies the glyph first instead.
+fn glyph_mut<'a>(ufo: &'a mut Ufo, name: &str) -> Option<&'a mut Glyph> {
+    let layer = ufo.get_default_layer_mut()?;
+    if layer.get_glyph_mut(name).is_none() {
+        let mut glyph = layer.get_glyph(name)?.clone();
+        Arc::make_mut(&mut glyph);
+        layer.insert_glyph(glyph);
+    }
+    layer.get_glyph_mut(name)
+}
+
+/// Update everything in `ufo` that refers to a glyph by name, except the
+/// glyph itself: components, groups, kerning and the glyph order.
+///
+/// Only group members are renamed; groups named after the glyph, such as
+/// `public.kern1.A`, keep their names. Kerning that already exists for
+/// `new` is merged with the kerning for `old`, keeping the existing value
+/// where both have a pair.
+fn rename_references(ufo: &mut Ufo, old: &GlyphName, new: &GlyphName) {
+    let users: Vec<_> = ufo
+        .iter_names()
+        .filter(|name| {
+            ufo.get_glyph(name)
+                .and_then(|g| g.outline.as_ref())
+                .map(|ol| ol.components.iter().any(|c| c.base == *old))
+                .unwrap_or(false)
+        })
+        .collect();
+    for name in users {
+        let outline = glyph_mut(ufo, &name).and_then(|g| g.outline.as_mut());
+        for component in outline.into_iter().flat_map(|ol| ol.components.iter_mut()) {
+            if component.base == *old {
+                component.base = new.clone();
+            }
+        }
+    }
+
+    for members in ufo.groups.iter_mut().flat_map(|groups| groups.values_mut()) {
+        for member in members.iter_mut().filter(|m| **m == *old) {
+            *member = new.clone();
+        }
+    }
+
+    if let Some(kerning) = ufo.kerning.as_mut() {
+        if let Some(pairs) = kerning.remove(&**old) {
+            let existing = kerning.entry(new.to_string()).or_default();
+            for (second, value) in pairs {
+                existing.entry(second).or_insert(value);
+            }
+        }
+        for pairs in kerning.values_mut() {
+            if let Some(value) = pairs.remove(&**old) {
+                pairs.entry(new.to_string()).or_insert(value);
+            }
+        }
+    }
+
+    let order = ufo
+        .lib
+        .as_mut()
+        .and_then(|lib| lib.get_mut(GLYPH_ORDER_KEY))
+        .and_then(plist::Value::as_array_mut);
+    for item in order.into_iter().flatten() {
+        if item.as_string() == Some(&**old) {
+            *item = plist::Value::String(new.to_string());
+        }
+    }
+}
+
+/// Apply `affine` to a glyph's contours, components and anchors.
+fn transform_glyph(glyph: &mut Glyph, affine: Affine) {
+    let transform_pt = |x: &mut f32, y: &mut f32| {
+        let pt = affine * Point::new(*x as f64, *y as f64);
+        *x = pt.x as f32;
+        *y = pt.y as f32;
+    };
+    if let Some(outline) = glyph.outline.as_mut() {
+        let points = outline
+            .contours
+            .iter_mut()
+            .flat_map(|c| c.points.iter_mut());
+        for point in points {
+            transform_pt(&mut point.x, &mut point.y);
+        }
+        // a flipped contour winds the other way, so we reverse it back
+        if affine.determinant() < 0.0 {
+            outline.contours.iter_mut().for_each(reverse_contour);
+        }
+        for component in outline.components.iter_mut() {
+            component.transform = (affine * Affine::from(component.transform)).into();
+        }
+    }
+    for anchor in glyph.anchors.iter_mut().flatten() {
+        transform_pt(&mut anchor.x, &mut anchor.y);
+    }
+}
+
+/// Reverse the direction of a contour, keeping its points' names and
+/// identifiers.
+///
+/// A closed contour keeps its first point; an open one starts from its old
+/// end.
+fn reverse_contour(contour: &mut Contour) {
+    let closed = !matches!(contour.points.first(), Some(p) if p.typ == PointType::Move);
+    // each on-curve point's type describes the segment that ends at it, so
+    // after reversing it takes the type of the next on-curve point.
+    let on_curve: Vec<usize> = (0..contour.points.len())
+        .filter(|i| contour.points[*i].typ != PointType::OffCurve)
+        .collect();
+    let types: Vec<PointType> = (0..on_curve.len())
+        .map(|i| match on_curve.get(i + 1) {
+            Some(next) => contour.points[*next].typ.clone(),
+            None if closed => contour.points[on_curve[0]].typ.clone(),
+            None => PointType::Move,
+        })
+        .collect();
+    for (idx, typ) in on_curve.into_iter().zip(types) {
+        contour.points[idx].typ = typ;
+    }
+    contour.points.reverse();
+    if closed {
+        contour.points.rotate_right(1);
+    }
+}
+
+/// Remove a deleted glyph from groups, kerning and the glyph order.
+fn remove_references(ufo: &mut Ufo, name: &GlyphName) {
+    for members in ufo.groups.iter_mut().flat_map(|groups| groups.values_mut()) {
+        members.retain(|member| member != name);
+    }
+
+    if let Some(kerning) = ufo.kerning.as_mut() {
+        kerning.remove(&**name);
+        for pairs in kerning.values_mut() {
+            pairs.remove(&**name);
+        }
+        let empty: Vec<_> = kerning
+            .iter()
+            .filter(|(_, pairs)| pairs.is_empty())
+            .map(|(first, _)| first.clone())
+            .collect();
+        for first in empty {
+            kerning.remove(&first);
+        }
+    }
+
+    let order = ufo
+        .lib
+        .as_mut()
+        .and_then(|lib| lib.get_mut(GLYPH_ORDER_KEY))
+        .and_then(plist::Value::as_array_mut);
+    if let Some(order) = order {
+        order.retain(|item| item.as_string() != Some(&**name));
+    }
+}
+
 //FIXME: put this in some `GlyphExt` trait or something
 /// Convert this glyph's path from the UFO representation into a `kurbo::BezPath`
 /// (which we know how to draw.)
@@ -923,6 +2869,15 @@ fn placeholder_outline() -> BezPath {
     bez
 }
 
+/// The names of the glyphs used as components in this glyph.
+fn component_names(glyph: &Glyph) -> impl Iterator<Item = GlyphName> + '_ {
+    glyph
+        .outline
+        .iter()
+        .flat_map(|o| o.components.iter())
+        .map(|comp| comp.base.clone())
+}
+
 /// Move the contents of the file at `path` to another location.
 ///
 /// If `path` exists, returns the backup location on success.
@@ -979,4 +2934,603 @@ mod tests {
         assert_eq!(font_info.style_name, Some("Extra Cheese".to_string()));
         assert_eq!(font_info.descender, Some(420.0.into()));
     }
+
+    /// An empty directory for a test to save fonts in.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("runebender-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A workspace for an unsaved font with `glyphs`, and a glyph order if
+    /// `order` is given.
+    fn workspace_with_glyphs(glyphs: &[Glyph], order: Option<&[&str]>) -> Workspace {
+        let mut ufo = Ufo::new();
+        let layer = ufo.get_default_layer_mut().unwrap();
+        for glyph in glyphs {
+            layer.insert_glyph(glyph.clone());
+        }
+        if let Some(order) = order {
+            let order = order.iter().map(|name| (*name).into()).collect();
+            let mut lib = plist::Dictionary::new();
+            lib.insert(GLYPH_ORDER_KEY.into(), plist::Value::Array(order));
+            ufo.lib = Some(lib);
+        }
+        let mut workspace = Workspace::default();
+        workspace.set_file(ufo, None);
+        workspace
+    }
+
+    fn empty_glyphs(names: &[&str]) -> Vec<Glyph> {
+        names.iter().map(|name| Glyph::new_named(*name)).collect()
+    }
+
+    fn encoded(name: &str, chr: char) -> Glyph {
+        let mut glyph = Glyph::new_named(name);
+        glyph.codepoints = Some(vec![chr]);
+        glyph
+    }
+
+    /// A glyph with a component for each of `bases`.
+    fn composite(name: &str, bases: &[&str]) -> Glyph {
+        let mut glyph = Glyph::new_named(name);
+        glyph.outline = Some(norad::glyph::Outline {
+            contours: Vec::new(),
+            components: bases
+                .iter()
+                .map(|base| Component::new((*base).into()).to_norad())
+                .collect(),
+        });
+        glyph
+    }
+
+    /// A glyph with a single contour through `points`.
+    fn drawn(name: &str, points: &[(f32, f32)]) -> Glyph {
+        let points = points
+            .iter()
+            .map(|&(x, y)| ContourPoint::new(x, y, PointType::Line, false, None, None, None))
+            .collect();
+        let mut glyph = Glyph::new_named(name);
+        glyph.outline = Some(norad::glyph::Outline {
+            contours: vec![Contour::new(points, None, None)],
+            components: Vec::new(),
+        });
+        glyph
+    }
+
+    #[test]
+    fn notifications_per_workspace() {
+        let mut state = AppState::default();
+        let one = state.add_workspace(Workspace::default());
+        let two = state.add_workspace(Workspace::default());
+        state.notify(Notification::error("everywhere"));
+        state.notify(Notification::error("one").for_workspace(one));
+        state.notify(Notification::error("two").for_workspace(two));
+
+        let messages = |state: &AppState, id| {
+            state
+                .notifications_for(id)
+                .map(|n| n.message.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(messages(&state, one), vec!["everywhere", "one"]);
+        assert_eq!(messages(&state, two), vec!["everywhere", "two"]);
+
+        let dismissed = state.dismiss_notification(one).unwrap();
+        assert_eq!(&*dismissed.message, "one");
+        assert_eq!(messages(&state, two), vec!["everywhere", "two"]);
+        state.dismiss_notification(one);
+        assert!(state.notifications_for(one).next().is_none());
+        assert_eq!(messages(&state, two), vec!["two"]);
+    }
+
+    #[test]
+    fn reload_default_layer_only() {
+        let mut ufo = Ufo::new();
+        let mut a = Glyph::new_named("A");
+        a.advance = Some(norad::glyph::Advance {
+            width: 500.0,
+            height: 0.0,
+        });
+        ufo.get_default_layer_mut().unwrap().insert_glyph(a);
+        let dir = test_dir("reload");
+        let path = dir.join("Test.ufo");
+        ufo.save(&path).unwrap();
+        let mut workspace = Workspace::default();
+        workspace.set_file(ufo, path.clone());
+
+        let glif = fs::read_to_string(path.join("glyphs/A_.glif")).unwrap();
+        let changed = glif.replace("500", "600");
+        let background = path.join("glyphs.background");
+        fs::create_dir(&background).unwrap();
+        fs::write(background.join("A_.glif"), &changed).unwrap();
+        let report = workspace.reload_changed_files(&[background.join("A_.glif")]);
+        assert!(report.reloaded.is_empty());
+        let advance = |ws: &Workspace| ws.font.ufo.get_glyph("A").unwrap().advance.clone();
+        assert_eq!(advance(&workspace).unwrap().width, 500.0);
+
+        fs::write(path.join("glyphs/A_.glif"), &changed).unwrap();
+        let report = workspace.reload_changed_files(&[path.join("glyphs/A_.glif")]);
+        assert_eq!(report.reloaded, vec![GlyphName::from("A")]);
+        assert_eq!(advance(&workspace).unwrap().width, 600.0);
+        assert!(!workspace.is_modified());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reload_plists_keeps_unsaved_edits() {
+        let mut ufo = Ufo::new();
+        ufo.get_default_layer_mut()
+            .unwrap()
+            .insert_glyph(Glyph::new_named("A"));
+        let dir = test_dir("reload-plists");
+        let path = dir.join("Test.ufo");
+        ufo.save(&path).unwrap();
+        let mut workspace = Workspace::default();
+        workspace.set_file(ufo, path.clone());
+
+        let mut groups = BTreeMap::new();
+        groups.insert("public.kern1.A".to_string(), vec![GlyphName::from("A")]);
+        plist::to_file_xml(path.join("groups.plist"), &groups).unwrap();
+        let report = workspace.reload_changed_files(&[path.join("groups.plist")]);
+        assert!(report.plist_conflicts.is_empty());
+        assert_eq!(workspace.font.ufo.groups.as_ref(), Some(&groups));
+        assert!(!workspace.is_modified());
+
+        let mut edited = groups.clone();
+        edited.insert("public.kern2.A".to_string(), vec!["A".into()]);
+        workspace.font_mut().ufo.groups = Some(edited.clone());
+        groups.insert("public.kern1.B".to_string(), vec!["A".into()]);
+        plist::to_file_xml(path.join("groups.plist"), &groups).unwrap();
+        let report = workspace.reload_changed_files(&[path.join("groups.plist")]);
+        assert_eq!(report.plist_conflicts, vec!["groups.plist"]);
+        assert_eq!(workspace.font.ufo.groups, Some(edited));
+        assert!(workspace.is_modified());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_save_as_keeps_path() {
+        let mut workspace = workspace_with_glyphs(&empty_glyphs(&["a"]), None);
+        let dir = test_dir("save-as");
+        // a font can't be saved inside a file
+        let file = dir.join("file");
+        fs::write(&file, "").unwrap();
+        assert!(workspace.save_as(&file.join("Test.ufo")).is_err());
+        assert!(workspace.font.path.is_none());
+
+        let path = dir.join("Test.ufo");
+        workspace.save_as(&path).unwrap();
+        assert_eq!(workspace.font.path.as_deref(), Some(path.as_path()));
+        assert!(!workspace.is_modified());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn update_saved_glyph_metadata() {
+        let mut workspace = workspace_with_glyphs(&empty_glyphs(&["a"]), None);
+        let mut glyph = workspace.font.ufo.get_glyph("a").unwrap().as_ref().clone();
+        glyph.advance = Some(norad::glyph::Advance {
+            width: 500.0,
+            height: 0.0,
+        });
+        workspace.update_glyph_metadata(&Arc::new(glyph));
+        let glyph = workspace.font.ufo.get_glyph("a").unwrap();
+        assert_eq!(glyph.advance.as_ref().unwrap().width, 500.0);
+        assert!(workspace.glyph_is_modified(&"a".into()));
+    }
+
+    #[test]
+    fn modified_cache() {
+        let mut workspace = workspace_with_glyphs(&empty_glyphs(&["a"]), None);
+        let mut cache = ModifiedCache::default();
+        assert!(!workspace.is_modified_cached(&mut cache));
+        workspace.add_new_glyph();
+        assert!(workspace.is_modified_cached(&mut cache));
+        workspace.mark_saved();
+        assert!(!workspace.is_modified_cached(&mut cache));
+        workspace.info.family_name = "Renamed".into();
+        assert!(workspace.is_modified_cached(&mut cache));
+    }
+
+    #[test]
+    fn paste_drops_taken_codepoints() {
+        let mut source = workspace_with_glyphs(&[encoded("A", 'A'), encoded("B", 'B')], None);
+        source.select_glyphs(&["A".into(), "B".into()]);
+        let copied = source.copy_selected_glyphs().unwrap();
+
+        let mut workspace = workspace_with_glyphs(&[encoded("uni0041", 'A')], None);
+        let pasted = workspace.paste_glyphs(&copied);
+        assert_eq!(pasted, vec![GlyphName::from("A"), "B".into()]);
+        let codepoints = |name: &str| {
+            workspace
+                .font
+                .ufo
+                .get_glyph(name)
+                .unwrap()
+                .codepoints
+                .clone()
+        };
+        assert_eq!(codepoints("A"), None);
+        assert_eq!(codepoints("B"), Some(vec!['B']));
+        assert_eq!(codepoints("uni0041"), Some(vec!['A']));
+    }
+
+    #[test]
+    fn rename_updates_references() {
+        let glyphs = [encoded("A", 'A'), composite("Aacute", &["A"])];
+        let mut workspace = workspace_with_glyphs(&glyphs, Some(&["A", "Aacute"]));
+        let ufo = &mut workspace.font_mut().ufo;
+        let mut groups = BTreeMap::new();
+        groups.insert(
+            "public.kern1.A".to_string(),
+            vec!["A".into(), "Aacute".into()],
+        );
+        ufo.groups = Some(groups);
+        let mut kerning = BTreeMap::new();
+        let mut pairs = BTreeMap::new();
+        pairs.insert("A".to_string(), -20.0);
+        kerning.insert("A".to_string(), pairs.clone());
+        kerning.insert("V".to_string(), pairs);
+        // left over from a glyph that was deleted outside of runebender
+        let mut stale = BTreeMap::new();
+        stale.insert("A".to_string(), 10.0);
+        stale.insert("O".to_string(), -5.0);
+        kerning.insert("Alpha".to_string(), stale);
+        ufo.kerning = Some(kerning);
+        let original = workspace.references();
+        workspace.rename_glyph("A".into(), "Alpha".into());
+
+        let ufo = &workspace.font.ufo;
+        assert!(ufo.get_glyph("A").is_none());
+        assert_eq!(ufo.get_glyph("Alpha").unwrap().codepoints, Some(vec!['Α']));
+        let aacute = ufo.get_glyph("Aacute").unwrap();
+        assert_eq!(
+            aacute.outline.as_ref().unwrap().components[0].base,
+            "Alpha".into()
+        );
+        let groups = ufo.groups.as_ref().unwrap();
+        assert_eq!(groups["public.kern1.A"][0], "Alpha".into());
+        let kerning = ufo.kerning.as_ref().unwrap();
+        assert!(kerning.get("A").is_none());
+        // existing pairs are kept when merging
+        assert_eq!(kerning["Alpha"]["Alpha"], 10.0);
+        assert_eq!(kerning["Alpha"]["O"], -5.0);
+        assert_eq!(kerning["V"]["Alpha"], -20.0);
+        let order = ufo.lib.as_ref().unwrap().get(GLYPH_ORDER_KEY).unwrap();
+        let order = order.as_array().unwrap();
+        assert_eq!(order[0].as_string(), Some("Alpha"));
+
+        // a rename onto an existing glyph is refused
+        workspace.rename_glyph("Aacute".into(), "Alpha".into());
+        assert!(workspace.font.ufo.get_glyph("Aacute").is_some());
+
+        assert!(workspace.undo_font_edit());
+        let ufo = &workspace.font.ufo;
+        assert_eq!(ufo.get_glyph("A").unwrap().codepoints, Some(vec!['A']));
+        let aacute = ufo.get_glyph("Aacute").unwrap();
+        assert_eq!(
+            aacute.outline.as_ref().unwrap().components[0].base,
+            "A".into()
+        );
+        // the merged kerning is separated again
+        assert_eq!(workspace.references(), original);
+        assert!(!workspace.undo_font_edit());
+
+        assert!(workspace.redo_font_edit());
+        let alpha = workspace.font.ufo.get_glyph("Alpha").unwrap();
+        assert_eq!(alpha.codepoints, Some(vec!['Α']));
+
+        // a glyph that has since taken the old name, such as one reloaded
+        // from disk, blocks the undo.
+        let layer = workspace.font_mut().ufo.get_default_layer_mut().unwrap();
+        layer.insert_glyph(Glyph::new_named("A"));
+        assert!(!workspace.undo_font_edit());
+        assert!(workspace.font.ufo.get_glyph("Alpha").is_some());
+        let layer = workspace.font_mut().ufo.get_default_layer_mut().unwrap();
+        layer.remove_glyph("A");
+        assert!(workspace.undo_font_edit());
+
+        // adding a glyph doesn't affect the history
+        workspace.add_new_glyph();
+        assert!(workspace.redo_font_edit());
+        assert!(workspace.font.ufo.get_glyph("Alpha").is_some());
+
+        // kerning that changed after the rename is renamed back, not restored
+        let kerning = workspace.font_mut().ufo.kerning.as_mut().unwrap();
+        kerning.get_mut("V").unwrap().insert("O".to_string(), -15.0);
+        assert!(workspace.undo_font_edit());
+        let kerning = workspace.font.ufo.kerning.as_ref().unwrap();
+        assert_eq!(kerning["V"]["A"], -20.0);
+        assert_eq!(kerning["V"]["O"], -15.0);
+        assert_eq!(kerning["A"]["A"], 10.0);
+    }
+
+    #[test]
+    fn delete_decomposes_dependents() {
+        let glyphs = [
+            drawn("bar", &[(0.0, 0.0), (0.0, 100.0), (50.0, 100.0)]),
+            composite("double", &["bar", "space"]),
+            Glyph::new_named("space"),
+        ];
+        let mut workspace = workspace_with_glyphs(&glyphs, None);
+        let ufo = &mut workspace.font_mut().ufo;
+        let mut groups = BTreeMap::new();
+        groups.insert(
+            "public.kern1.bar".to_string(),
+            vec!["bar".into(), "double".into()],
+        );
+        ufo.groups = Some(groups);
+        let mut kerning = BTreeMap::new();
+        let mut pairs = BTreeMap::new();
+        pairs.insert("bar".to_string(), -20.0);
+        kerning.insert("bar".to_string(), pairs.clone());
+        kerning.insert("double".to_string(), pairs);
+        ufo.kerning = Some(kerning);
+
+        let bar: GlyphName = "bar".into();
+        assert_eq!(workspace.glyph_dependents(&bar), vec!["double".into()]);
+        assert!(workspace.delete_glyph(&bar).is_some());
+
+        let ufo = &workspace.font.ufo;
+        assert!(ufo.get_glyph("bar").is_none());
+        let outline = ufo.get_glyph("double").unwrap().outline.clone().unwrap();
+        assert_eq!(outline.contours.len(), 1);
+        // only the components using the deleted glyph are decomposed
+        assert_eq!(outline.components.len(), 1);
+        assert_eq!(
+            ufo.groups.as_ref().unwrap()["public.kern1.bar"],
+            vec![GlyphName::from("double")]
+        );
+        assert!(ufo.kerning.as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn reverse_norad_contour() {
+        let point = |x, y, typ| ContourPoint::new(x, y, typ, false, None, None, None);
+        let summary = |contour: &Contour| {
+            contour
+                .points
+                .iter()
+                .map(|p| (p.x, p.y, p.typ.clone()))
+                .collect::<Vec<_>>()
+        };
+        let mut closed = Contour::new(
+            vec![
+                point(0.0, 0.0, PointType::Line),
+                point(0.0, 50.0, PointType::OffCurve),
+                point(50.0, 100.0, PointType::OffCurve),
+                point(100.0, 100.0, PointType::Curve),
+                point(100.0, 0.0, PointType::Line),
+            ],
+            None,
+            None,
+        );
+        reverse_contour(&mut closed);
+        assert_eq!(
+            summary(&closed),
+            vec![
+                (0.0, 0.0, PointType::Curve),
+                (100.0, 0.0, PointType::Line),
+                (100.0, 100.0, PointType::Line),
+                (50.0, 100.0, PointType::OffCurve),
+                (0.0, 50.0, PointType::OffCurve),
+            ]
+        );
+
+        let mut open = Contour::new(
+            vec![
+                point(0.0, 0.0, PointType::Move),
+                point(0.0, 100.0, PointType::Line),
+                point(50.0, 100.0, PointType::OffCurve),
+                point(100.0, 50.0, PointType::OffCurve),
+                point(100.0, 0.0, PointType::Curve),
+            ],
+            None,
+            None,
+        );
+        reverse_contour(&mut open);
+        assert_eq!(
+            summary(&open),
+            vec![
+                (100.0, 0.0, PointType::Move),
+                (100.0, 50.0, PointType::OffCurve),
+                (50.0, 100.0, PointType::OffCurve),
+                (0.0, 100.0, PointType::Curve),
+                (0.0, 0.0, PointType::Line),
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_edit_selection() {
+        let points = [(0.0, 0.0), (0.0, 100.0), (100.0, 100.0)];
+        let glyphs: Vec<_> = ["a", "b", "c"]
+            .iter()
+            .map(|name| drawn(name, &points))
+            .collect();
+        let mut workspace = workspace_with_glyphs(&glyphs, None);
+        workspace.select_only(Some("a".into()));
+        Arc::make_mut(&mut workspace.selection).insert("b".into());
+        let selected = workspace.selected_glyphs();
+        assert_eq!(selected, vec!["a".into(), "b".into()]);
+
+        workspace.transform_glyphs(&selected, Affine::FLIP_X);
+        workspace.set_advance_width(&selected, 320.0);
+        workspace.set_mark_color(&selected, Some(&Color::rgb8(255, 0, 0)));
+
+        let ufo = &workspace.font.ufo;
+        for name in &["a", "b"] {
+            let glyph = ufo.get_glyph(*name).unwrap();
+            // flipped around the center of its bounds
+            let points = &glyph.outline.as_ref().unwrap().contours[0].points;
+            assert_eq!((points[0].x, points[0].y), (100.0, 0.0));
+            // and reversed, so that it winds the same way as before
+            assert_eq!((points[1].x, points[1].y), (0.0, 100.0));
+            assert_eq!(glyph.advance.as_ref().unwrap().width, 320.0);
+            assert!(crate::glyph_lib::mark_color(glyph).is_some());
+        }
+        let c = ufo.get_glyph("c").unwrap();
+        assert_eq!(c.outline.as_ref().unwrap().contours[0].points[0].x, 0.0);
+        assert!(c.advance.is_none());
+
+        workspace.select_glyphs(&["c".into(), "a".into()]);
+        assert_eq!(workspace.selected, Some("c".into()));
+        assert_eq!(workspace.selection.len(), 2);
+        workspace.select_only(None);
+        assert!(workspace.selection.is_empty() && workspace.selected.is_none());
+    }
+
+    #[test]
+    fn reorder_glyphs() {
+        let glyphs: Vec<_> = "abcd".chars().map(|c| encoded(&c.to_string(), c)).collect();
+        let mut workspace = workspace_with_glyphs(&glyphs, Some(&["d", "missing", "b"]));
+        let names = |names: Vec<GlyphName>| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        // listed glyphs first, then the rest
+        assert_eq!(names(workspace.glyph_order()), vec!["d", "b", "a", "c"]);
+        assert_eq!(
+            names(workspace.filtered_glyph_names()),
+            vec!["d", "b", "a", "c"]
+        );
+        workspace.grid_sort = GridSort::Unicode;
+        assert_eq!(
+            names(workspace.filtered_glyph_names()),
+            vec!["a", "b", "c", "d"]
+        );
+
+        assert!(!workspace.is_modified());
+        workspace.move_glyphs(&["a".into(), "c".into()], Some(&"d".into()));
+        assert_eq!(names(workspace.glyph_order()), vec!["a", "c", "d", "b"]);
+        workspace.move_glyphs(&["a".into()], None);
+        assert_eq!(names(workspace.glyph_order()), vec!["c", "d", "b", "a"]);
+        assert!(workspace.is_modified());
+
+        let lib = workspace.font.ufo.lib.as_ref().unwrap();
+        let order = lib.get(GLYPH_ORDER_KEY).and_then(plist::Value::as_array);
+        assert_eq!(order.unwrap().len(), 4);
+    }
+
+    #[test]
+    fn grid_filter_cache() {
+        let glyphs = [Glyph::new_named("a"), encoded("space", ' ')];
+        let mut workspace = workspace_with_glyphs(&glyphs, None);
+        let mut cache = GridFilterCache::default();
+        let names = |ws: &Workspace, cache: &mut GridFilterCache| {
+            let names = ws.filtered_glyph_names_cached(cache);
+            assert_eq!(names, ws.filtered_glyph_names());
+            names.iter().map(|n| n.to_string()).collect::<Vec<_>>()
+        };
+
+        workspace.grid_filter.query = "space".into();
+        assert_eq!(names(&workspace, &mut cache), vec!["space"]);
+        workspace.grid_filter.query = "/[/".into();
+        assert!(names(&workspace, &mut cache).is_empty());
+
+        // an empty 'a' is a problem, but an empty space is not
+        workspace.grid_filter.query.clear();
+        workspace.grid_filter.has_errors = true;
+        assert_eq!(names(&workspace, &mut cache), vec!["a"]);
+        let layer = workspace.font_mut().ufo.get_default_layer_mut().unwrap();
+        layer.insert_glyph(Glyph::new_named("b"));
+        assert_eq!(names(&workspace, &mut cache), vec!["a", "b"]);
+        // and a glyph is checked again when it changes
+        let square = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
+        let mut a = drawn("a", &square);
+        a.codepoints = Some(vec!['a']);
+        let layer = workspace.font_mut().ufo.get_default_layer_mut().unwrap();
+        layer.insert_glyph(a);
+        assert_eq!(names(&workspace, &mut cache), vec!["b"]);
+    }
+
+    #[test]
+    fn add_glyphs_for_chars() {
+        let glyphs = [encoded("a.alt", 'a'), Glyph::new_named("b")];
+        let mut workspace = workspace_with_glyphs(&glyphs, None);
+        let added = workspace.add_glyphs_for_chars(&['a', 'b', 'é', 'б', 'é']);
+        assert_eq!(added, vec![GlyphName::from("eacute"), "uni0431".into()]);
+
+        let ufo = &workspace.font.ufo;
+        assert_eq!(ufo.get_glyph("eacute").unwrap().codepoints, Some(vec!['é']));
+        assert_eq!(
+            ufo.get_glyph("uni0431").unwrap().codepoints,
+            Some(vec!['б'])
+        );
+        assert!(ufo.get_glyph("a").is_none());
+        assert!(ufo.get_glyph("b").unwrap().codepoints.is_none());
+    }
+
+    #[test]
+    fn unicode_sort_uses_glyph_names() {
+        let order = ["b", "a.sc", "uni0431", "newGlyph", "a"];
+        let mut workspace = workspace_with_glyphs(&empty_glyphs(&order), Some(&order));
+        workspace.grid_sort = GridSort::Unicode;
+        let names = workspace.filtered_glyph_names();
+        let names: Vec<_> = names.iter().map(|n| n.to_string()).collect();
+        assert_eq!(names, vec!["a.sc", "a", "b", "uni0431", "newGlyph"]);
+    }
+
+    #[test]
+    fn add_glyphs_to_glyph_order() {
+        let mut workspace = workspace_with_glyphs(&empty_glyphs(&["z"]), Some(&["z"]));
+        let glyphs =
+            crate::charset::parse_list("z c a.sc b", crate::charset::ListKind::GlyphNames).unwrap();
+        let added = workspace.add_glyphs(&glyphs);
+        assert_eq!(added.len(), 3);
+        let names: Vec<_> = workspace
+            .glyph_order()
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(names, vec!["z", "c", "a.sc", "b"]);
+        let ufo = &workspace.font.ufo;
+        assert_eq!(ufo.get_glyph("c").unwrap().codepoints, Some(vec!['c']));
+        assert!(ufo.get_glyph("a.sc").unwrap().codepoints.is_none());
+    }
+
+    #[test]
+    fn duplicate_glyphs() {
+        let mut a = encoded("A", 'A');
+        a.advance = Some(norad::glyph::Advance {
+            width: 600.0,
+            height: 0.0,
+        });
+        let glyphs = [
+            a,
+            Glyph::new_named("acute"),
+            composite("Aacute", &["A", "acute"]),
+        ];
+        let order = ["A", "Aacute", "acute"];
+        let mut workspace = workspace_with_glyphs(&glyphs, Some(&order));
+        assert_eq!(workspace.next_stylistic_set(), "ss01");
+        let copies = workspace.duplicate_glyphs(&["A".into(), "Aacute".into()], ".ss01");
+        assert_eq!(
+            copies,
+            vec![GlyphName::from("A.ss01"), "Aacute.ss01".into()]
+        );
+        assert_eq!(workspace.next_stylistic_set(), "ss02");
+
+        let ufo = &workspace.font.ufo;
+        let copy = ufo.get_glyph("A.ss01").unwrap();
+        assert!(copy.codepoints.is_none());
+        assert_eq!(copy.advance.as_ref().unwrap().width, 600.0);
+        let components = &ufo
+            .get_glyph("Aacute.ss01")
+            .unwrap()
+            .outline
+            .as_ref()
+            .unwrap()
+            .components;
+        assert_eq!(components[0].base, "A.ss01".into());
+        assert_eq!(components[1].base, "acute".into());
+        let names: Vec<_> = workspace
+            .glyph_order()
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(names, vec!["A", "A.ss01", "Aacute", "Aacute.ss01", "acute"]);
+
+        let copies = workspace.duplicate_glyphs(&["A".into()], "ss01");
+        assert_eq!(copies, vec![GlyphName::from("A.ss01.1")]);
+    }
 }
@@ -2,7 +2,7 @@
 
 use std::sync::Arc;
 
-use crate::component::Component;
+use crate::component::{broken_component_placeholder, Component};
 use crate::data::{FontMetrics, Workspace};
 use crate::design_space::ViewPort;
 use crate::edit_session::EditSession;
@@ -10,6 +10,7 @@ use crate::guides::{Guide, GuideLine};
 use crate::path::Path;
 use crate::point::PointType;
 use crate::point_list::Segment;
+use crate::qa::Issue;
 use crate::selection::Selection;
 use crate::theme;
 
@@ -19,6 +20,9 @@ use druid::{Env, PaintCtx};
 
 use norad::Glyph;
 
+/// The radius of the markers drawn at outline problems, in screen space.
+const QA_MARKER_RADIUS: f64 = 9.0;
+
 /// A context for drawing that maps between screen space and design space.
 struct DrawCtx<'a, 'b: 'a> {
     ctx: &'a mut Piet<'b>,
@@ -313,6 +317,27 @@ impl<'a, 'b: 'a> DrawCtx<'a, 'b> {
             self.fill(&*bez, color);
         }
     }
+
+    /// Draw a placeholder for a component whose base is missing, or which
+    /// would contain itself.
+    fn draw_broken_component(&mut self, component: &Component, color: &Color) {
+        let affine = self.space.affine() * component.transform;
+        let bez = affine * broken_component_placeholder();
+        self.fill(&bez, &color.clone().with_alpha(0.25));
+        self.stroke(&bez, color, 1.0);
+    }
+
+    /// Draw a ring around each outline problem.
+    fn draw_issues(&mut self, issues: &[Issue], env: &Env) {
+        let color = env.get(theme::QA_MARKER_COLOR);
+        for issue in issues {
+            let center = self.space.affine() * issue.pos;
+            if !self.visible_rect.contains(center) {
+                continue;
+            }
+            self.stroke(Circle::new(center, QA_MARKER_RADIUS), &color, 2.0);
+        }
+    }
 }
 
 struct PointStyle {
@@ -414,6 +439,7 @@ pub(crate) fn draw_session(
     metrics: &FontMetrics,
     session: &EditSession,
     font: &Workspace,
+    issues: &[Issue],
     is_preview: bool,
 ) {
     let mut draw_ctx = DrawCtx::new(&mut ctx.render_ctx, env, space, visible_rect);
@@ -449,8 +475,20 @@ pub(crate) fn draw_session(
     }
 
     for component in session.components.iter() {
-        draw_ctx.draw_component(component, font, &env.get(theme::COMPONENT_FILL_COLOR));
+        if font
+            .component_problem(&session.name, &component.base)
+            .is_some()
+        {
+            draw_ctx.draw_broken_component(component, &env.get(theme::ERROR_COLOR));
+        } else if session.selection.contains(&component.id) {
+            let color = env.get(theme::SELECTED_COMPONENT_FILL_COLOR);
+            draw_ctx.draw_component(component, font, &color);
+        } else {
+            draw_ctx.draw_component(component, font, &env.get(theme::COMPONENT_FILL_COLOR));
+        }
     }
+
+    draw_ctx.draw_issues(issues, env);
 }
 
 /// Return the tangent of the cubic bezier `cb`, at time `t`, as a vector
@@ -1,12 +1,12 @@
 use std::collections::BTreeSet;
 use std::sync::Arc;
 
-use druid::kurbo::{BezPath, ParamCurveNearest, Point, Rect, Shape, Size, Vec2};
+use druid::kurbo::{Affine, BezPath, ParamCurveNearest, Point, Rect, Shape, Size, Vec2};
 use druid::{Data, Lens};
 use norad::glyph::Outline;
 use norad::{Glyph, GlyphName};
 
-use crate::component::Component;
+use crate::component::{Component, ComponentTransform};
 use crate::data::Workspace;
 use crate::design_space::{DPoint, DVec2, ViewPort};
 use crate::guides::Guide;
@@ -61,6 +61,9 @@ pub struct CoordinateSelection {
     /// the bounding box of the selection
     pub frame: Rect,
     pub quadrant: Quadrant,
+    /// The transform of the selected component, if the selection is a
+    /// single component.
+    pub component: Option<ComponentTransform>,
 }
 
 impl EditSession {
@@ -79,7 +82,16 @@ impl EditSession {
         let components = glyph
             .outline
             .as_ref()
-            .map(|ol| ol.components.iter().map(Component::from_norad).collect())
+            .map(|ol| {
+                ol.components
+                    .iter()
+                    .map(|comp| {
+                        let mut component = Component::from_norad(comp);
+                        component.base_bounds = glyphs.component_base_bounds(&component.base);
+                        component
+                    })
+                    .collect()
+            })
             .unwrap_or_default();
         let guides = glyph
             .guidelines
@@ -107,6 +119,20 @@ impl EditSession {
         }
     }
 
+    /// Replace the contents of this session with the current version of the
+    /// glyph in the workspace, such as after it has been changed on disk.
+    ///
+    /// The session keeps its id and viewport, but the selection is cleared.
+    pub fn reload(&mut self, glyphs: &Workspace) {
+        let new = EditSession::new(&self.name, glyphs);
+        self.glyph = new.glyph;
+        self.paths = new.paths;
+        self.components = new.components;
+        self.guides = new.guides;
+        self.selection = new.selection;
+        self.work_bounds = new.work_bounds;
+    }
+
     /// Construct a bezier of the paths in this glyph, ignoring components.
     pub fn to_bezier(&self) -> BezPath {
         let mut bez = BezPath::new();
@@ -125,15 +151,26 @@ impl EditSession {
     pub fn update_glyph_metadata(&mut self, changed: &Arc<Glyph>) {
         let glyph = Arc::make_mut(&mut self.glyph);
         glyph.advance = changed.advance.clone();
+        glyph.lib = changed.lib.clone();
     }
 
-    pub fn rename(&mut self, name: GlyphName) {
+    pub fn rename(&mut self, name: GlyphName, codepoints: Option<Vec<char>>) {
         self.name = name.clone();
         let glyph = Arc::make_mut(&mut self.glyph);
-        glyph.codepoints = crate::glyph_names::codepoints_for_glyph(&name);
+        glyph.codepoints = codepoints;
         glyph.name = name;
     }
 
+    /// Point components that use the glyph `old` at `new`, after it is renamed.
+    pub fn rename_component_base(&mut self, old: &GlyphName, new: &GlyphName) {
+        for component in Arc::make_mut(&mut self.components).iter_mut() {
+            if component.base == *old {
+                component.base = new.clone();
+            }
+        }
+        self.rebuild_glyph();
+    }
+
     /// Returns the current layout bounds of the 'work', that is, all the things
     /// that are 'part of the glyph'.
     pub fn work_bounds(&self) -> Rect {
@@ -148,6 +185,30 @@ impl EditSession {
         Arc::make_mut(&mut self.components)
     }
 
+    /// Add a component at `offset`, and select it.
+    ///
+    /// The caller is responsible for checking that `base` is a valid
+    /// component for this glyph.
+    pub fn add_component(&mut self, base: GlyphName, offset: DVec2) {
+        let mut component = Component::new(base);
+        component.nudge(offset);
+        self.selection.select_one(component.id);
+        self.components_mut().push(component);
+    }
+
+    /// The selected component, if the selection is exactly one component.
+    pub fn selected_component(&self) -> Option<&Component> {
+        if self.selection.len() != 1 {
+            return None;
+        }
+        let id = *self.selection.iter().next().unwrap();
+        self.components.iter().find(|c| c.id == id)
+    }
+
+    pub(crate) fn component_for_id_mut(&mut self, id: EntityId) -> Option<&mut Component> {
+        self.components_mut().iter_mut().find(|c| c.id == id)
+    }
+
     pub fn guides_mut(&mut self) -> &mut Vec<Guide> {
         Arc::make_mut(&mut self.guides)
     }
@@ -264,6 +325,18 @@ impl EditSession {
         None
     }
 
+    /// Hit test a point against the bounds of the components.
+    ///
+    /// If components overlap, the one drawn last wins.
+    pub fn hit_test_components(&self, point: Point) -> Option<EntityId> {
+        let dpt = self.viewport.from_screen(point).to_raw();
+        self.components
+            .iter()
+            .rev()
+            .find(|c| c.bounds().contains(dpt))
+            .map(|c| c.id)
+    }
+
     /// Return the index of the path that is currently drawing. To be currently
     /// drawing, there must be a single currently selected point.
     fn active_path_idx(&self) -> Option<usize> {
@@ -368,6 +441,9 @@ impl EditSession {
                 path.delete_points(path_points);
             } else if path_points[0].is_guide() {
                 self.guides_mut().retain(|g| !path_points.contains(&g.id));
+            } else if path_points[0].is_component() {
+                self.components_mut()
+                    .retain(|c| !path_points.contains(&c.id));
             }
         }
         self.paths_mut().retain(|p| !p.points().is_empty());
@@ -384,14 +460,20 @@ impl EditSession {
     ///
     /// Will return Rect::ZERO if nothing is selected.
     pub(crate) fn selection_dpoint_bbox(&self) -> Rect {
-        let mut iter = self
-            .selection
-            .iter()
-            .flat_map(|id| self.path_point_for_id(*id).map(|pt| pt.point.to_raw()));
+        let mut iter = self.selection.iter().flat_map(|id| {
+            if id.is_component() {
+                self.components
+                    .iter()
+                    .find(|c| c.id == *id)
+                    .map(Component::bounds)
+            } else {
+                self.path_point_for_id(*id)
+                    .map(|pt| Rect::ZERO.with_origin(pt.point.to_raw()))
+            }
+        });
 
-        let first_point = iter.next().unwrap_or_default();
-        let bbox = Rect::ZERO.with_origin(first_point);
-        iter.fold(bbox, |bb, pt| bb.union_pt(pt))
+        let first = iter.next().unwrap_or_default();
+        iter.fold(first, |bb, rect| bb.union(rect))
     }
 
     /// If the current selection is a single point, select the next point
@@ -456,6 +538,12 @@ impl EditSession {
                         guide.nudge(nudge);
                     }
                 }
+            } else if path_points[0].is_component() {
+                for id in path_points {
+                    if let Some(component) = self.component_for_id_mut(*id) {
+                        component.nudge(nudge);
+                    }
+                }
             }
         }
     }
@@ -469,6 +557,37 @@ impl EditSession {
         }
     }
 
+    /// Apply `affine` to every path, component and anchor, around `anchor`.
+    pub(crate) fn transform_all(&mut self, affine: Affine, anchor: DPoint) {
+        // a flip changes the direction of each contour, so we reverse it back
+        let is_flip = affine.determinant() < 0.0;
+        for path in self.paths_mut() {
+            path.transform_all(affine, anchor);
+            if is_flip {
+                path.reverse_contour();
+            }
+        }
+        for component in self.components_mut() {
+            component.transform_around(affine, anchor);
+        }
+        let around = Affine::translate(anchor.to_raw().to_vec2())
+            * affine
+            * Affine::translate(-anchor.to_raw().to_vec2());
+        let glyph = Arc::make_mut(&mut self.glyph);
+        for glyph_anchor in glyph.anchors.iter_mut().flatten() {
+            let pt = around * Point::new(glyph_anchor.x as f64, glyph_anchor.y as f64);
+            glyph_anchor.x = pt.x as f32;
+            glyph_anchor.y = pt.y as f32;
+        }
+    }
+
+    pub(crate) fn set_advance_width(&mut self, width: f64) {
+        let glyph = Arc::make_mut(&mut self.glyph);
+        let mut advance = glyph.advance.clone().unwrap_or_default();
+        advance.width = width as f32;
+        glyph.advance = Some(advance);
+    }
+
     pub(crate) fn adjust_sidebearing(&mut self, delta: f64, is_left: bool) {
         let glyph = Arc::make_mut(&mut self.glyph);
         if let Some(advance) = glyph.advance.as_mut() {
@@ -493,11 +612,73 @@ impl EditSession {
             for path_points in sel.iter() {
                 if let Some(path) = self.path_for_point_mut(path_points[0]) {
                     path.scale_points(path_points, scale, anchor);
+                } else if path_points[0].is_component() {
+                    let affine = Affine::scale_non_uniform(scale.x, scale.y);
+                    self.transform_components(path_points, affine, anchor);
                 }
             }
         }
     }
 
+    /// Rotate the selection counter-clockwise by `angle` radians around `anchor`.
+    pub(crate) fn rotate_selection(&mut self, angle: f64, anchor: DPoint) {
+        assert!(angle.is_finite());
+        if !self.selection.is_empty() {
+            let sel = self.selection.per_path_selection();
+            for path_points in sel.iter() {
+                if let Some(path) = self.path_for_point_mut(path_points[0]) {
+                    path.rotate_points(path_points, angle, anchor);
+                } else if path_points[0].is_component() {
+                    self.transform_components(path_points, Affine::rotate(angle), anchor);
+                }
+            }
+        }
+    }
+
+    fn transform_components(&mut self, ids: &[EntityId], affine: Affine, anchor: DPoint) {
+        for id in ids {
+            if let Some(component) = self.component_for_id_mut(*id) {
+                component.transform_around(affine, anchor);
+            }
+        }
+    }
+
+    /// Replace the components in `ids` with their outlines, and select the
+    /// new paths.
+    ///
+    /// Components that can't be drawn are left in place. Returns the number
+    /// of components that were decomposed.
+    pub(crate) fn decompose_components(&mut self, ids: &[EntityId], font: &Workspace) -> usize {
+        let (to_decompose, kept): (Vec<_>, Vec<_>) =
+            self.components.iter().cloned().partition(|c| {
+                ids.contains(&c.id) && font.component_problem(&self.name, &c.base).is_none()
+            });
+        if to_decompose.is_empty() {
+            return 0;
+        }
+
+        let paths: Vec<Path> = to_decompose
+            .iter()
+            .flat_map(|c| font.decompose_component(c))
+            .collect();
+        self.selection.clear();
+        self.selection
+            .extend(paths.iter().flat_map(|p| p.points().iter().map(|pt| pt.id)));
+        self.paths_mut().extend(paths);
+        *self.components_mut() = kept;
+        to_decompose.len()
+    }
+
+    /// If a single component is selected, replace its transform with one
+    /// built from `parts`.
+    pub(crate) fn set_selected_component_transform(&mut self, parts: &ComponentTransform) {
+        if let Some(id) = self.selected_component().map(|c| c.id) {
+            if let Some(component) = self.component_for_id_mut(id) {
+                component.set_decomposed_transform(parts);
+            }
+        }
+    }
+
     /// Update an off-curve point in response to a drag.
     ///
     /// `is_locked` corresponds to the shift key being held.
@@ -551,23 +732,15 @@ impl EditSession {
         let guide = match self.selection.len() {
             1 => {
                 let id = *self.selection.iter().next().unwrap();
-                if !id.is_guide() {
-                    let p = self.path_point_for_id(id).map(|pp| pp.point).unwrap();
-                    Some(Guide::horiz(p))
-                } else {
-                    None
-                }
+                self.path_point_for_id(id).map(|pp| Guide::horiz(pp.point))
             }
             2 => {
                 let mut iter = self.selection.iter().cloned();
                 let id1 = iter.next().unwrap();
                 let id2 = iter.next().unwrap();
-                if !id1.is_guide() && !id2.is_guide() {
-                    let p1 = self.path_point_for_id(id1).map(|pp| pp.point).unwrap();
-                    let p2 = self.path_point_for_id(id2).map(|pp| pp.point).unwrap();
-                    Some(Guide::angle(p1, p2))
-                } else {
-                    None
+                match (self.path_point_for_id(id1), self.path_point_for_id(id2)) {
+                    (Some(p1), Some(p2)) => Some(Guide::angle(p1.point, p2.point)),
+                    _ => None,
                 }
             }
             _ => None,
@@ -581,23 +754,27 @@ impl EditSession {
 
     /// Convert the current session back into a norad `Glyph`, for saving.
     pub fn to_norad_glyph(&self) -> Glyph {
-        let mut glyph = Glyph::new_named("");
+        // start from the existing glyph, so we preserve things we don't edit,
+        // like anchors and the lib.
+        let mut glyph = (*self.glyph).clone();
         glyph.name = self.name.clone();
-        glyph.advance = self.glyph.advance.clone();
-        glyph.codepoints = self.glyph.codepoints.clone();
 
         let contours: Vec<_> = self.paths.iter().map(Path::to_norad).collect();
         let components: Vec<_> = self.components.iter().map(Component::to_norad).collect();
-        if !contours.is_empty() || !components.is_empty() {
-            glyph.outline = Some(Outline {
+        glyph.outline = if !contours.is_empty() || !components.is_empty() {
+            Some(Outline {
                 components,
                 contours,
-            });
-        }
+            })
+        } else {
+            None
+        };
         let guidelines: Vec<_> = self.guides.iter().map(Guide::to_norad).collect();
-        if !guidelines.is_empty() {
-            glyph.guidelines = Some(guidelines);
-        }
+        glyph.guidelines = if !guidelines.is_empty() {
+            Some(guidelines)
+        } else {
+            None
+        };
         glyph
     }
 }
@@ -623,10 +800,14 @@ pub mod lenses {
             let count = data.selection.len();
             let frame = data.selection_dpoint_bbox();
             let quadrant = data.quadrant;
+            let component = data
+                .selected_component()
+                .map(Component::decomposed_transform);
             f(&CoordinateSelection {
                 count,
                 quadrant,
                 frame,
+                component,
             })
         }
 
@@ -638,10 +819,14 @@ pub mod lenses {
             let count = data.selection.len();
             let frame = data.selection_dpoint_bbox();
             let quadrant = data.quadrant;
+            let component = data
+                .selected_component()
+                .map(Component::decomposed_transform);
             let mut sel = CoordinateSelection {
                 count,
                 quadrant,
                 frame,
+                component,
             };
             let r = f(&mut sel);
             data.quadrant = sel.quadrant;
@@ -0,0 +1,258 @@
+//! Searching, filtering and sorting the glyphs shown in the glyph grid.
+//!
+//! A search is a list of terms separated by spaces, all of which must match:
+//!
+//! - `/pattern/` matches glyph names against a regular expression
+//! - `cat:Lu` matches glyphs whose character is in a general category;
+//!   `cat:L` matches any letter
+//! - `script:latin` matches glyphs whose character is in a script
+//! - `tag:done` matches glyphs with a tag, ignoring case
+//! - anything else matches a single character, a codepoint written like
+//!   `U+00C1`, `uni00C1` or `0xC1`, or part of a glyph name.
+
+use druid::{Data, Lens};
+use regex::Regex;
+
+use crate::unicode;
+
+/// The search text and quick filters for the glyph grid.
+#[derive(Debug, Clone, Default, PartialEq, Data, Lens)]
+pub struct GlyphFilter {
+    pub query: String,
+    /// Only show glyphs with no contours or components.
+    pub empty: bool,
+    /// Only show glyphs with components.
+    pub composites: bool,
+    /// Only show glyphs with problems in the QA report.
+    pub has_errors: bool,
+    /// Only show glyphs with unsaved changes.
+    pub modified: bool,
+}
+
+/// The order glyphs are shown in the glyph grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum GridSort {
+    /// The order in the font's `public.glyphOrder`, which can be changed
+    /// by dragging glyphs in the grid.
+    GlyphOrder,
+    Unicode,
+    Name,
+    /// By script, and then by codepoint.
+    Script,
+    /// By general category, and then by codepoint.
+    Category,
+}
+
+/// A parsed search query.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    Text(String),
+    Regex(Regex),
+    Category(String),
+    Script(String),
+    Tag(String),
+}
+
+impl GlyphFilter {
+    /// Returns `true` if this filter shows every glyph.
+    pub fn is_empty(&self) -> bool {
+        self.query.trim().is_empty()
+            && !(self.empty || self.composites || self.has_errors || self.modified)
+    }
+}
+
+impl GridSort {
+    pub const ALL: &'static [GridSort] = &[
+        GridSort::GlyphOrder,
+        GridSort::Unicode,
+        GridSort::Name,
+        GridSort::Script,
+        GridSort::Category,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            GridSort::GlyphOrder => "Glyph Order",
+            GridSort::Unicode => "Unicode",
+            GridSort::Name => "Name",
+            GridSort::Script => "Script",
+            GridSort::Category => "Category",
+        }
+    }
+
+    /// Sort `glyphs`, which are (name, first codepoint) pairs in glyph order.
+    ///
+    /// The sort is stable, and glyphs without a codepoint are placed after
+    /// those with one, except when sorting by name.
+    pub fn sort<T>(self, glyphs: &mut [(T, Option<char>)])
+    where
+        T: Ord,
+    {
+        match self {
+            GridSort::GlyphOrder => (),
+            GridSort::Name => glyphs.sort_by(|a, b| a.0.cmp(&b.0)),
+            GridSort::Unicode => glyphs.sort_by_key(|(_, cp)| (cp.is_none(), *cp)),
+            GridSort::Script => {
+                glyphs.sort_by_key(|(_, cp)| (cp.is_none(), cp.map(unicode::script), *cp))
+            }
+            GridSort::Category => {
+                glyphs.sort_by_key(|(_, cp)| (cp.is_none(), cp.map(unicode::general_category), *cp))
+            }
+        }
+    }
+}
+
+impl Default for GridSort {
+    fn default() -> Self {
+        GridSort::GlyphOrder
+    }
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Query, regex::Error> {
+        let terms = text
+            .split_whitespace()
+            .map(|term| {
+                let lower = term.to_lowercase();
+                if term.len() > 2 && term.starts_with('/') && term.ends_with('/') {
+                    Regex::new(&term[1..term.len() - 1]).map(Term::Regex)
+                } else if let Some(cat) = lower.strip_prefix("cat:") {
+                    Ok(Term::Category(cat.to_string()))
+                } else if let Some(script) = lower.strip_prefix("script:") {
+                    Ok(Term::Script(script.to_string()))
+                } else if let Some(tag) = lower.strip_prefix("tag:") {
+                    Ok(Term::Tag(tag.to_string()))
+                } else {
+                    Ok(Term::Text(term.to_string()))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Query { terms })
+    }
+
+    /// Returns `true` if the query has any `tag:` terms, and so needs each
+    /// glyph's tags.
+    pub fn uses_tags(&self) -> bool {
+        self.terms.iter().any(|term| matches!(term, Term::Tag(_)))
+    }
+
+    /// Returns `true` if a glyph with this name, codepoints and tags matches
+    /// every term in the query.
+    pub fn matches(&self, name: &str, codepoints: &[char], tags: &[String]) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.matches(name, codepoints, tags))
+    }
+}
+
+impl Term {
+    fn matches(&self, name: &str, codepoints: &[char], tags: &[String]) -> bool {
+        match self {
+            Term::Text(text) => {
+                let mut chars = text.chars();
+                if let (Some(c), None) = (chars.next(), chars.next()) {
+                    if codepoints.contains(&c) {
+                        return true;
+                    }
+                }
+                if let Some(c) = parse_codepoint(text) {
+                    if codepoints.contains(&c) {
+                        return true;
+                    }
+                }
+                name.to_lowercase().contains(&text.to_lowercase())
+            }
+            Term::Regex(regex) => regex.is_match(name),
+            Term::Category(cat) => codepoints.iter().any(|c| {
+                unicode::general_category(*c)
+                    .to_lowercase()
+                    .starts_with(cat)
+            }),
+            Term::Script(script) => codepoints
+                .iter()
+                .any(|c| unicode::script(*c).to_lowercase().starts_with(script)),
+            Term::Tag(tag) => tags.iter().any(|t| t.to_lowercase() == *tag),
+        }
+    }
+}
+
+/// Parse a codepoint written like `U+00C1`, `uni00C1`, `0xC1` or just `00C1`.
+pub fn parse_codepoint(text: &str) -> Option<char> {
+    let lower = text.to_lowercase();
+    let hex = ["u+", "uni", "0x"]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))
+        .or_else(|| Some(lower.as_str()).filter(|s| s.len() >= 4))?;
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .and_then(std::char::from_u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_terms() {
+        let matches = |query: &str, name: &str, codepoints: &[char]| {
+            Query::parse(query).unwrap().matches(name, codepoints, &[])
+        };
+        assert!(matches("", "A", &['A']));
+        assert!(matches("acute", "Aacute", &['Á']));
+        assert!(matches("ACUTE", "aacute", &['á']));
+        assert!(matches("Á", "Aacute", &['Á']));
+        assert!(matches("U+00C1", "Aacute", &['Á']));
+        assert!(!matches("grave", "Aacute", &['Á']));
+
+        assert!(matches("/^A.*e$/", "Aacute", &[]));
+        assert!(!matches("/^a/", "Aacute", &[]));
+        assert!(Query::parse("/(/").is_err());
+
+        assert!(matches("cat:Lu", "A", &['A']));
+        assert!(matches("cat:l", "a", &['a']));
+        assert!(!matches("cat:Lu", "a", &['a']));
+        assert!(matches("script:cyr", "Zhe-cy", &['Ж']));
+        assert!(!matches("script:latin", "Zhe-cy", &['Ж']));
+        assert!(!matches("script:latin", "A.alt", &[]));
+
+        // all of the terms must match
+        assert!(matches("cat:Lu acute", "Aacute", &['Á']));
+        assert!(!matches("cat:Lu acute", "aacute", &['á']));
+    }
+
+    #[test]
+    fn tag_terms() {
+        let query = Query::parse("tag:Review").unwrap();
+        assert!(query.uses_tags());
+        assert!(query.matches("A", &['A'], &["review".into()]));
+        assert!(query.matches("A", &['A'], &["done".into(), "Review".into()]));
+        assert!(!query.matches("A", &['A'], &["reviewed".into()]));
+        assert!(!query.matches("A", &['A'], &[]));
+        assert!(!Query::parse("acute").unwrap().uses_tags());
+    }
+
+    #[test]
+    fn sorting() {
+        let glyphs = vec![
+            ("b", Some('b')),
+            ("A", Some('A')),
+            ("x.alt", None),
+            ("Zhe", Some('Ж')),
+        ];
+        let sorted = |sort: GridSort| {
+            let mut glyphs = glyphs.clone();
+            sort.sort(&mut glyphs);
+            glyphs.into_iter().map(|(name, _)| name).collect::<Vec<_>>()
+        };
+        assert_eq!(sorted(GridSort::GlyphOrder), vec!["b", "A", "x.alt", "Zhe"]);
+        assert_eq!(sorted(GridSort::Name), vec!["A", "Zhe", "b", "x.alt"]);
+        assert_eq!(sorted(GridSort::Unicode), vec!["A", "b", "Zhe", "x.alt"]);
+        assert_eq!(sorted(GridSort::Script), vec!["Zhe", "A", "b", "x.alt"]);
+        assert_eq!(sorted(GridSort::Category), vec!["b", "A", "Zhe", "x.alt"]);
+    }
+}
@@ -0,0 +1,158 @@
+//! Reading and writing well-known keys in a glyph's lib.
+
+use druid::Color;
+use norad::Glyph;
+
+/// The lib key for a glyph's mark color, as a string of the form `r,g,b,a`
+/// with each component between 0 and 1.
+const MARK_COLOR_KEY: &str = "public.markColor";
+
+/// The lib key for a glyph's tags, as an array of strings.
+const TAGS_KEY: &str = "org.linebender.runebender.tags";
+
+/// The mark colors offered in menus.
+pub const MARK_COLORS: &[(&str, Color)] = &[
+    ("Red", Color::rgb8(0xe5, 0x39, 0x35)),
+    ("Orange", Color::rgb8(0xfb, 0x8c, 0x00)),
+    ("Yellow", Color::rgb8(0xfd, 0xd8, 0x35)),
+    ("Green", Color::rgb8(0x43, 0xa0, 0x47)),
+    ("Blue", Color::rgb8(0x1e, 0x88, 0xe5)),
+    ("Purple", Color::rgb8(0x8e, 0x24, 0xaa)),
+    ("Gray", Color::rgb8(0x9e, 0x9e, 0x9e)),
+];
+
+/// The glyph's mark color, if it has a valid one.
+pub fn mark_color(glyph: &Glyph) -> Option<Color> {
+    let value = glyph.lib.as_ref()?.get(MARK_COLOR_KEY)?.as_string()?;
+    parse_color(value)
+}
+
+/// Set or clear the glyph's mark color.
+pub fn set_mark_color(glyph: &mut Glyph, color: Option<&Color>) {
+    match color {
+        Some(color) => {
+            let value = plist::Value::String(format_color(color));
+            glyph
+                .lib
+                .get_or_insert_with(plist::Dictionary::new)
+                .insert(MARK_COLOR_KEY.into(), value);
+        }
+        None => remove_key(glyph, MARK_COLOR_KEY),
+    }
+}
+
+/// The glyph's tags, in the order they were added.
+pub fn tags(glyph: &Glyph) -> Vec<String> {
+    glyph
+        .lib
+        .as_ref()
+        .and_then(|lib| lib.get(TAGS_KEY))
+        .and_then(|value| value.as_array())
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|tag| tag.as_string())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Replace the glyph's tags; if `tags` is empty the key is removed.
+pub fn set_tags(glyph: &mut Glyph, tags: &[String]) {
+    if tags.is_empty() {
+        return remove_key(glyph, TAGS_KEY);
+    }
+    let value = plist::Value::Array(tags.iter().cloned().map(plist::Value::String).collect());
+    glyph
+        .lib
+        .get_or_insert_with(plist::Dictionary::new)
+        .insert(TAGS_KEY.into(), value);
+}
+
+/// Parse tags typed by the user, separated by commas or spaces.
+///
+/// Duplicates are dropped.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(|c: char| c == ',' || c.is_whitespace()) {
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Remove a key from the glyph's lib, and remove the lib if it is empty.
+fn remove_key(glyph: &mut Glyph, key: &str) {
+    if let Some(lib) = glyph.lib.as_mut() {
+        lib.remove(key);
+        if lib.is_empty() {
+            glyph.lib = None;
+        }
+    }
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let parts = value
+        .split(',')
+        .map(|s| {
+            s.trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|v| (0.0..=1.0).contains(v))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    match parts.as_slice() {
+        [r, g, b, a] => Some(Color::rgba(*r, *g, *b, *a)),
+        _ => None,
+    }
+}
+
+fn format_color(color: &Color) -> String {
+    let (r, g, b, a) = color.as_rgba();
+    // three decimal places is plenty, and is what other editors write
+    let round = |v: f64| (v * 1000.0).round() / 1000.0;
+    format!("{},{},{},{}", round(r), round(g), round(b), round(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mark_color_round_trip() {
+        let mut glyph = Glyph::new_named("A");
+        assert!(mark_color(&glyph).is_none());
+
+        set_mark_color(&mut glyph, Some(&Color::rgba(1.0, 0.2, 0.0, 1.0)));
+        let value = glyph.lib.as_ref().unwrap().get(MARK_COLOR_KEY).unwrap();
+        assert_eq!(value.as_string(), Some("1,0.2,0,1"));
+        let color = mark_color(&glyph).unwrap();
+        assert_eq!(color.as_rgba(), (1.0, 0.2, 0.0, 1.0));
+
+        set_mark_color(&mut glyph, None);
+        assert!(glyph.lib.is_none());
+
+        assert!(parse_color("1,0,0").is_none());
+        assert!(parse_color("1,0,0,2").is_none());
+        assert!(parse_color(" 0.2, 0.4, 0.6, 0.8 ").is_some());
+    }
+
+    #[test]
+    fn tags_round_trip() {
+        let mut glyph = Glyph::new_named("A");
+        assert!(tags(&glyph).is_empty());
+
+        let parsed = parse_tags("done, review  done,,latin");
+        assert_eq!(parsed, vec!["done", "review", "latin"]);
+        set_tags(&mut glyph, &parsed);
+        set_mark_color(&mut glyph, Some(&Color::rgba(1.0, 0.2, 0.0, 1.0)));
+        assert_eq!(tags(&glyph), parsed);
+
+        set_tags(&mut glyph, &[]);
+        assert!(tags(&glyph).is_empty());
+        assert!(mark_color(&glyph).is_some());
+        set_mark_color(&mut glyph, None);
+        assert!(glyph.lib.is_none());
+    }
+}
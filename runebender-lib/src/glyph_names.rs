@@ -15,6 +15,21 @@ pub fn glyph_name_for_char(chr: char) -> Option<&'static str> {
         .map(|idx| GLYPH_NAMES[idx].1)
 }
 
+/// Every character in the aglfn, with its postscript name, in codepoint order.
+pub fn aglfn_chars() -> impl Iterator<Item = (char, &'static str)> {
+    GLYPH_NAMES.iter().copied()
+}
+
+/// The name to use for a new glyph for `chr`: its aglfn name if it has one,
+/// and otherwise a name like `uni0431` or `u1F600`.
+pub fn default_glyph_name(chr: char) -> String {
+    match glyph_name_for_char(chr) {
+        Some(name) => name.to_string(),
+        None if (chr as u32) <= 0xFFFF => format!("uni{:04X}", chr as u32),
+        None => format!("u{:X}", chr as u32),
+    }
+}
+
 /// Given a glyph (represented as a &str), return the postcript name, if one
 /// exists in aglfn.
 ///
@@ -29,7 +44,8 @@ pub fn glyph_name_for_glyph(glyph: &str) -> Option<&'static str> {
     }
 }
 
-fn is_valid_glyph_name(name: &str) -> bool {
+/// Returns `true` if `name` only uses the characters allowed in glyph names.
+pub fn is_valid_glyph_name(name: &str) -> bool {
     name.chars()
         .all(|c| matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_'))
 }
@@ -42,22 +58,80 @@ pub fn validate_and_standardize_name(name: &str) -> Result<String, IllegalName>
     }
 }
 
-/// Given a glyph name, guess what the unicode value is?
+/// The codepoints to give a glyph, based on its name.
 ///
-/// Works fine for known glyph names, otherwise just uses the first character :shrug:
+/// Names like `A`, `eacute`, `uni0431` and `u1F600` have one codepoint;
+/// alternates with a suffix, like `a.alt`, and ligatures like `f_i` have none.
 pub fn codepoints_for_glyph(name: &str) -> Option<Vec<char>> {
-    GLYPH_NAMES
-        .iter()
-        .find(|(_, n)| *n == name)
-        .map(|(c, _)| vec![*c])
-        .or_else(|| {
-            let mut chars = name.chars();
-            // if we're at most one char long, use that as our codepoint
-            match (chars.next(), chars.next()) {
-                (Some(c), None) => Some(vec![c]),
-                _ => None,
+    match split_suffix(name) {
+        (base, None) => chars_for_glyph_name(base).filter(|chars| chars.len() == 1),
+        (_, Some(_)) => None,
+    }
+}
+
+/// The character a glyph would be sorted with: the first character its name
+/// stands for, ignoring any suffix.
+///
+/// This lets `a.sc` sort with `a`, even though it is not encoded.
+pub fn sort_char_for_glyph(name: &str) -> Option<char> {
+    chars_for_glyph_name(name).and_then(|chars| chars.first().copied())
+}
+
+/// The characters a glyph name stands for, following the Adobe Glyph List
+/// Specification.
+///
+/// Anything after the first period is a suffix, and is ignored; underscores
+/// separate the parts of a ligature, each of which is an aglfn name, a name
+/// like `uni0431` (possibly with several codepoints, as in `uni00660069`)
+/// or a name like `u1F600`. A name that is a single character, like `é`,
+/// stands for that character.
+pub fn chars_for_glyph_name(name: &str) -> Option<Vec<char>> {
+    let (base, _) = split_suffix(name);
+    let mut chars = base.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(vec![c]);
+    }
+    let mut result = Vec::new();
+    for part in base.split('_') {
+        if let Some((c, _)) = GLYPH_NAMES.iter().find(|(_, n)| *n == part) {
+            result.push(*c);
+        } else if let Some(hex) = part.strip_prefix("uni") {
+            if hex.is_empty() || hex.len() % 4 != 0 {
+                return None;
+            }
+            for idx in (0..hex.len()).step_by(4) {
+                result.push(parse_hex(&hex[idx..idx + 4])?);
             }
-        })
+        } else if let Some(hex) = part
+            .strip_prefix('u')
+            .filter(|hex| (4..=6).contains(&hex.len()))
+        {
+            result.push(parse_hex(hex)?);
+        } else {
+            return None;
+        }
+    }
+    Some(result)
+}
+
+/// Split a glyph name at its first period, into the base name and suffix.
+///
+/// A name that is only a period, like `.`, has no suffix.
+fn split_suffix(name: &str) -> (&str, Option<&str>) {
+    match name.find('.') {
+        Some(idx) if name != "." => (&name[..idx], Some(&name[idx + 1..])),
+        _ => (name, None),
+    }
+}
+
+/// Parse a codepoint written in uppercase hex, as glyph names require.
+fn parse_hex(hex: &str) -> Option<char> {
+    if !hex.chars().all(|c| matches!(c, '0'..='9' | 'A'..='F')) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .and_then(std::char::from_u32)
 }
 
 /// An error indicating a name included illegal characters.
@@ -97,11 +171,49 @@ mod tests {
         assert_eq!(glyph_name_for_glyph("!"), Some("exclam"));
     }
 
+    #[test]
+    fn default_names() {
+        assert_eq!(default_glyph_name('é'), "eacute");
+        assert_eq!(default_glyph_name('б'), "uni0431");
+        assert_eq!(default_glyph_name('😀'), "u1F600");
+        assert!(aglfn_chars().any(|(c, name)| c == 'A' && name == "A"));
+    }
+
     #[test]
     fn codepoints_for_glyph_() {
         assert_eq!(codepoints_for_glyph("A"), Some(vec!['A']));
         assert_eq!(codepoints_for_glyph("eacute"), Some(vec!['é']));
         assert_eq!(codepoints_for_glyph("some-string"), None);
+        assert_eq!(codepoints_for_glyph("é"), Some(vec!['é']));
+        assert_eq!(codepoints_for_glyph("."), Some(vec!['.']));
+        assert_eq!(codepoints_for_glyph("uni0431"), Some(vec!['б']));
+        assert_eq!(codepoints_for_glyph("u1F600"), Some(vec!['😀']));
+        assert_eq!(codepoints_for_glyph("uni0431.alt"), None);
+        assert_eq!(codepoints_for_glyph("Eng.alt"), None);
+        assert_eq!(codepoints_for_glyph("f_i"), None);
+        assert_eq!(codepoints_for_glyph("uni00660069"), None);
+        assert_eq!(codepoints_for_glyph("uni0431x"), None);
+        assert_eq!(codepoints_for_glyph("unid800"), None);
+        assert_eq!(codepoints_for_glyph("uniD800"), None);
+        assert_eq!(codepoints_for_glyph(".notdef"), None);
+    }
+
+    #[test]
+    fn glyph_name_parsing() {
+        assert_eq!(chars_for_glyph_name("Eng.alt"), Some(vec!['Ŋ']));
+        assert_eq!(
+            chars_for_glyph_name("f_f_i.liga"),
+            Some(vec!['f', 'f', 'i'])
+        );
+        assert_eq!(chars_for_glyph_name("uni00660069"), Some(vec!['f', 'i']));
+        assert_eq!(
+            chars_for_glyph_name("uni0431_u1F600"),
+            Some(vec!['б', '😀'])
+        );
+        assert_eq!(chars_for_glyph_name("f_myglyph"), None);
+        assert_eq!(sort_char_for_glyph("a.sc"), Some('a'));
+        assert_eq!(sort_char_for_glyph("uni0431.ss01"), Some('б'));
+        assert_eq!(sort_char_for_glyph("newGlyph.1"), None);
     }
 
     #[test]
@@ -13,28 +13,38 @@ mod util;
 
 mod app_delegate;
 mod bez_cache;
+mod charset;
 mod clipboard;
 mod component;
 mod consts;
+mod construction;
+mod coverage;
 mod design_space;
 mod draw;
 mod edit_session;
+mod glyph_filter;
+mod glyph_lib;
 mod glyph_names;
 mod guides;
 mod path;
 mod plist;
 mod point;
 mod point_list;
+mod qa;
 mod quadrant;
+mod save;
 mod selection;
 mod tools;
 mod undo;
+mod unicode;
 
 pub mod data;
 pub mod menus;
 pub mod mouse;
+pub mod preferences;
+pub mod report;
 pub mod theme;
 pub mod widgets;
 
-pub use app_delegate::Delegate;
+pub use app_delegate::{make_font_window, Delegate};
 pub use util::create_blank_font;
@@ -6,10 +6,17 @@ use druid::{
     Data, FileDialogOptions, FileSpec, KbKey, LocalizedString, MenuDesc, MenuItem, Point, SysMods,
 };
 
+use crate::charset::TEMPLATES;
 use crate::consts;
-use crate::data::{AppState, EditorState};
+use crate::data::{AppState, EditorState, Workspace};
+use crate::glyph_filter::GridSort;
+use crate::glyph_lib::MARK_COLORS;
+use crate::preferences::Preferences;
 
 pub const UFO_FILE_TYPE: FileSpec = FileSpec::new("Font Object", &["ufo"]);
+pub const RECIPE_FILE_TYPE: FileSpec = FileSpec::new("Glyph Recipes", &["txt"]);
+pub const CHARSET_FILE_TYPE: FileSpec =
+    FileSpec::new("Character Set or Encoding", &["txt", "enc", "nam"]);
 
 /// Context menu's inner menu must have type T == the root app state.
 pub fn make_context_menu(data: &EditorState, pos: Point) -> MenuDesc<AppState> {
@@ -28,30 +35,86 @@ pub fn make_context_menu(data: &EditorState, pos: Point) -> MenuDesc<AppState> {
             consts::cmd::TOGGLE_GUIDE.with(args),
         ));
     }
+
+    if data.session.selection.iter().any(|s| s.is_component()) {
+        menu = menu.append(MenuItem::new(
+            LocalizedString::new("menu-item-decompose-component")
+                .with_placeholder("Decompose Component"),
+            consts::cmd::DECOMPOSE_COMPONENTS,
+        ));
+    }
     menu
 }
 
+/// The context menu for the selected glyphs in the glyph grid.
+pub fn make_grid_context_menu(data: &Workspace) -> MenuDesc<AppState> {
+    MenuDesc::empty()
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-open-selected-glyphs")
+                .with_placeholder("Open Selected Glyphs"),
+            consts::cmd::OPEN_SELECTED_GLYPHS,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-edit-selected-glyphs")
+                .with_placeholder("Edit Selected Glyphs…"),
+            consts::cmd::EDIT_SELECTED_GLYPHS,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-duplicate-glyphs")
+                .with_placeholder("Duplicate Glyphs…"),
+            consts::cmd::SHOW_DUPLICATE_GLYPHS,
+        ))
+        .append(mark_color_menu(data))
+        .append_separator()
+        .append(MenuItem::new(
+            LocalizedString::new("common-menu-copy").with_placeholder("Copy"),
+            commands::COPY,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-delete-glyph").with_placeholder("Delete Glyph"),
+            consts::cmd::DELETE_SELECTED_GLYPH,
+        ))
+}
+
 /// The main window/app menu.
+///
+/// Each window has its own menu, built from the state of the font that
+/// window belongs to.
 #[allow(unused_mut)]
-pub fn make_menu(data: &AppState) -> MenuDesc<AppState> {
+pub fn make_menu(data: &Workspace, prefs: &Preferences) -> MenuDesc<AppState> {
     let mut menu = MenuDesc::empty();
     #[cfg(target_os = "macos")]
     {
-        menu = menu.append(platform_menus::mac::application::default());
+        menu = menu.append(app_menu());
     }
 
-    menu.append(file_menu(data))
+    menu.append(file_menu(data, prefs))
         .append(edit_menu())
-        .append(view_menu())
+        .append(view_menu(data))
         .append(glyph_menu(data))
         .append(paths_menu())
         .append(tools_menu())
 }
 
-fn file_menu(data: &AppState) -> MenuDesc<AppState> {
-    let has_path = data.workspace.font.path.is_some();
+#[cfg(target_os = "macos")]
+fn app_menu() -> MenuDesc<AppState> {
+    use platform_menus::mac::application;
+    MenuDesc::new(LocalizedString::new("macos-menu-application-menu"))
+        .append(application::about())
+        .append_separator()
+        .append(application::preferences())
+        .append_separator()
+        .append(application::hide())
+        .append(application::hide_others())
+        .append_separator()
+        .append(application::quit())
+}
+
+fn file_menu(data: &Workspace, prefs: &Preferences) -> MenuDesc<AppState> {
+    let has_path = data.font.path.is_some();
     let mut menu = MenuDesc::new(LocalizedString::new("common-menu-file-menu"))
-        .append(platform_menus::mac::file::new_file().disabled())
+        .append(platform_menus::mac::file::new_file())
+        .append(new_from_template_menu())
         .append(
             MenuItem::new(
                 LocalizedString::new("common-menu-file-open"),
@@ -60,6 +123,7 @@ fn file_menu(data: &AppState) -> MenuDesc<AppState> {
             )
             .hotkey(SysMods::Cmd, "o"),
         )
+        .append(recent_files_menu(prefs))
         .append_separator()
         .append(platform_menus::mac::file::close());
     if has_path {
@@ -81,9 +145,62 @@ fn file_menu(data: &AppState) -> MenuDesc<AppState> {
             .hotkey(SysMods::Cmd, "s"),
         );
     }
-    menu.append_separator()
+    menu = menu
+        .append_separator()
         .append(platform_menus::mac::file::page_setup().disabled())
-        .append(platform_menus::mac::file::print().disabled())
+        .append(platform_menus::mac::file::print().disabled());
+
+    // on macOS this lives in the application menu
+    #[cfg(not(target_os = "macos"))]
+    {
+        menu = menu.append_separator().append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-preferences").with_placeholder("Preferences…"),
+                commands::SHOW_PREFERENCES,
+            )
+            .hotkey(SysMods::Cmd, ","),
+        );
+    }
+    menu
+}
+
+fn new_from_template_menu() -> MenuDesc<AppState> {
+    TEMPLATES.iter().fold(
+        MenuDesc::new(
+            LocalizedString::new("menu-file-new-from-template")
+                .with_placeholder("New from Template"),
+        ),
+        |menu, template| {
+            menu.append(MenuItem::new(
+                LocalizedString::new("menu-item-new-from-template").with_placeholder(template.name),
+                consts::cmd::NEW_FONT_FROM_TEMPLATE.with(*template),
+            ))
+        },
+    )
+}
+
+fn recent_files_menu(prefs: &Preferences) -> MenuDesc<AppState> {
+    let mut menu = MenuDesc::new(
+        LocalizedString::new("menu-file-open-recent").with_placeholder("Open Recent"),
+    );
+    for path in prefs.recent_files.iter() {
+        let title = path
+            .file_name()
+            .unwrap_or_else(|| path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        menu = menu.append(MenuItem::new(
+            LocalizedString::new("menu-item-recent-file").with_placeholder(title),
+            consts::cmd::OPEN_RECENT_FILE.with(path.clone()),
+        ));
+    }
+    menu.append_separator().append(
+        MenuItem::new(
+            LocalizedString::new("menu-item-clear-recent-files").with_placeholder("Clear Menu"),
+            consts::cmd::CLEAR_RECENT_FILES,
+        )
+        .disabled_if(|| prefs.recent_files.is_empty()),
+    )
 }
 
 fn edit_menu<T: Data>() -> MenuDesc<T> {
@@ -115,7 +232,7 @@ fn edit_menu<T: Data>() -> MenuDesc<T> {
         )
 }
 
-fn view_menu<T: Data>() -> MenuDesc<T> {
+fn view_menu(data: &Workspace) -> MenuDesc<AppState> {
     MenuDesc::new(LocalizedString::new("menu-view-menu").with_placeholder("View"))
         .append(
             MenuItem::new(
@@ -138,9 +255,28 @@ fn view_menu<T: Data>() -> MenuDesc<T> {
             )
             .hotkey(SysMods::Cmd, "0"),
         )
+        .append_separator()
+        .append(sort_menu(data))
 }
 
-fn glyph_menu(data: &AppState) -> MenuDesc<AppState> {
+fn sort_menu(data: &Workspace) -> MenuDesc<AppState> {
+    GridSort::ALL.iter().fold(
+        MenuDesc::new(
+            LocalizedString::new("menu-sort-glyphs-menu").with_placeholder("Sort Glyphs"),
+        ),
+        |menu, sort| {
+            menu.append(
+                MenuItem::new(
+                    LocalizedString::new("menu-item-sort-glyphs").with_placeholder(sort.title()),
+                    consts::cmd::SET_GRID_SORT.with(*sort),
+                )
+                .selected_if(|| data.grid_sort == *sort),
+            )
+        },
+    )
+}
+
+fn glyph_menu(data: &Workspace) -> MenuDesc<AppState> {
     MenuDesc::new(LocalizedString::new("menu-glyph-menu").with_placeholder("Glyph"))
         .append(
             MenuItem::new(
@@ -149,22 +285,98 @@ fn glyph_menu(data: &AppState) -> MenuDesc<AppState> {
             )
             .hotkey(SysMods::CmdShift, "N"),
         )
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-add-glyphs").with_placeholder("Add Glyphs…"),
+            consts::cmd::SHOW_ADD_GLYPHS,
+        ))
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-duplicate-glyphs")
+                    .with_placeholder("Duplicate Glyphs…"),
+                consts::cmd::SHOW_DUPLICATE_GLYPHS,
+            )
+            .hotkey(SysMods::Cmd, "d")
+            .disabled_if(|| data.selection.is_empty()),
+        )
         .append(
             MenuItem::new(
                 LocalizedString::new("menu-item-delete-glyph").with_placeholder("Delete Glyph"),
                 consts::cmd::DELETE_SELECTED_GLYPH,
             )
             .hotkey(SysMods::Cmd, KbKey::Backspace)
-            .disabled_if(|| data.workspace.selected.is_none()),
+            .disabled_if(|| data.selection.is_empty()),
         )
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-open-selected-glyphs")
+                    .with_placeholder("Open Selected Glyphs"),
+                consts::cmd::OPEN_SELECTED_GLYPHS,
+            )
+            .disabled_if(|| data.selection.is_empty()),
+        )
+        .append(
+            MenuItem::new(
+                LocalizedString::new("menu-item-edit-selected-glyphs")
+                    .with_placeholder("Edit Selected Glyphs…"),
+                consts::cmd::EDIT_SELECTED_GLYPHS,
+            )
+            .disabled_if(|| data.selection.is_empty()),
+        )
+        .append(mark_color_menu(data))
         .append(
             MenuItem::new(
                 LocalizedString::new("menu-item-add-component").with_placeholder("Add Component"),
                 consts::cmd::ADD_COMPONENT,
             )
             .hotkey(SysMods::CmdShift, "C")
-            .disabled(),
+            .disabled_if(|| data.open_glyphs.is_empty()),
         )
+        .append_separator()
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-build-standard-composites")
+                .with_placeholder("Build Standard Composites"),
+            consts::cmd::BUILD_STANDARD_COMPOSITES,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-build-composites")
+                .with_placeholder("Build Composites from Recipes…"),
+            commands::SHOW_OPEN_PANEL.with(
+                FileDialogOptions::new()
+                    .allowed_types(vec![RECIPE_FILE_TYPE])
+                    .accept_command(consts::cmd::BUILD_COMPOSITES_FROM_FILE),
+            ),
+        ))
+        .append_separator()
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-qa-report").with_placeholder("QA Report…"),
+            consts::cmd::SHOW_QA_REPORT,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-coverage").with_placeholder("Unicode Coverage…"),
+            consts::cmd::SHOW_COVERAGE,
+        ))
+}
+
+fn mark_color_menu(data: &Workspace) -> MenuDesc<AppState> {
+    let none = MenuItem::new(
+        LocalizedString::new("menu-item-mark-color-none").with_placeholder("None"),
+        consts::cmd::SET_MARK_COLOR.with(None),
+    )
+    .disabled_if(|| data.selection.is_empty());
+    MARK_COLORS.iter().fold(
+        MenuDesc::new(LocalizedString::new("menu-mark-color-menu").with_placeholder("Mark Color"))
+            .append(none)
+            .append_separator(),
+        |menu, (name, color)| {
+            menu.append(
+                MenuItem::new(
+                    LocalizedString::new("menu-item-mark-color").with_placeholder(*name),
+                    consts::cmd::SET_MARK_COLOR.with(Some(color.clone())),
+                )
+                .disabled_if(|| data.selection.is_empty()),
+            )
+        },
+    )
 }
 
 fn paths_menu<T: Data>() -> MenuDesc<T> {
@@ -182,6 +394,20 @@ fn paths_menu<T: Data>() -> MenuDesc<T> {
             )
             .hotkey(SysMods::CmdShift, "A"),
         )
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-decompose-components")
+                .with_placeholder("Decompose Components"),
+            consts::cmd::DECOMPOSE_COMPONENTS,
+        ))
+        .append_separator()
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-flip-horizontal").with_placeholder("Flip Horizontally"),
+            consts::cmd::FLIP_HORIZONTAL,
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("menu-item-flip-vertical").with_placeholder("Flip Vertically"),
+            consts::cmd::FLIP_VERTICAL,
+        ))
 }
 
 fn tools_menu<T: Data>() -> MenuDesc<T> {
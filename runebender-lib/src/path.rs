@@ -351,11 +351,20 @@ impl Path {
         self.points.transform_points(points, scale_xform, anchor);
     }
 
+    pub(crate) fn rotate_points(&mut self, points: &[EntityId], angle: f64, anchor: DPoint) {
+        self.points
+            .transform_points(points, Affine::rotate(angle), anchor);
+    }
+
     pub(crate) fn nudge_points(&mut self, points: &[EntityId], v: DVec2) {
         let affine = Affine::translate(v.to_raw());
         self.points.transform_points(points, affine, DPoint::ZERO);
     }
 
+    pub(crate) fn transform_all(&mut self, affine: Affine, anchor: DPoint) {
+        self.points.transform_all(affine, anchor);
+    }
+
     pub(crate) fn nudge_all_points(&mut self, v: DVec2) {
         let affine = Affine::translate(v.to_raw());
         self.points.transform_all(affine, DPoint::ZERO);
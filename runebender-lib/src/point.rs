@@ -12,6 +12,7 @@ use norad::glyph::PointType as NoradPointType;
 const RESERVED_ID_COUNT: IdComponent = 5;
 const NO_PARENT_TYPE_ID: IdComponent = 0;
 const GUIDE_TYPE_ID: IdComponent = 1;
+const COMPONENT_TYPE_ID: IdComponent = 2;
 
 type IdComponent = usize;
 
@@ -71,6 +72,14 @@ impl EntityId {
         }
     }
 
+    #[inline]
+    pub fn new_for_component() -> Self {
+        EntityId {
+            parent: COMPONENT_TYPE_ID,
+            ..EntityId::next()
+        }
+    }
+
     /// Return the `EntityId` representing this id's parent.
     ///
     /// If this entity's parent has its own parent, it will not be present.
@@ -85,6 +94,10 @@ impl EntityId {
         self.parent == GUIDE_TYPE_ID
     }
 
+    pub fn is_component(self) -> bool {
+        self.parent == COMPONENT_TYPE_ID
+    }
+
     pub(crate) fn parent_eq(self, other: EntityId) -> bool {
         self.parent == other.parent
     }
@@ -0,0 +1,236 @@
+//! User preferences, persisted between launches.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use druid::kurbo::Size;
+use druid::{Data, Env, Key, Lens};
+
+use crate::tools::{self, ToolId};
+
+const PREFERENCES_DIR: &str = "runebender";
+const PREFERENCES_FILE: &str = "preferences.plist";
+
+/// The maximum number of files shown in File > Open Recent.
+const MAX_RECENT_FILES: usize = 10;
+
+/// The distance, in design units, that arrow keys move the selection.
+pub const NUDGE_SMALL: Key<f64> = Key::new("runebender.prefs.nudge-small");
+/// The nudge distance with shift held.
+pub const NUDGE_MEDIUM: Key<f64> = Key::new("runebender.prefs.nudge-medium");
+/// The nudge distance with cmd held.
+pub const NUDGE_LARGE: Key<f64> = Key::new("runebender.prefs.nudge-large");
+/// Whether dragged points snap to a grid.
+pub const SNAP_TO_GRID: Key<bool> = Key::new("runebender.prefs.snap-to-grid");
+/// The spacing of the snapping grid, in design units.
+pub const SNAP_GRID_SIZE: Key<f64> = Key::new("runebender.prefs.snap-grid-size");
+/// The size of the cells in the glyph grid.
+pub const GLYPH_GRID_CELL_SIZE: Key<f64> = Key::new("runebender.prefs.glyph-grid-cell-size");
+/// Whether glyph grid cells have labels with the glyph's name, unicode and
+/// advance underneath them.
+pub const GLYPH_GRID_LABELS: Key<bool> = Key::new("runebender.prefs.glyph-grid-labels");
+/// Whether the glyph grid is shown as a list, with a row for each glyph.
+pub const GLYPH_GRID_LIST_MODE: Key<bool> = Key::new("runebender.prefs.glyph-grid-list-mode");
+
+/// The smallest and largest glyph grid cells.
+pub const MIN_GLYPH_GRID_CELL_SIZE: f64 = 32.0;
+pub const MAX_GLYPH_GRID_CELL_SIZE: f64 = 320.0;
+
+/// The smallest size for new windows.
+pub const MIN_WINDOW_WIDTH: f64 = 400.0;
+pub const MIN_WINDOW_HEIGHT: f64 = 300.0;
+
+/// The available themes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data, Serialize, Deserialize)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// Settings that apply to the whole application.
+#[derive(Debug, Clone, PartialEq, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: Theme,
+    /// The name of the tool that is selected when an editor opens.
+    pub default_tool: String,
+    pub nudge_small: f64,
+    pub nudge_medium: f64,
+    pub nudge_large: f64,
+    pub snap_to_grid: bool,
+    pub snap_grid_size: f64,
+    pub glyph_grid_cell_size: f64,
+    pub glyph_grid_labels: bool,
+    pub glyph_grid_list_mode: bool,
+    pub window_width: f64,
+    pub window_height: f64,
+    /// Recently opened fonts, most recent first.
+    pub recent_files: Arc<Vec<PathBuf>>,
+}
+
+impl Theme {
+    /// The path to the file this theme is loaded from.
+    pub fn path(self) -> &'static str {
+        match self {
+            Theme::Light => "resources/default.theme",
+            Theme::Dark => "resources/dark.theme",
+        }
+    }
+}
+
+impl Preferences {
+    /// Load the preferences from disk.
+    ///
+    /// If there are no saved preferences, or they can't be read, the
+    /// defaults are used.
+    pub fn load() -> Preferences {
+        let path = match preferences_path() {
+            Some(path) if path.exists() => path,
+            _ => return Preferences::default(),
+        };
+        match plist::from_file::<_, Preferences>(&path) {
+            Ok(mut prefs) => {
+                prefs.clamp_window_size();
+                prefs
+            }
+            Err(e) => {
+                log::warn!("failed to load preferences from {:?}: '{}'", path, e);
+                Preferences::default()
+            }
+        }
+    }
+
+    /// Write the preferences to disk.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = preferences_path().ok_or("no config directory found")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        plist::to_file_xml(&path, self)?;
+        Ok(())
+    }
+
+    /// Move `path` to the front of the recent files list.
+    pub fn add_recent_file(&mut self, path: &Path) {
+        let recent = Arc::make_mut(&mut self.recent_files);
+        recent.retain(|p| p != path);
+        recent.insert(0, path.to_owned());
+        recent.truncate(MAX_RECENT_FILES);
+    }
+
+    pub fn clear_recent_files(&mut self) {
+        self.recent_files = Default::default();
+    }
+
+    /// The tool to select when an editor opens.
+    pub fn initial_tool(&self) -> ToolId {
+        tools::tool_for_id(&self.default_tool)
+            .map(|tool| tool.name())
+            .unwrap_or("Select")
+    }
+
+    /// The size for newly opened windows.
+    pub fn window_size(&self) -> Size {
+        Size::new(self.window_width, self.window_height)
+    }
+
+    /// Make sure new windows are no smaller than the minimum size.
+    fn clamp_window_size(&mut self) {
+        self.window_width = self.window_width.max(MIN_WINDOW_WIDTH);
+        self.window_height = self.window_height.max(MIN_WINDOW_HEIGHT);
+    }
+
+    /// Add the preferences that affect widgets to the `Env`.
+    pub fn configure_env(&self, env: &mut Env) {
+        env.set(NUDGE_SMALL, self.nudge_small);
+        env.set(NUDGE_MEDIUM, self.nudge_medium);
+        env.set(NUDGE_LARGE, self.nudge_large);
+        env.set(SNAP_TO_GRID, self.snap_to_grid);
+        env.set(SNAP_GRID_SIZE, self.snap_grid_size.max(1.0).round());
+        env.set(
+            GLYPH_GRID_CELL_SIZE,
+            self.glyph_grid_cell_size
+                .max(MIN_GLYPH_GRID_CELL_SIZE)
+                .min(MAX_GLYPH_GRID_CELL_SIZE),
+        );
+        env.set(GLYPH_GRID_LABELS, self.glyph_grid_labels);
+        env.set(GLYPH_GRID_LIST_MODE, self.glyph_grid_list_mode);
+    }
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            theme: Theme::Light,
+            default_tool: "Select".into(),
+            nudge_small: 1.0,
+            nudge_medium: 10.0,
+            nudge_large: 100.0,
+            snap_to_grid: false,
+            snap_grid_size: 10.0,
+            glyph_grid_cell_size: 128.0,
+            glyph_grid_labels: false,
+            glyph_grid_list_mode: false,
+            window_width: 900.0,
+            window_height: 800.0,
+            recent_files: Default::default(),
+        }
+    }
+}
+
+fn preferences_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(PREFERENCES_DIR).join(PREFERENCES_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_files() {
+        let mut prefs = Preferences::default();
+        prefs.add_recent_file(Path::new("a.ufo"));
+        prefs.add_recent_file(Path::new("b.ufo"));
+        prefs.add_recent_file(Path::new("a.ufo"));
+        assert_eq!(
+            prefs.recent_files.as_slice(),
+            &[PathBuf::from("a.ufo"), PathBuf::from("b.ufo")]
+        );
+
+        for i in 0..20 {
+            prefs.add_recent_file(Path::new(&format!("{}.ufo", i)));
+        }
+        assert_eq!(prefs.recent_files.len(), MAX_RECENT_FILES);
+        assert_eq!(prefs.recent_files[0], PathBuf::from("19.ufo"));
+    }
+
+    #[test]
+    fn window_size_minimum() {
+        let mut prefs = Preferences {
+            window_width: 20.0,
+            window_height: f64::NAN,
+            ..Default::default()
+        };
+        prefs.clamp_window_size();
+        assert_eq!(
+            prefs.window_size(),
+            Size::new(MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT)
+        );
+        prefs.window_width = 1200.0;
+        prefs.clamp_window_size();
+        assert_eq!(prefs.window_width, 1200.0);
+    }
+
+    #[test]
+    fn unknown_default_tool() {
+        let mut prefs = Preferences {
+            default_tool: "Pen".into(),
+            ..Default::default()
+        };
+        assert_eq!(prefs.initial_tool(), "Pen");
+        prefs.default_tool = "Lasso".into();
+        assert_eq!(prefs.initial_tool(), "Select");
+    }
+}
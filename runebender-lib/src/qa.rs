@@ -0,0 +1,409 @@
+//! Checks for common problems in glyph outlines.
+
+use std::sync::Arc;
+
+use druid::kurbo::{
+    BezPath, Line, ParamCurve, ParamCurveArclen, ParamCurveExtrema, PathEl, PathSeg, Point, Shape,
+};
+use druid::Data;
+
+use crate::path::Path;
+use crate::point::{EntityId, PathPoint};
+use crate::point_list::Segment;
+
+/// Segments shorter than this, in design units, are reported as tiny.
+const TINY_SEGMENT_LEN: f64 = 2.0;
+/// Corners whose handles are within this many degrees of collinear are
+/// reported as probably meant to be smooth.
+const NEAR_COLLINEAR_DEGREES: f64 = 3.0;
+/// Extrema closer than this to either end of a segment are ignored.
+const EXTREMA_TOLERANCE: f64 = 1.0;
+const ARCLEN_ACCURACY: f64 = 0.1;
+const FLATTEN_TOLERANCE: f64 = 0.5;
+
+/// The types of problem we check for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Data)]
+pub enum IssueKind {
+    OpenContour,
+    StrayPoint,
+    DuplicatePoint,
+    ZeroLengthHandle,
+    NearlySmooth,
+    MissingExtrema,
+    TinySegment,
+    OverlappingContours,
+    WrongDirection,
+}
+
+/// A problem found in a glyph's outlines.
+#[derive(Debug, Clone, Data)]
+pub struct Issue {
+    pub kind: IssueKind,
+    /// Where to draw the marker for this issue, in design space.
+    pub pos: Point,
+    /// The points involved; these are selected when the issue is picked
+    /// from a list.
+    pub points: Arc<[EntityId]>,
+}
+
+/// Caches the issues for a set of paths, so that we only rerun the checks
+/// when the paths change.
+#[derive(Debug, Default)]
+pub struct IssueCache {
+    paths: Option<Arc<Vec<Path>>>,
+    issues: Arc<Vec<Issue>>,
+}
+
+impl IssueKind {
+    pub fn description(self) -> &'static str {
+        match self {
+            IssueKind::OpenContour => "Open contour",
+            IssueKind::StrayPoint => "Stray point",
+            IssueKind::DuplicatePoint => "Duplicate points",
+            IssueKind::ZeroLengthHandle => "Zero-length handle",
+            IssueKind::NearlySmooth => "Corner is nearly smooth",
+            IssueKind::MissingExtrema => "Missing extreme point",
+            IssueKind::TinySegment => "Tiny segment",
+            IssueKind::OverlappingContours => "Overlapping contours",
+            IssueKind::WrongDirection => "Wrong contour direction",
+        }
+    }
+}
+
+impl Issue {
+    fn new(kind: IssueKind, pos: Point, points: impl IntoIterator<Item = EntityId>) -> Self {
+        Issue {
+            kind,
+            pos,
+            points: points.into_iter().collect(),
+        }
+    }
+}
+
+impl IssueCache {
+    /// Returns the issues for `paths`, rerunning the checks if needed.
+    pub fn issues(&mut self, paths: &Arc<Vec<Path>>) -> Arc<Vec<Issue>> {
+        if !self.paths.as_ref().map(|p| p.same(paths)).unwrap_or(false) {
+            self.issues = Arc::new(check_paths(paths));
+            self.paths = Some(paths.clone());
+        }
+        self.issues.clone()
+    }
+}
+
+/// Run all of the checks on a glyph's paths.
+pub fn check_paths(paths: &[Path]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for path in paths {
+        check_path(path, &mut issues);
+    }
+    check_overlaps(paths, &mut issues);
+    check_directions(paths, &mut issues);
+    issues
+}
+
+fn check_path(path: &Path, issues: &mut Vec<Issue>) {
+    let points = path.points();
+    if points.len() == 1 {
+        let point = points[0];
+        issues.push(Issue::new(
+            IssueKind::StrayPoint,
+            point.to_kurbo(),
+            Some(point.id),
+        ));
+        return;
+    }
+
+    if !path.is_closed() {
+        let start = path.start_point();
+        let end = points.last().unwrap();
+        issues.push(Issue::new(
+            IssueKind::OpenContour,
+            start.to_kurbo(),
+            vec![start.id, end.id],
+        ));
+    }
+
+    for segment in path.iter_segments() {
+        check_segment(segment, issues);
+    }
+
+    for point in points.iter().filter(|p| p.is_on_curve() && !p.is_smooth()) {
+        check_corner(path, point, issues);
+    }
+}
+
+fn check_segment(segment: Segment, issues: &mut Vec<Issue>) {
+    let start = segment.start();
+    let end = segment.end();
+    if let Segment::Cubic(p0, p1, p2, p3) = segment {
+        for (on, off) in &[(p0, p1), (p3, p2)] {
+            if on.point == off.point {
+                issues.push(Issue::new(
+                    IssueKind::ZeroLengthHandle,
+                    on.to_kurbo(),
+                    vec![on.id, off.id],
+                ));
+            }
+        }
+    }
+
+    let kurbo_seg = segment.to_kurbo();
+    let length = kurbo_seg.arclen(ARCLEN_ACCURACY);
+    if start.point == end.point && length < TINY_SEGMENT_LEN {
+        issues.push(Issue::new(
+            IssueKind::DuplicatePoint,
+            end.to_kurbo(),
+            vec![start.id, end.id],
+        ));
+        return;
+    } else if length < TINY_SEGMENT_LEN {
+        issues.push(Issue::new(
+            IssueKind::TinySegment,
+            kurbo_seg.eval(0.5),
+            segment.ids(),
+        ));
+        return;
+    }
+
+    if let PathSeg::Cubic(cubic) = kurbo_seg {
+        for t in cubic.extrema() {
+            let pos = cubic.eval(t);
+            if pos.distance(cubic.p0) > EXTREMA_TOLERANCE
+                && pos.distance(cubic.p3) > EXTREMA_TOLERANCE
+            {
+                issues.push(Issue::new(IssueKind::MissingExtrema, pos, segment.ids()));
+            }
+        }
+    }
+}
+
+/// Report corner points whose handles are almost, but not quite, in a line.
+fn check_corner(path: &Path, point: &PathPoint, issues: &mut Vec<Issue>) {
+    let (prev, next) = match (path.prev_point(point.id), path.next_point(point.id)) {
+        (Some(prev), Some(next)) => (prev, next),
+        _ => return,
+    };
+    // a corner between two lines is just a corner
+    if prev.is_on_curve() && next.is_on_curve() {
+        return;
+    }
+
+    let incoming = point.to_kurbo() - prev.to_kurbo();
+    let outgoing = next.to_kurbo() - point.to_kurbo();
+    // zero-length handles are reported separately
+    if incoming.hypot() == 0.0 || outgoing.hypot() == 0.0 {
+        return;
+    }
+
+    let angle = incoming
+        .cross(outgoing)
+        .atan2(incoming.dot(outgoing))
+        .abs()
+        .to_degrees();
+    if angle > 0.0 && angle < NEAR_COLLINEAR_DEGREES {
+        issues.push(Issue::new(
+            IssueKind::NearlySmooth,
+            point.to_kurbo(),
+            vec![prev.id, point.id, next.id],
+        ));
+    }
+}
+
+/// Report pairs of closed contours that intersect.
+fn check_overlaps(paths: &[Path], issues: &mut Vec<Issue>) {
+    let closed: Vec<_> = paths
+        .iter()
+        .filter(|path| path.is_closed())
+        .map(|path| {
+            let bez = path.bezier();
+            (path, flatten(&bez), bez.bounding_box())
+        })
+        .collect();
+
+    for (i, (path, lines, bbox)) in closed.iter().enumerate() {
+        for (other, other_lines, other_bbox) in &closed[i + 1..] {
+            if bbox.intersect(*other_bbox).area() == 0.0 {
+                continue;
+            }
+            if let Some(pos) = first_intersection(lines, other_lines) {
+                let points = path.points().iter().chain(other.points()).map(|p| p.id);
+                issues.push(Issue::new(IssueKind::OverlappingContours, pos, points));
+            }
+        }
+    }
+}
+
+/// Report closed contours that run the wrong way.
+///
+/// We use the PostScript convention: outer contours run counter-clockwise,
+/// and the direction alternates with each level of nesting.
+fn check_directions(paths: &[Path], issues: &mut Vec<Issue>) {
+    let beziers: Vec<_> = paths
+        .iter()
+        .map(|path| Some(path.bezier()).filter(|_| path.is_closed()))
+        .collect();
+
+    for (i, path) in paths.iter().enumerate() {
+        let bez = match &beziers[i] {
+            Some(bez) => bez,
+            None => continue,
+        };
+        let area = bez.area();
+        if area == 0.0 {
+            continue;
+        }
+
+        let probe = path.start_point().to_kurbo();
+        let depth = beziers
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .filter_map(|(_, other)| other.as_ref())
+            .filter(|other| other.winding(probe) != 0)
+            .count();
+        // kurbo's area is positive for counter-clockwise paths when y is up
+        let is_counter_clockwise = area > 0.0;
+        if is_counter_clockwise != (depth % 2 == 0) {
+            issues.push(Issue::new(
+                IssueKind::WrongDirection,
+                probe,
+                path.points().iter().map(|p| p.id),
+            ));
+        }
+    }
+}
+
+/// Approximate a path with a series of lines.
+fn flatten(bez: &BezPath) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut start = Point::ZERO;
+    let mut last = Point::ZERO;
+    bez.flatten(FLATTEN_TOLERANCE, |el| match el {
+        PathEl::MoveTo(p) => {
+            start = p;
+            last = p;
+        }
+        PathEl::LineTo(p) => {
+            lines.push(Line::new(last, p));
+            last = p;
+        }
+        PathEl::ClosePath => {
+            if last != start {
+                lines.push(Line::new(last, start));
+            }
+            last = start;
+        }
+        _ => (),
+    });
+    lines
+}
+
+fn first_intersection(lines: &[Line], others: &[Line]) -> Option<Point> {
+    lines
+        .iter()
+        .flat_map(|line| others.iter().map(move |other| (line, other)))
+        .find_map(|(line, other)| line_intersection(*line, *other))
+}
+
+fn line_intersection(a: Line, b: Line) -> Option<Point> {
+    let r = a.p1 - a.p0;
+    let s = b.p1 - b.p0;
+    let denom = r.cross(s);
+    // parallel lines; we don't report overlapping collinear edges
+    if denom.abs() < f64::EPSILON {
+        return None;
+    }
+    let offset = b.p0 - a.p0;
+    let t = offset.cross(s) / denom;
+    let u = offset.cross(r) / denom;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(a.p0 + r * t)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use druid::kurbo::Rect;
+
+    fn path(bez: BezPath) -> Path {
+        Path::from_bezpath(bez).unwrap()
+    }
+
+    fn kinds(paths: &[Path]) -> Vec<IssueKind> {
+        check_paths(paths).iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn clean_rect() {
+        let rect = path(Rect::new(0., 0., 100., 100.).to_path(0.1));
+        assert!(kinds(&[rect]).is_empty());
+    }
+
+    #[test]
+    fn open_contour_and_stray_point() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.line_to((100., 0.));
+        bez.line_to((100., 100.));
+        assert_eq!(kinds(&[path(bez)]), vec![IssueKind::OpenContour]);
+
+        let mut bez = BezPath::new();
+        bez.move_to((10., 10.));
+        assert_eq!(kinds(&[path(bez)]), vec![IssueKind::StrayPoint]);
+    }
+
+    #[test]
+    fn duplicate_and_tiny() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        bez.line_to((100., 0.));
+        bez.line_to((100., 0.));
+        bez.line_to((100., 100.));
+        bez.line_to((101., 100.));
+        bez.close_path();
+        let found = kinds(&[path(bez)]);
+        assert!(found.contains(&IssueKind::DuplicatePoint));
+        assert!(found.contains(&IssueKind::TinySegment));
+    }
+
+    #[test]
+    fn missing_extrema() {
+        let mut bez = BezPath::new();
+        bez.move_to((0., 0.));
+        // this curve bulges well above y = 100 between its end points.
+        bez.curve_to((0., 200.), (100., 200.), (100., 0.));
+        bez.close_path();
+        assert!(kinds(&[path(bez)]).contains(&IssueKind::MissingExtrema));
+    }
+
+    #[test]
+    fn direction_and_overlap() {
+        let outer = path(Rect::new(0., 0., 100., 100.).to_path(0.1));
+        // a counter-clockwise counter is the wrong way round
+        let counter = path(Rect::new(25., 25., 75., 75.).to_path(0.1));
+        assert_eq!(
+            kinds(&[outer.clone(), counter.clone()]),
+            vec![IssueKind::WrongDirection]
+        );
+
+        let mut counter = counter;
+        counter.reverse_contour();
+        assert!(kinds(&[outer.clone(), counter]).is_empty());
+
+        let overlapping = path(Rect::new(50., 50., 150., 150.).to_path(0.1));
+        assert!(kinds(&[outer, overlapping]).contains(&IssueKind::OverlappingContours));
+    }
+
+    #[test]
+    fn lines_intersect() {
+        let a = Line::new((0., 0.), (10., 10.));
+        let b = Line::new((0., 10.), (10., 0.));
+        assert_eq!(line_intersection(a, b), Some(Point::new(5., 5.)));
+        let c = Line::new((20., 0.), (30., 10.));
+        assert_eq!(line_intersection(a, c), None);
+    }
+}
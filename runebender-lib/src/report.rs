@@ -0,0 +1,684 @@
+//! A quality report covering a whole font.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::sync::Arc;
+
+use druid::kurbo::{Affine, BezPath, Rect, Shape};
+use norad::{Glyph, GlyphName, Ufo};
+
+use crate::component::{check_component, ComponentProblem};
+use crate::data::{FontMetrics, Workspace};
+use crate::path::Path;
+use crate::qa;
+
+/// Components nested deeper than this are assumed to be part of a cycle.
+const MAX_COMPONENT_DEPTH: usize = 32;
+
+const FIGURES: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Glyphs that are expected to have no outline.
+const BLANK_GLYPHS: &[&str] = &[
+    ".null",
+    "NULL",
+    "CR",
+    "nonmarkingreturn",
+    "space",
+    "nbspace",
+];
+
+/// The kinds of problem included in a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    Outline,
+    MissingUnicode,
+    EmptyGlyph,
+    MissingComponent,
+    ComponentCycle,
+    InconsistentAdvance,
+    VerticalMetrics,
+}
+
+/// A single problem in a report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportItem {
+    pub category: Category,
+    /// The glyph with the problem, or `None` for problems with the font itself.
+    pub glyph: Option<String>,
+    pub message: String,
+}
+
+/// The problems found in a font.
+#[derive(Debug, Clone, Serialize)]
+pub struct FontReport {
+    pub font: String,
+    pub glyph_count: usize,
+    pub items: Vec<ReportItem>,
+}
+
+impl Category {
+    pub fn title(self) -> &'static str {
+        match self {
+            Category::Outline => "Outline problems",
+            Category::MissingUnicode => "Missing unicodes",
+            Category::EmptyGlyph => "Empty glyphs",
+            Category::MissingComponent => "Missing components",
+            Category::ComponentCycle => "Component cycles",
+            Category::InconsistentAdvance => "Inconsistent advances",
+            Category::VerticalMetrics => "Vertical metrics",
+        }
+    }
+}
+
+impl ReportItem {
+    fn glyph(category: Category, glyph: &str, message: impl Into<String>) -> Self {
+        ReportItem {
+            category,
+            glyph: Some(glyph.to_string()),
+            message: message.into(),
+        }
+    }
+
+    fn font(category: Category, message: impl Into<String>) -> Self {
+        ReportItem {
+            category,
+            glyph: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl FontReport {
+    /// Check a font loaded from disk.
+    pub fn for_ufo(ufo: &Ufo) -> FontReport {
+        let info = ufo.font_info.as_ref();
+        let name = font_name(
+            info.and_then(|i| i.family_name.as_deref()),
+            info.and_then(|i| i.style_name.as_deref()),
+        );
+        let metrics = info.map(FontMetrics::from).unwrap_or_default();
+        let glyphs = ufo
+            .iter_names()
+            .filter_map(|name| ufo.get_glyph(&name).map(|g| (name, g.clone())))
+            .collect();
+        FontReport::build(name, &metrics, &glyphs)
+    }
+
+    /// Check an open font, including any unsaved edits.
+    pub fn for_workspace(workspace: &Workspace) -> FontReport {
+        let name = font_name(
+            Some(&*workspace.info.family_name),
+            Some(&*workspace.info.style_name),
+        );
+        FontReport::build(name, &workspace.info.metrics, &current_glyphs(workspace))
+    }
+
+    fn build(
+        font: String,
+        metrics: &FontMetrics,
+        glyphs: &BTreeMap<GlyphName, Arc<Glyph>>,
+    ) -> FontReport {
+        let mut items = Vec::new();
+        for (name, glyph) in glyphs {
+            check_glyph(name, glyph, &mut items);
+            check_components(name, glyph, glyphs, &mut items);
+        }
+        check_advances(glyphs, &mut items);
+        check_vertical_metrics(metrics, glyphs, &mut items);
+        // keep the order of glyphs within each category
+        items.sort_by_key(|item| item.category);
+
+        FontReport {
+            font,
+            glyph_count: glyphs.len(),
+            items,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The items in the report, grouped by category.
+    pub fn by_category(&self) -> Vec<(Category, &[ReportItem])> {
+        let mut groups = Vec::new();
+        let mut start = 0;
+        for i in 1..=self.items.len() {
+            if i == self.items.len() || self.items[i].category != self.items[start].category {
+                groups.push((self.items[start].category, &self.items[start..i]));
+                start = i;
+            }
+        }
+        groups
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report serialization cannot fail")
+    }
+
+    /// A standalone HTML page describing the report.
+    pub fn to_html(&self) -> String {
+        let title = format!("QA report: {}", escape_html(&self.font));
+        let mut html = String::new();
+        // writing to a String cannot fail
+        let _ = writeln!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; margin: 2em; }}\n\
+             table {{ border-collapse: collapse; margin-bottom: 2em; }}\n\
+             td, th {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}\n\
+             </style>\n</head>\n<body>\n<h1>{}</h1>",
+            title, title
+        );
+        let _ = writeln!(
+            html,
+            "<p>{} glyphs, {} problems.</p>",
+            self.glyph_count,
+            self.items.len()
+        );
+        for (category, items) in self.by_category() {
+            let _ = writeln!(
+                html,
+                "<h2>{} ({})</h2>\n<table>\n<tr><th>Glyph</th><th>Problem</th></tr>",
+                category.title(),
+                items.len()
+            );
+            for item in items {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    escape_html(item.glyph.as_deref().unwrap_or("—")),
+                    escape_html(&item.message)
+                );
+            }
+            let _ = writeln!(html, "</table>");
+        }
+        let _ = writeln!(html, "</body>\n</html>");
+        html
+    }
+}
+
+fn font_name(family: Option<&str>, style: Option<&str>) -> String {
+    match (family, style) {
+        (Some(family), Some(style)) if !style.is_empty() => format!("{} {}", family, style),
+        (Some(family), _) => family.to_string(),
+        _ => String::from("Untitled"),
+    }
+}
+
+/// Whether each glyph has problems found by the checks that only look at
+/// that glyph, kept until the glyph changes.
+///
+/// This lets the glyph grid find the glyphs with problems without checking
+/// every outline again after each edit.
+#[derive(Default)]
+pub struct GlyphReportCache {
+    glyphs: HashMap<GlyphName, (Arc<Glyph>, bool)>,
+}
+
+impl GlyphReportCache {
+    /// The names of the glyphs in `workspace` that have problems.
+    ///
+    /// This is every glyph that `FontReport::for_workspace` would report.
+    pub fn glyphs_with_errors(&mut self, workspace: &Workspace) -> BTreeSet<String> {
+        let glyphs = current_glyphs(workspace);
+        self.glyphs.retain(|name, _| glyphs.contains_key(name));
+
+        let mut names = BTreeSet::new();
+        // the checks that compare glyphs with each other are cheap, so we
+        // always rerun them.
+        let mut items = Vec::new();
+        for (name, glyph) in glyphs.iter() {
+            let has_errors = match self.glyphs.get(name) {
+                Some((checked, has_errors)) if Arc::ptr_eq(checked, glyph) => *has_errors,
+                _ => {
+                    let mut glyph_items = Vec::new();
+                    check_glyph(name, glyph, &mut glyph_items);
+                    let has_errors = !glyph_items.is_empty();
+                    self.glyphs
+                        .insert(name.clone(), (glyph.clone(), has_errors));
+                    has_errors
+                }
+            };
+            if has_errors {
+                names.insert(name.to_string());
+            }
+            check_components(name, glyph, &glyphs, &mut items);
+        }
+        check_advances(&glyphs, &mut items);
+        names.extend(items.into_iter().filter_map(|item| item.glyph));
+        names
+    }
+}
+
+/// The glyphs in an open font, including any unsaved edits.
+fn current_glyphs(workspace: &Workspace) -> BTreeMap<GlyphName, Arc<Glyph>> {
+    let ufo = &workspace.font.ufo;
+    let mut glyphs: BTreeMap<_, _> = ufo
+        .iter_names()
+        .filter_map(|name| ufo.get_glyph(&name).map(|g| (name, g.clone())))
+        .collect();
+    for session in workspace.sessions.values() {
+        glyphs.insert(session.name.clone(), session.glyph.clone());
+    }
+    glyphs
+}
+
+/// The checks that depend only on the glyph itself.
+fn check_glyph(name: &str, glyph: &Glyph, items: &mut Vec<ReportItem>) {
+    check_outlines(name, glyph, items);
+    check_unicode(name, glyph, items);
+    check_empty(name, glyph, items);
+}
+
+fn check_outlines(name: &str, glyph: &Glyph, items: &mut Vec<ReportItem>) {
+    let paths: Vec<_> = match glyph.outline.as_ref() {
+        Some(outline) => outline
+            .contours
+            .iter()
+            .filter(|contour| !contour.points.is_empty())
+            .map(Path::from_norad)
+            .collect(),
+        None => return,
+    };
+    for issue in qa::check_paths(&paths) {
+        let message = format!(
+            "{} at ({}, {})",
+            issue.kind.description(),
+            issue.pos.x.round(),
+            issue.pos.y.round()
+        );
+        items.push(ReportItem::glyph(Category::Outline, name, message));
+    }
+}
+
+/// Report glyphs whose name implies a codepoint that they don't have.
+fn check_unicode(name: &str, glyph: &Glyph, items: &mut Vec<ReportItem>) {
+    let has_codepoints = glyph
+        .codepoints
+        .as_ref()
+        .map(|cps| !cps.is_empty())
+        .unwrap_or(false);
+    if has_codepoints {
+        return;
+    }
+    if let Some(chr) = expected_codepoint(name) {
+        let message = format!("expected U+{:04X}", chr as u32);
+        items.push(ReportItem::glyph(Category::MissingUnicode, name, message));
+    }
+}
+
+/// The codepoint implied by a glyph's name, if any.
+///
+/// Names with a suffix (such as `a.alt`) are alternates, and are not
+/// expected to be encoded.
+fn expected_codepoint(name: &str) -> Option<char> {
+    crate::glyph_names::codepoints_for_glyph(name).and_then(|cps| cps.first().copied())
+}
+
+fn check_empty(name: &str, glyph: &Glyph, items: &mut Vec<ReportItem>) {
+    let has_outline = glyph
+        .outline
+        .as_ref()
+        .map(|ol| !ol.components.is_empty() || ol.contours.iter().any(|c| !c.points.is_empty()))
+        .unwrap_or(false);
+    if has_outline || BLANK_GLYPHS.contains(&name) {
+        return;
+    }
+    let is_whitespace = glyph
+        .codepoints
+        .iter()
+        .flatten()
+        .copied()
+        .chain(expected_codepoint(name))
+        .any(|chr| chr.is_whitespace() || chr.is_control());
+    if !is_whitespace {
+        items.push(ReportItem::glyph(Category::EmptyGlyph, name, "no outline"));
+    }
+}
+
+fn check_components(
+    name: &str,
+    glyph: &Glyph,
+    glyphs: &BTreeMap<GlyphName, Arc<Glyph>>,
+    items: &mut Vec<ReportItem>,
+) {
+    let get_glyph = |name: &GlyphName| glyphs.get(name).map(|g| &**g);
+    for component in glyph.outline.iter().flat_map(|ol| ol.components.iter()) {
+        let problem = match check_component(&glyph.name, &component.base, get_glyph) {
+            Some(problem) => problem,
+            None => continue,
+        };
+        let category = match problem {
+            ComponentProblem::MissingBase => Category::MissingComponent,
+            ComponentProblem::Cycle => Category::ComponentCycle,
+        };
+        let message = format!("component '{}': {}", component.base, problem.description());
+        items.push(ReportItem::glyph(category, name, message));
+    }
+}
+
+fn advance(glyph: &Glyph) -> f64 {
+    glyph
+        .advance
+        .as_ref()
+        .map(|a| a.width as f64)
+        .unwrap_or(0.0)
+}
+
+/// Report glyphs whose advance differs from glyphs it should match.
+///
+/// These are the figures in each set (which are assumed to be tabular)
+/// and composites built on an unshifted base glyph, such as accented
+/// letters.
+#[allow(clippy::float_cmp)]
+fn check_advances(glyphs: &BTreeMap<GlyphName, Arc<Glyph>>, items: &mut Vec<ReportItem>) {
+    // figures, grouped by suffix (so that "one.osf" goes with "two.osf")
+    let mut figure_sets: BTreeMap<&str, Vec<&Glyph>> = BTreeMap::new();
+    for (name, glyph) in glyphs {
+        let (base, suffix) = match name.find('.') {
+            Some(idx) => (&name[..idx], &name[idx..]),
+            None => (&name[..], ""),
+        };
+        if FIGURES.contains(&base) && !suffix.contains("pnum") {
+            figure_sets.entry(suffix).or_default().push(glyph);
+        }
+    }
+
+    for figures in figure_sets.values().filter(|figs| figs.len() > 1) {
+        let mut counts: HashMap<i64, usize> = HashMap::new();
+        for glyph in figures {
+            *counts.entry(advance(glyph).round() as i64).or_default() += 1;
+        }
+        let (common, _) = counts
+            .iter()
+            .max_by_key(|(width, count)| (**count, -**width))
+            .unwrap();
+        for glyph in figures {
+            let width = advance(glyph).round() as i64;
+            if width != *common {
+                let message = format!(
+                    "advance {} differs from the other figures ({})",
+                    width, common
+                );
+                items.push(ReportItem::glyph(
+                    Category::InconsistentAdvance,
+                    &glyph.name,
+                    message,
+                ));
+            }
+        }
+    }
+
+    for (name, glyph) in glyphs {
+        let outline = match glyph.outline.as_ref() {
+            Some(outline) if outline.contours.is_empty() => outline,
+            _ => continue,
+        };
+        let first = match outline.components.first() {
+            Some(first) => first,
+            None => continue,
+        };
+        let transform: Affine = first.transform.into();
+        if transform.as_coeffs() != Affine::default().as_coeffs() {
+            continue;
+        }
+        if let Some(base) = glyphs.get(&first.base) {
+            let (width, base_width) = (advance(glyph).round(), advance(base).round());
+            if width != base_width {
+                let message = format!(
+                    "advance {} differs from its base '{}' ({})",
+                    width, first.base, base_width
+                );
+                items.push(ReportItem::glyph(
+                    Category::InconsistentAdvance,
+                    name,
+                    message,
+                ));
+            }
+        }
+    }
+}
+
+/// Report font metrics that don't agree with the glyphs.
+#[allow(clippy::float_cmp)]
+fn check_vertical_metrics(
+    metrics: &FontMetrics,
+    glyphs: &BTreeMap<GlyphName, Arc<Glyph>>,
+    items: &mut Vec<ReportItem>,
+) {
+    let bounds: Vec<(&GlyphName, Rect)> = glyphs
+        .keys()
+        .filter_map(|name| {
+            let bez = resolved_bezier(name, glyphs, 0)?;
+            if bez.elements().is_empty() {
+                None
+            } else {
+                Some((name, bez.bounding_box()))
+            }
+        })
+        .collect();
+
+    let tallest = bounds
+        .iter()
+        .max_by(|a, b| a.1.max_y().partial_cmp(&b.1.max_y()).unwrap());
+    if let (Some(ascender), Some((name, rect))) = (metrics.ascender, tallest) {
+        if rect.max_y().round() > ascender {
+            let message = format!(
+                "'{}' reaches {}, above the ascender ({})",
+                name,
+                rect.max_y().round(),
+                ascender
+            );
+            items.push(ReportItem::font(Category::VerticalMetrics, message));
+        }
+    }
+
+    let deepest = bounds
+        .iter()
+        .min_by(|a, b| a.1.min_y().partial_cmp(&b.1.min_y()).unwrap());
+    if let (Some(descender), Some((name, rect))) = (metrics.descender, deepest) {
+        if rect.min_y().round() < descender {
+            let message = format!(
+                "'{}' reaches {}, below the descender ({})",
+                name,
+                rect.min_y().round(),
+                descender
+            );
+            items.push(ReportItem::font(Category::VerticalMetrics, message));
+        }
+    }
+
+    let reference_heights = [
+        ("x-height", metrics.x_height, "x"),
+        ("cap height", metrics.cap_height, "H"),
+    ];
+    for (metric, value, reference) in reference_heights.iter() {
+        let top = bounds
+            .iter()
+            .find(|(name, _)| &***name == *reference)
+            .map(|(_, rect)| rect.max_y().round());
+        if let (Some(value), Some(top)) = (value, top) {
+            if top != *value {
+                let message = format!(
+                    "the {} is {}, but '{}' is {} tall",
+                    metric, value, reference, top
+                );
+                items.push(ReportItem::font(Category::VerticalMetrics, message));
+            }
+        }
+    }
+}
+
+/// The outline of a glyph, including its components.
+fn resolved_bezier(
+    name: &GlyphName,
+    glyphs: &BTreeMap<GlyphName, Arc<Glyph>>,
+    depth: usize,
+) -> Option<BezPath> {
+    if depth > MAX_COMPONENT_DEPTH {
+        return None;
+    }
+    let glyph = glyphs.get(name)?;
+    let mut path = crate::data::path_for_glyph(glyph).unwrap_or_default();
+    for component in glyph.outline.iter().flat_map(|ol| ol.components.iter()) {
+        if let Some(base) = resolved_bezier(&component.base, glyphs, depth + 1) {
+            let transform: Affine = component.transform.into();
+            path.extend((transform * base).elements().iter().copied());
+        }
+    }
+    Some(path)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use norad::glyph::{Advance, Component, Contour, ContourPoint, Outline, PointType};
+
+    fn glyph(name: &str, width: f32, contours: Vec<Contour>, components: Vec<Component>) -> Glyph {
+        let mut glyph = Glyph::new_named(name);
+        glyph.advance = Some(Advance { width, height: 0.0 });
+        if !contours.is_empty() || !components.is_empty() {
+            glyph.outline = Some(Outline {
+                contours,
+                components,
+            });
+        }
+        glyph
+    }
+
+    fn rect(x0: f32, y0: f32, x1: f32, y1: f32) -> Contour {
+        let points = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)]
+            .iter()
+            .map(|(x, y)| ContourPoint::new(*x, *y, PointType::Line, false, None, None, None))
+            .collect();
+        Contour::new(points, None, None)
+    }
+
+    fn report(glyphs: Vec<Glyph>, metrics: FontMetrics) -> FontReport {
+        let glyphs = glyphs
+            .into_iter()
+            .map(|g| (g.name.clone(), Arc::new(g)))
+            .collect();
+        FontReport::build("Test".into(), &metrics, &glyphs)
+    }
+
+    fn categories(report: &FontReport) -> Vec<(Category, Option<&str>)> {
+        report
+            .items
+            .iter()
+            .map(|item| (item.category, item.glyph.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn glyph_checks() {
+        let base = Component::new("nope".into(), Affine::default().into(), None, None);
+        let cycle = Component::new("c.alt".into(), Affine::default().into(), None, None);
+        let glyphs = vec![
+            glyph("H", 600., vec![rect(0., 0., 600., 700.)], vec![]),
+            glyph("space", 250., vec![], vec![]),
+            glyph("a", 500., vec![], vec![]),
+            glyph("b.alt", 500., vec![], vec![base]),
+            glyph("c.alt", 500., vec![], vec![cycle]),
+        ];
+        let mut glyphs = glyphs;
+        glyphs[0].codepoints = Some(vec!['H']);
+        glyphs[1].codepoints = Some(vec![' ']);
+        let report = report(glyphs, FontMetrics::default());
+        assert_eq!(
+            categories(&report),
+            vec![
+                (Category::MissingUnicode, Some("a")),
+                (Category::EmptyGlyph, Some("a")),
+                (Category::MissingComponent, Some("b.alt")),
+                (Category::ComponentCycle, Some("c.alt")),
+            ]
+        );
+    }
+
+    #[test]
+    fn advances() {
+        let figure = |name| glyph(name, 550., vec![rect(0., 0., 500., 700.)], vec![]);
+        let mut one = figure("one");
+        one.advance = Some(Advance {
+            width: 400.,
+            height: 0.,
+        });
+        let base = Component::new("two".into(), Affine::default().into(), None, None);
+        let composite = glyph("two.alt", 500., vec![], vec![base]);
+        let glyphs = vec![figure("zero"), one, figure("two"), composite];
+        let report = report(glyphs, FontMetrics::default());
+        assert_eq!(
+            categories(&report)
+                .into_iter()
+                .filter(|(cat, _)| *cat == Category::InconsistentAdvance)
+                .collect::<Vec<_>>(),
+            vec![
+                (Category::InconsistentAdvance, Some("one")),
+                (Category::InconsistentAdvance, Some("two.alt")),
+            ]
+        );
+    }
+
+    #[test]
+    fn vertical_metrics() {
+        let metrics = FontMetrics {
+            ascender: Some(800.),
+            descender: Some(-200.),
+            cap_height: Some(700.),
+            x_height: Some(500.),
+            ..Default::default()
+        };
+        let mut tall = glyph("H", 600., vec![rect(0., 0., 600., 900.)], vec![]);
+        tall.codepoints = Some(vec!['H']);
+        let report = report(vec![tall], metrics);
+        let messages: Vec<_> = report.items.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'H' reaches 900, above the ascender (800)",
+                "the cap height is 700, but 'H' is 900 tall",
+            ]
+        );
+    }
+
+    #[test]
+    fn expected_codepoints() {
+        assert_eq!(expected_codepoint("uni00E9"), Some('é'));
+        assert_eq!(expected_codepoint("u1F600"), Some('😀'));
+        assert_eq!(expected_codepoint("eacute"), Some('é'));
+        assert_eq!(expected_codepoint("eacute.alt"), None);
+        assert_eq!(expected_codepoint("myglyph"), None);
+        assert_eq!(expected_codepoint("uni0431.sc"), None);
+        assert_eq!(expected_codepoint("f_i"), None);
+    }
+
+    #[test]
+    fn html_is_escaped() {
+        let mut report = report(vec![], FontMetrics::default());
+        report
+            .items
+            .push(ReportItem::glyph(Category::Outline, "<script>", "a & b"));
+        let html = report.to_html();
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("a &amp; b"));
+    }
+}
@@ -0,0 +1,319 @@
+//! Writing changes to a UFO that already exists on disk.
+//!
+//! Rather than rewriting the whole font, we only touch the files for glyphs
+//! and plists that have changed since the last save, and we reuse existing
+//! glyph file names; this keeps saves fast and diffs quiet.
+
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use norad::{GlyphName, Ufo};
+
+use crate::data::SavedState;
+
+const GLYPHS_DIR: &str = "glyphs";
+const CONTENTS_FILE: &str = "contents.plist";
+const FONTINFO_FILE: &str = "fontinfo.plist";
+const LIB_FILE: &str = "lib.plist";
+const GROUPS_FILE: &str = "groups.plist";
+const KERNING_FILE: &str = "kerning.plist";
+
+const GLIF_SUFFIX: &str = ".glif";
+const MAX_FILE_NAME_LEN: usize = 255;
+
+/// Write the parts of `ufo` that have changed since `saved` to the UFO at `path`.
+///
+/// `changed` is the list of glyphs that have been added, removed, or modified.
+/// Only the default layer is written; other layers are left as they are.
+pub(crate) fn save_changes(
+    ufo: &Ufo,
+    path: &Path,
+    changed: &[GlyphName],
+    saved: &SavedState,
+) -> Result<(), Box<dyn Error>> {
+    let glyph_dir = path.join(GLYPHS_DIR);
+    let contents_path = glyph_dir.join(CONTENTS_FILE);
+    let mut contents = plist::Value::from_file(&contents_path)?
+        .into_dictionary()
+        .ok_or("contents.plist is not a dictionary")?;
+    let mut existing_files: HashSet<String> = contents
+        .values()
+        .filter_map(|v| v.as_string())
+        .map(str::to_lowercase)
+        .collect();
+    let mut contents_changed = false;
+
+    for name in changed {
+        match ufo.get_glyph(name) {
+            Some(glyph) => {
+                let file_name = match contents.get(name).and_then(|v| v.as_string()) {
+                    Some(file_name) => file_name.to_owned(),
+                    None => {
+                        let file_name = user_name_to_file_name(name, &existing_files);
+                        existing_files.insert(file_name.to_lowercase());
+                        contents.insert(name.to_string(), file_name.clone().into());
+                        contents_changed = true;
+                        file_name
+                    }
+                };
+                log::debug!("writing {}", file_name);
+                glyph.save(glyph_dir.join(file_name))?;
+            }
+            None => {
+                if let Some(file_name) = contents.remove(name) {
+                    contents_changed = true;
+                    if let Some(file_name) = file_name.as_string() {
+                        existing_files.remove(&file_name.to_lowercase());
+                        let glif_path = glyph_dir.join(file_name);
+                        if glif_path.exists() {
+                            fs::remove_file(glif_path)?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if contents_changed {
+        // sorted, to match the order norad uses when writing the whole font.
+        let contents: BTreeMap<String, String> = contents
+            .into_iter()
+            .filter_map(|(name, file)| file.into_string().map(|file| (name, file)))
+            .collect();
+        plist::to_file_xml(&contents_path, &contents)?;
+    }
+
+    if ufo.font_info != saved.font_info {
+        write_or_remove(&path.join(FONTINFO_FILE), ufo.font_info.as_ref())?;
+    }
+    if ufo.lib != saved.lib {
+        // `Dictionary` is not `Serialize`, so this is written the way norad
+        // writes it.
+        let lib_path = path.join(LIB_FILE);
+        match ufo.lib.clone() {
+            Some(lib) => plist::Value::Dictionary(lib).to_file_xml(&lib_path)?,
+            None if lib_path.exists() => fs::remove_file(&lib_path)?,
+            None => (),
+        }
+    }
+    if ufo.groups != saved.groups {
+        write_or_remove(&path.join(GROUPS_FILE), ufo.groups.as_ref())?;
+    }
+    if ufo.kerning != saved.kerning {
+        write_or_remove(&path.join(KERNING_FILE), ufo.kerning.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Write `value` as an xml plist to `path`, or remove the file if `value` is `None`.
+fn write_or_remove<T: serde::Serialize>(
+    path: &Path,
+    value: Option<&T>,
+) -> Result<(), Box<dyn Error>> {
+    match value {
+        Some(value) => plist::to_file_xml(path, value)?,
+        None if path.exists() => fs::remove_file(path)?,
+        None => (),
+    }
+    Ok(())
+}
+
+/// Generate a file name for a glyph, following the algorithm in the UFO spec.
+///
+/// `existing` is the set of file names already in use, lowercased; names are
+/// compared case-insensitively to be safe on case-insensitive filesystems.
+///
+/// See <http://unifiedfontobject.org/versions/ufo3/conventions/#common-user-name-to-file-name-algorithm>
+fn user_name_to_file_name(name: &str, existing: &HashSet<String>) -> String {
+    let mut result = String::with_capacity(name.len() + GLIF_SUFFIX.len());
+    for (i, c) in name.chars().enumerate() {
+        match c {
+            '.' if i == 0 => result.push('_'),
+            '"' | '*' | '+' | '/' | ':' | '<' | '>' | '?' | '[' | '\\' | ']' | '|' => {
+                result.push('_')
+            }
+            c if (c as u32) < 0x20 || c as u32 == 0x7f => result.push('_'),
+            c if c.is_uppercase() => {
+                result.push(c);
+                result.push('_');
+            }
+            c => result.push(c),
+        }
+    }
+
+    // prefix any reserved windows file names
+    let result = result
+        .split('.')
+        .map(|part| {
+            if is_reserved_file_name(part) {
+                format!("_{}", part)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+
+    let max_len = MAX_FILE_NAME_LEN - GLIF_SUFFIX.len();
+    let base = truncate(&result, max_len);
+    let candidate = format!("{}{}", base, GLIF_SUFFIX);
+    if !existing.contains(&candidate.to_lowercase()) {
+        return candidate;
+    }
+
+    // on a clash, append a 15 digit counter
+    let base = truncate(&result, max_len - 15);
+    (1..)
+        .map(|i| format!("{}{:015}{}", base, i, GLIF_SUFFIX))
+        .find(|candidate| !existing.contains(&candidate.to_lowercase()))
+        .unwrap()
+}
+
+fn is_reserved_file_name(name: &str) -> bool {
+    const RESERVED: &[&str] = &[
+        "con", "prn", "aux", "clock$", "nul", "a:-z:", "com1", "lpt1", "lpt2", "lpt3", "com2",
+        "com3", "com4",
+    ];
+    RESERVED.contains(&name.to_lowercase().as_str())
+}
+
+/// Truncate a string to at most `len` bytes, respecting char boundaries.
+fn truncate(s: &str, len: usize) -> &str {
+    if s.len() <= len {
+        return s;
+    }
+    let mut end = len;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charset::GlyphSpec;
+    use crate::data::{Workspace, GLYPH_ORDER_KEY};
+    use norad::Glyph;
+
+    fn file_name(name: &str) -> String {
+        user_name_to_file_name(name, &HashSet::new())
+    }
+
+    #[test]
+    fn file_names() {
+        assert_eq!(file_name("a"), "a.glif");
+        assert_eq!(file_name("A"), "A_.glif");
+        assert_eq!(file_name("AE"), "A_E_.glif");
+        assert_eq!(file_name("Ae"), "A_e.glif");
+        assert_eq!(file_name("T_H"), "T__H_.glif");
+        assert_eq!(file_name(".notdef"), "_notdef.glif");
+        assert_eq!(file_name("a.alt"), "a.alt.glif");
+        assert_eq!(file_name("con"), "_con.glif");
+        assert_eq!(file_name("a.com1"), "a._com1.glif");
+        assert_eq!(file_name("a/b"), "a_b.glif");
+    }
+
+    #[test]
+    fn file_name_clash() {
+        let mut existing = HashSet::new();
+        existing.insert("a.glif".to_string());
+        assert_eq!(
+            user_name_to_file_name("a", &existing),
+            "a000000000000001.glif"
+        );
+        existing.insert("a000000000000001.glif".to_string());
+        assert_eq!(
+            user_name_to_file_name("a", &existing),
+            "a000000000000002.glif"
+        );
+    }
+
+    #[test]
+    fn file_name_max_len() {
+        let long_name = "a".repeat(300);
+        let name = file_name(&long_name);
+        assert_eq!(name.len(), MAX_FILE_NAME_LEN);
+        assert!(name.ends_with(GLIF_SUFFIX));
+    }
+
+    #[test]
+    fn save_only_changes() {
+        let dir = std::env::temp_dir().join(format!("runebender-save-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Test.ufo");
+
+        let mut ufo = Ufo::new();
+        let layer = ufo.get_default_layer_mut().unwrap();
+        for name in &["A", "B", "C"] {
+            layer.insert_glyph(Glyph::new_named(*name));
+        }
+        ufo.font_info = Some(norad::FontInfo {
+            family_name: Some("Test".into()),
+            ..Default::default()
+        });
+        let mut lib = plist::Dictionary::new();
+        let order = vec!["A".into(), "B".into(), "C".into()];
+        lib.insert(GLYPH_ORDER_KEY.into(), plist::Value::Array(order));
+        ufo.lib = Some(lib);
+        let mut pairs = BTreeMap::new();
+        pairs.insert("B".to_string(), -20.0);
+        let mut kerning = BTreeMap::new();
+        kerning.insert("A".to_string(), pairs);
+        ufo.kerning = Some(kerning);
+        let mut workspace = Workspace::default();
+        workspace.set_file(ufo, path.clone());
+        workspace.save().unwrap();
+
+        // files that should not be rewritten keep this marker.
+        let marker = "<!-- untouched -->";
+        let glyph_dir = path.join(GLYPHS_DIR);
+        let untouched = [
+            glyph_dir.join("A_.glif"),
+            path.join(FONTINFO_FILE),
+            path.join(KERNING_FILE),
+        ];
+        for file in &untouched {
+            let mut text = fs::read_to_string(file).unwrap();
+            text.push_str(marker);
+            fs::write(file, text).unwrap();
+        }
+
+        let mut b = Glyph::new_named("B");
+        b.advance = Some(norad::glyph::Advance {
+            width: 600.0,
+            height: 0.0,
+        });
+        workspace
+            .font_mut()
+            .ufo
+            .get_default_layer_mut()
+            .unwrap()
+            .insert_glyph(b);
+        workspace.delete_glyph(&"C".into()).unwrap();
+        workspace.add_glyphs(&[GlyphSpec {
+            name: "D".into(),
+            codepoints: Vec::new(),
+        }]);
+        workspace.save().unwrap();
+
+        let read = |file: &Path| fs::read_to_string(file).unwrap();
+        assert!(read(&glyph_dir.join("B_.glif")).contains("width=\"600\""));
+        assert!(!glyph_dir.join("C_.glif").exists());
+        assert!(glyph_dir.join("D_.glif").exists());
+        let contents = read(&glyph_dir.join(CONTENTS_FILE));
+        assert!(contents.contains("D_.glif"));
+        assert!(!contents.contains("C_.glif"));
+        // the glyph order changed, since D was added.
+        assert!(read(&path.join(LIB_FILE)).contains("<string>D</string>"));
+        assert!(untouched.iter().all(|file| read(file).ends_with(marker)));
+
+        let reloaded = Ufo::load(&path).unwrap();
+        assert_eq!(reloaded.iter_names().count(), 3);
+        assert!(reloaded.get_glyph("C").is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
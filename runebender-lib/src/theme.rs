@@ -4,9 +4,12 @@ pub use druid::theme::{
     BACKGROUND_LIGHT, BUTTON_DARK, BUTTON_LIGHT, CURSOR_COLOR, LABEL_COLOR, SELECTION_COLOR,
     UI_FONT, WINDOW_BACKGROUND_COLOR,
 };
-use druid::{Color, Data, Env, FontDescriptor, Key, Widget};
+use druid::widget::{prelude::*, Controller};
+use druid::{Color, FontDescriptor, Key, WidgetExt};
+use druid_theme_loader::{ThemeLoader, SET_THEME_PATH};
 
-const THEME_FILE_PATH: &str = "resources/default.theme";
+use crate::data::AppState;
+use crate::preferences::Theme;
 
 pub const SIDEBAR_BACKGROUND: Key<Color> = Key::new("runebender.sidebar-background");
 pub const SIDEBAR_EDGE_STROKE: Key<Color> = Key::new("runebender.sidebar-edge-stroke");
@@ -22,6 +25,13 @@ pub const GLYPH_GRID_CELL_OUTLINE_COLOR: Key<Color> =
 pub const FOCUS_BACKGROUND_COLOR: Key<Color> = Key::new("runebender.focus-background-color");
 pub const FOCUS_OUTLINE_COLOR: Key<Color> = Key::new("runebender.focus-outline-color");
 
+/// The color of the marker drawn on glyphs with unsaved changes
+pub const MODIFIED_MARKER_COLOR: Key<Color> = Key::new("runebender.modified-marker-color");
+
+/// The colors used to mark errors and warnings in the status bar
+pub const ERROR_COLOR: Key<Color> = Key::new("runebender.error-color");
+pub const WARNING_COLOR: Key<Color> = Key::new("runebender.warning-color");
+
 /// The color for placeholder glyphs
 pub const PLACEHOLDER_GLYPH_COLOR: Key<Color> = Key::new("runebender.placeholder-glyph-color");
 /// The color for primary text, filled glyph outlines, etc
@@ -61,6 +71,10 @@ pub const OFF_CURVE_POINT_INNER_COLOR: Key<Color> =
 pub const OFF_CURVE_HANDLE_COLOR: Key<Color> = Key::new("runebender.off-curve-handle-color");
 pub const DIRECTION_ARROW_COLOR: Key<Color> = Key::new("runebender.direction-arrow-color");
 pub const COMPONENT_FILL_COLOR: Key<Color> = Key::new("runebender.component-fill-color");
+pub const SELECTED_COMPONENT_FILL_COLOR: Key<Color> =
+    Key::new("runebender.selected-component-fill-color");
+/// The color of the markers drawn at outline problems.
+pub const QA_MARKER_COLOR: Key<Color> = Key::new("runebender.qa-marker-color");
 
 // Colors used by tools in the tool menu
 pub const KNIFE_GUIDE: Key<Color> = Key::new("runebender.knife-guide");
@@ -88,6 +102,9 @@ druid_theme_loader::loadable_theme!(pub MyTheme {
     GLYPH_GRID_CELL_OUTLINE_COLOR,
     FOCUS_BACKGROUND_COLOR,
     FOCUS_OUTLINE_COLOR,
+    MODIFIED_MARKER_COLOR,
+    ERROR_COLOR,
+    WARNING_COLOR,
     PRIMARY_TEXT_COLOR,
     SECONDARY_TEXT_COLOR,
     SELECTION_RECT_STROKE_COLOR,
@@ -116,6 +133,8 @@ druid_theme_loader::loadable_theme!(pub MyTheme {
     OFF_CURVE_HANDLE_COLOR,
     DIRECTION_ARROW_COLOR,
     COMPONENT_FILL_COLOR,
+    SELECTED_COMPONENT_FILL_COLOR,
+    QA_MARKER_COLOR,
     KNIFE_GUIDE,
     KNIFE_GUIDE_INTERSECTION,
     SMOOTH_RADIUS,
@@ -127,6 +146,32 @@ druid_theme_loader::loadable_theme!(pub MyTheme {
 
 });
 
-pub fn wrap_in_theme_loader<T: Data>(widget: impl Widget<T>) -> impl Widget<T> {
-    druid_theme_loader::ThemeLoader::new(THEME_FILE_PATH, MyTheme, widget)
+/// Wrap the root widget of a window so that it gets the current theme, and
+/// any preferences that are passed through the `Env`.
+pub fn wrap_in_theme_loader(
+    theme: Theme,
+    widget: impl Widget<AppState> + 'static,
+) -> impl Widget<AppState> {
+    let widget = widget.env_scope(|env, data: &AppState| data.preferences.configure_env(env));
+    ThemeLoader::new(theme.path(), MyTheme, widget).controller(ThemeSwitcher)
+}
+
+/// Reloads the theme when the user picks a different one.
+struct ThemeSwitcher;
+
+impl<W: Widget<AppState>> Controller<AppState, W> for ThemeSwitcher {
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &AppState,
+        data: &AppState,
+        env: &Env,
+    ) {
+        let theme = data.preferences.theme;
+        if old_data.preferences.theme != theme {
+            ctx.submit_command(SET_THEME_PATH.with(theme.path().into()).to(ctx.widget_id()));
+        }
+        child.update(ctx, old_data, data, env)
+    }
 }
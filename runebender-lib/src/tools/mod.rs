@@ -116,7 +116,7 @@ pub trait Tool {
 }
 
 /// Returns the tool for the given `ToolId`.
-pub fn tool_for_id(id: ToolId) -> Option<Box<dyn Tool>> {
+pub fn tool_for_id(id: &str) -> Option<Box<dyn Tool>> {
     match id {
         "Preview" => Some(Box::new(Preview::default())),
         "Pen" => Some(Box::new(Pen::default())),
@@ -1,10 +1,13 @@
-use druid::kurbo::{BezPath, Circle, Insets, Point, Rect, Shape, Vec2};
+use druid::kurbo::{BezPath, Circle, Insets, Line, Point, Rect, Shape, Vec2};
 use druid::piet::{RenderContext, StrokeStyle};
 use druid::{Data, Env, EventCtx, HotKey, KbKey, KeyEvent, MouseEvent, PaintCtx, RawMods};
+use norad::GlyphName;
 
+use crate::app_delegate::EDIT_GLYPH;
 use crate::edit_session::EditSession;
 use crate::mouse::{Drag, Mouse, MouseDelegate, TaggedEvent};
 use crate::point_list::Segment;
+use crate::preferences::{NUDGE_LARGE, NUDGE_MEDIUM, NUDGE_SMALL, SNAP_GRID_SIZE, SNAP_TO_GRID};
 use crate::tools::{EditType, Tool, ToolId};
 use crate::{
     design_space::{DPoint, DVec2, ViewPort},
@@ -16,6 +19,10 @@ use crate::{
 // distance from edges of the selection bbox to where we draw the handles
 const SELECTION_BBOX_HANDLE_PADDING: Insets = Insets::uniform(6.0);
 const SELECTION_HANDLE_RADIUS: f64 = 4.;
+// distance above the top handle of the selection bbox to the rotation handle
+const ROTATION_HANDLE_OFFSET: f64 = 20.0;
+// when shift is held, rotation snaps to multiples of this many degrees
+const ROTATION_SNAP_DEGREES: f64 = 15.0;
 
 /// A set of states that are possible while handling a mouse drag.
 #[derive(Debug, Clone)]
@@ -28,6 +35,9 @@ enum DragState {
     /// State for a drag that is moving a selected object.
     Move {
         delta: DVec2,
+        /// The position of the point under the cursor when the drag began;
+        /// this is the point that is snapped to the grid.
+        anchor: DPoint,
     },
     /// State for a drag that is moving an off-curve point.
     MoveHandle,
@@ -42,6 +52,14 @@ enum DragState {
         /// until the gesture completes
         pre_paths: BezPath,
     },
+    /// State for a drag that is rotating the selection around its center.
+    RotateSelection {
+        previous: EditSession,
+        center: DPoint,
+        /// The angle of the drag's start point, relative to the center.
+        start_angle: f64,
+        pre_paths: BezPath,
+    },
     None,
 }
 
@@ -59,6 +77,15 @@ pub struct Select {
     /// It is an invariant that this is always `None`, except while we are in
     /// a `key_down`, `key_up`, or `mouse_event` method.
     this_edit_type: Option<EditType>,
+    /// The base of a component that was double-clicked.
+    ///
+    /// We need an `EventCtx` to open it, so it is stashed here until the
+    /// end of `mouse_event`.
+    open_base: Option<GlyphName>,
+    /// The spacing of the grid that moved points snap to, if snapping is on.
+    ///
+    /// This is read from the `Env` at the start of each `mouse_event`.
+    snap_grid: Option<f64>,
 }
 
 impl Tool for Select {
@@ -70,7 +97,7 @@ impl Tool for Select {
                 ctx.stroke(rect, &selection_stroke, 1.0);
             }
             // draw the selection bounding box
-            DragState::None if data.selection.len() > 1 => {
+            DragState::None if has_selection_frame(data) => {
                 let bbox = data.viewport.rect_to_screen(data.selection_dpoint_bbox());
                 let style = StrokeStyle::new().dash(vec![2.0, 4.0], 0.0);
                 ctx.stroke_styled(&bbox, &selection_stroke, 0.5, &style);
@@ -81,6 +108,23 @@ impl Tool for Select {
                     }
                     ctx.stroke(circle, &selection_stroke, 0.5);
                 }
+
+                let (stem, circle) = rotation_handle(data);
+                ctx.stroke(stem, &selection_stroke, 0.5);
+                if circle.contains(self.last_pos) {
+                    ctx.fill(circle, &selection_stroke);
+                }
+                ctx.stroke(circle, &selection_stroke, 0.5);
+            }
+            DragState::RotateSelection { pre_paths, .. } => {
+                ctx.stroke(
+                    data.viewport.affine() * pre_paths,
+                    &env.get(theme::PLACEHOLDER_GLYPH_COLOR),
+                    1.0,
+                );
+                let bbox = data.viewport.rect_to_screen(data.selection_dpoint_bbox());
+                let style = StrokeStyle::new().dash(vec![2.0, 4.0], 0.0);
+                ctx.stroke_styled(bbox, &selection_stroke, 0.5, &style);
             }
             DragState::TransformSelection { pre_paths, .. } => {
                 ctx.stroke(
@@ -111,7 +155,7 @@ impl Tool for Select {
         event: &KeyEvent,
         _ctx: &mut EventCtx,
         data: &mut EditSession,
-        _: &Env,
+        env: &Env,
     ) -> Option<EditType> {
         assert!(self.this_edit_type.is_none());
         match event {
@@ -120,7 +164,7 @@ impl Tool for Select {
                 || e.key == KbKey::ArrowUp
                 || e.key == KbKey::ArrowRight =>
             {
-                self.nudge(data, event);
+                self.nudge(data, event, env);
             }
             e if e.key == KbKey::Backspace => {
                 data.delete_selection();
@@ -140,14 +184,22 @@ impl Tool for Select {
         mouse: &mut Mouse,
         ctx: &mut EventCtx,
         data: &mut EditSession,
-        _: &Env,
+        env: &Env,
     ) -> Option<EditType> {
         assert!(self.this_edit_type.is_none());
+        self.snap_grid = if env.get(SNAP_TO_GRID) {
+            Some(env.get(SNAP_GRID_SIZE))
+        } else {
+            None
+        };
         let pre_rect = self.drag.drag_rect();
         mouse.mouse_event(event, data, self);
         if !pre_rect.same(&self.drag.drag_rect()) {
             ctx.request_paint();
         }
+        if let Some(base) = self.open_base.take() {
+            ctx.submit_command(EDIT_GLYPH.with(base));
+        }
         self.this_edit_type.take()
     }
 
@@ -157,7 +209,7 @@ impl Tool for Select {
 }
 
 impl Select {
-    fn nudge(&mut self, data: &mut EditSession, event: &KeyEvent) {
+    fn nudge(&mut self, data: &mut EditSession, event: &KeyEvent, env: &Env) {
         let (mut nudge, edit_type) = match event.key {
             KbKey::ArrowLeft => (Vec2::new(-1.0, 0.), EditType::NudgeLeft),
             KbKey::ArrowRight => (Vec2::new(1.0, 0.), EditType::NudgeRight),
@@ -166,24 +218,27 @@ impl Select {
             _ => unreachable!(),
         };
 
+        let is_small_nudge = !(event.mods.meta() || event.mods.shift());
         if event.mods.meta() {
-            nudge *= 100.;
+            nudge *= env.get(NUDGE_LARGE);
         } else if event.mods.shift() {
-            nudge *= 10.;
+            nudge *= env.get(NUDGE_MEDIUM);
+        } else {
+            nudge *= env.get(NUDGE_SMALL);
         }
 
         data.nudge_selection(DVec2::from_raw(nudge));
 
-        // for the purposes of undo, we only combine single-unit nudges
-        if nudge.hypot().abs() > 1.0 {
-            self.this_edit_type = Some(EditType::Normal);
-        } else {
+        // for the purposes of undo, we only combine small nudges
+        if is_small_nudge {
             self.this_edit_type = Some(edit_type);
+        } else {
+            self.this_edit_type = Some(EditType::Normal);
         }
     }
 
     fn selection_handle_hit(&self, data: &EditSession, pos: Point) -> Option<Quadrant> {
-        if data.selection.len() <= 1 {
+        if !has_selection_frame(data) {
             return None;
         }
 
@@ -206,6 +261,11 @@ impl Select {
             None
         }
     }
+
+    fn rotation_handle_hit(&self, data: &EditSession, pos: Point) -> bool {
+        has_selection_frame(data)
+            && rotation_handle(data).1.center.distance(pos) <= SELECTION_HANDLE_RADIUS
+    }
 }
 
 impl MouseDelegate<EditSession> for Select {
@@ -219,7 +279,9 @@ impl MouseDelegate<EditSession> for Select {
             // if we have an existing multi-point selection we first hit-test
             // our own selection handles. If we're on one of them, we don't
             // do anything further; we will start a transform in drag_began
-            if self.selection_handle_hit(data, event.pos).is_some() {
+            if self.selection_handle_hit(data, event.pos).is_some()
+                || self.rotation_handle_hit(data, event.pos)
+            {
                 return;
             }
 
@@ -260,6 +322,14 @@ impl MouseDelegate<EditSession> for Select {
                 } else if append_mode {
                     data.selection.extend(ids);
                 }
+            } else if let Some(component_id) = data.hit_test_components(event.pos) {
+                if !event.mods.shift() {
+                    if !data.selection.contains(&component_id) {
+                        data.selection.select_one(component_id);
+                    }
+                } else if !data.selection.remove(&component_id) {
+                    data.selection.insert(component_id);
+                }
             } else if !event.mods.shift() {
                 data.selection.clear();
             }
@@ -280,7 +350,12 @@ impl MouseDelegate<EditSession> for Select {
                     self.this_edit_type = Some(EditType::Normal);
                 }
                 _ => {
-                    data.select_path(event.pos, event.mods.shift());
+                    if !data.select_path(event.pos, event.mods.shift()) {
+                        self.open_base = data
+                            .hit_test_components(event.pos)
+                            .and_then(|id| data.components.iter().find(|c| c.id == id))
+                            .map(|c| c.base.clone());
+                    }
                 }
             }
         }
@@ -307,6 +382,18 @@ impl MouseDelegate<EditSession> for Select {
             return;
         }
 
+        if self.rotation_handle_hit(data, drag.start.pos) {
+            let center = DPoint::from_raw(data.selection_dpoint_bbox().center());
+            let start = data.viewport.from_screen(drag.start.pos) - center;
+            self.drag = DragState::RotateSelection {
+                center,
+                start_angle: start.to_raw().atan2(),
+                previous: data.clone(),
+                pre_paths: data.to_bezier(),
+            };
+            return;
+        }
+
         // if we're starting a rectangular selection, we save the previous selection
         let sel = data.hit_test_all(drag.start.pos, None);
         self.drag = if let Some(pt) = sel.and_then(|id| data.path_point_for_id(id)) {
@@ -315,10 +402,25 @@ impl MouseDelegate<EditSession> for Select {
             if is_dragging_handle {
                 DragState::MoveHandle
             } else {
-                DragState::Move { delta: DVec2::ZERO }
+                DragState::Move {
+                    delta: DVec2::ZERO,
+                    anchor: pt.point,
+                }
             }
         } else if data.hit_test_segments(drag.start.pos, None).is_some() {
-            DragState::Move { delta: DVec2::ZERO }
+            DragState::Move {
+                delta: DVec2::ZERO,
+                anchor: data.viewport.from_screen(drag.start.pos),
+            }
+        } else if let Some(component) = data
+            .hit_test_components(drag.start.pos)
+            .and_then(|id| data.components.iter().find(|c| c.id == id))
+        {
+            // components snap by their origin
+            DragState::Move {
+                delta: DVec2::ZERO,
+                anchor: DPoint::from_raw(component.offset()),
+            }
         } else {
             // if we're starting a rectangular selection, we save the previous selection
             DragState::Select {
@@ -335,8 +437,11 @@ impl MouseDelegate<EditSession> for Select {
                 *rect = Rect::from_points(drag.current.pos, drag.start.pos);
                 update_selection_for_drag(data, previous, *rect, drag.current.mods.shift());
             }
-            DragState::Move { delta } => {
+            DragState::Move { delta, anchor } => {
                 let mut new_delta = delta_for_drag_change(&drag, data.viewport);
+                if let Some(grid) = self.snap_grid {
+                    new_delta = snap_delta(*anchor, new_delta, grid);
+                }
                 if drag.current.mods.shift() {
                     new_delta = new_delta.axis_locked();
                 }
@@ -368,6 +473,24 @@ impl MouseDelegate<EditSession> for Select {
                     *data = new_data;
                 }
             }
+            DragState::RotateSelection {
+                previous,
+                center,
+                start_angle,
+                ..
+            } => {
+                let current = data.viewport.from_screen(drag.current.pos) - *center;
+                let mut angle = current.to_raw().atan2() - *start_angle;
+                if drag.current.mods.shift() {
+                    let snap = ROTATION_SNAP_DEGREES.to_radians();
+                    angle = (angle / snap).round() * snap;
+                }
+                if angle.is_finite() {
+                    let mut new_data = previous.clone();
+                    new_data.rotate_selection(angle, *center);
+                    *data = new_data;
+                }
+            }
             DragState::None => unreachable!("invalid state"),
         }
 
@@ -398,6 +521,14 @@ fn delta_for_drag_change(drag: &Drag, viewport: ViewPort) -> DVec2 {
     drag_pos - drag_start
 }
 
+/// Adjust the delta of a drag so that `anchor` lands on the snapping grid.
+fn snap_delta(anchor: DPoint, delta: DVec2, grid: f64) -> DVec2 {
+    let target = anchor + delta;
+    let snapped_x = (target.x / grid).round() * grid;
+    let snapped_y = (target.y / grid).round() * grid;
+    DVec2::from_raw((snapped_x - anchor.x, snapped_y - anchor.y))
+}
+
 fn iter_handle_circles(session: &EditSession) -> impl Iterator<Item = (Quadrant, Circle)> {
     let bbox = session
         .viewport
@@ -417,16 +548,47 @@ fn iter_handle_circles(session: &EditSession) -> impl Iterator<Item = (Quadrant,
         })
 }
 
+/// Whether the selection has a bounding box with transform handles: either
+/// multiple items are selected, or a single component.
+fn has_selection_frame(session: &EditSession) -> bool {
+    session.selection.len() > 1 || session.selected_component().is_some()
+}
+
+/// The rotation handle, and the line joining it to the top of the selection
+/// bounding box, in screen space.
+fn rotation_handle(session: &EditSession) -> (Line, Circle) {
+    let bbox = session
+        .viewport
+        .rect_to_screen(session.selection_dpoint_bbox());
+    let handle_frame = bbox + SELECTION_BBOX_HANDLE_PADDING;
+    let top = Quadrant::Top.point_in_rect(handle_frame);
+    let center = Point::new(top.x, top.y - ROTATION_HANDLE_OFFSET);
+    let stem = Line::new(
+        Point::new(top.x, top.y - SELECTION_HANDLE_RADIUS),
+        Point::new(center.x, center.y + SELECTION_HANDLE_RADIUS),
+    );
+    (stem, Circle::new(center, SELECTION_HANDLE_RADIUS))
+}
+
 fn update_selection_for_drag(
     data: &mut EditSession,
     prev_sel: &Selection,
     rect: Rect,
     shift: bool,
 ) {
+    let components_in_rect = data
+        .components
+        .iter()
+        .filter(|c| {
+            let bounds = data.viewport.rect_to_screen(c.bounds());
+            rect.union(bounds) == rect
+        })
+        .map(|c| c.id);
     let in_select_rect = data
         .iter_points()
         .filter(|p| rect.contains(p.to_screen(data.viewport)))
         .map(|p| p.id)
+        .chain(components_in_rect)
         .collect();
     data.selection = if shift {
         prev_sel.symmetric_difference(&in_select_rect)
@@ -455,6 +617,9 @@ impl DragState {
     }
 
     fn is_transform(&self) -> bool {
-        matches!(self, DragState::TransformSelection{ .. })
+        matches!(
+            self,
+            DragState::TransformSelection { .. } | DragState::RotateSelection { .. }
+        )
     }
 }
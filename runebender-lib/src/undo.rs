@@ -1,6 +1,8 @@
 //! Managing undo state
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
+
+use norad::GlyphName;
 
 // for no good reason
 const DEFAULT_UNDO_STACK_SIZE: usize = 128;
@@ -66,3 +68,77 @@ impl<T> UndoState<T> {
         f(self.stack.get_mut(self.live_index).unwrap())
     }
 }
+
+/// An edit to the font as a whole, rather than to a single glyph.
+#[derive(Debug, Clone)]
+pub(crate) enum FontEdit {
+    /// A glyph was renamed, along with all references to it.
+    Rename {
+        old: GlyphName,
+        new: GlyphName,
+        /// The glyph's codepoints before it was renamed.
+        old_codepoints: Option<Vec<char>>,
+        /// The glyph's codepoints after it was renamed.
+        new_codepoints: Option<Vec<char>>,
+        /// The groups, kerning and glyph order before the rename.
+        old_references: References,
+        /// The groups, kerning and glyph order after the rename.
+        new_references: References,
+    },
+}
+
+/// The parts of a font outside of its glyphs that refer to glyphs by name.
+///
+/// A rename can merge kerning pairs, which renaming back can't separate, so
+/// we keep copies to restore instead.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct References {
+    pub(crate) groups: Option<BTreeMap<String, Vec<GlyphName>>>,
+    pub(crate) kerning: Option<BTreeMap<String, BTreeMap<String, f32>>>,
+    /// The `public.glyphOrder` value in the lib, if there is one.
+    pub(crate) glyph_order: Option<plist::Value>,
+}
+
+/// The undo history for edits to the font as a whole.
+///
+/// Unlike `UndoState`, which stores snapshots of a single glyph, this stores
+/// the edits themselves, which know how to reverse themselves.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FontHistory {
+    undo: Vec<FontEdit>,
+    redo: Vec<FontEdit>,
+}
+
+impl FontHistory {
+    pub(crate) fn push(&mut self, edit: FontEdit) {
+        self.redo.clear();
+        self.undo.push(edit);
+        if self.undo.len() > DEFAULT_UNDO_STACK_SIZE {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Returns the most recent edit, which should then be reversed.
+    pub(crate) fn undo(&mut self) -> Option<FontEdit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Returns the most recently undone edit, which should be applied again.
+    pub(crate) fn redo(&mut self) -> Option<FontEdit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+        Some(edit)
+    }
+
+    /// The edit that `undo` would return, without moving it.
+    pub(crate) fn next_undo(&self) -> Option<&FontEdit> {
+        self.undo.last()
+    }
+
+    /// The edit that `redo` would return, without moving it.
+    pub(crate) fn next_redo(&self) -> Option<&FontEdit> {
+        self.redo.last()
+    }
+}
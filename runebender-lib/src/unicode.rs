@@ -0,0 +1,159 @@
+//! Unicode properties of characters, for searching and sorting glyphs and
+//! for checking a font's coverage.
+//!
+//! This file relies on code that is generated in our build.rs script, which
+//! is based on the Unicode Character Database, at
+//! https://www.unicode.org/ucd/
+
+use std::borrow::Cow;
+
+include!(concat!(env!("OUT_DIR"), "/unicode_codegen.rs"));
+
+const HANGUL_FIRST: u32 = 0xAC00;
+const HANGUL_LAST: u32 = 0xD7A3;
+
+/// A named range of codepoints, such as `Latin-1 Supplement`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block {
+    pub first: u32,
+    pub last: u32,
+    pub name: &'static str,
+}
+
+/// The Unicode name of a character, such as `LATIN SMALL LETTER A`.
+///
+/// Control characters, private use characters and unassigned codepoints
+/// have no name.
+pub fn name(c: char) -> Option<Cow<'static, str>> {
+    if let Ok(idx) = UNICODE_NAMES.binary_search_by(|probe| probe.0.cmp(&c)) {
+        return Some(UNICODE_NAMES[idx].1.into());
+    }
+    let cp = c as u32;
+    if (HANGUL_FIRST..=HANGUL_LAST).contains(&cp) {
+        return Some(hangul_syllable_name(cp).into());
+    }
+    lookup(NAME_RANGES, c).map(|(_, _, prefix)| format!("{}{:04X}", prefix, cp).into())
+}
+
+/// The name of a precomposed Hangul syllable, which is made from the names
+/// of its jamo.
+fn hangul_syllable_name(cp: u32) -> String {
+    const LEADS: [&str; 19] = [
+        "G", "GG", "N", "D", "DD", "R", "M", "B", "BB", "S", "SS", "", "J", "JJ", "C", "K", "T",
+        "P", "H",
+    ];
+    const VOWELS: [&str; 21] = [
+        "A", "AE", "YA", "YAE", "EO", "E", "YEO", "YE", "O", "WA", "WAE", "OE", "YO", "U", "WEO",
+        "WE", "WI", "YU", "EU", "YI", "I",
+    ];
+    const TAILS: [&str; 28] = [
+        "", "G", "GG", "GS", "N", "NJ", "NH", "D", "L", "LG", "LM", "LB", "LS", "LT", "LP", "LH",
+        "M", "B", "BS", "S", "SS", "NG", "J", "C", "K", "T", "P", "H",
+    ];
+    let idx = (cp - HANGUL_FIRST) as usize;
+    let (lead, rest) = (idx / (21 * 28), idx % (21 * 28));
+    format!(
+        "HANGUL SYLLABLE {}{}{}",
+        LEADS[lead],
+        VOWELS[rest / 28],
+        TAILS[rest % 28]
+    )
+}
+
+/// The two-letter general category of a character, such as `Lu` for an
+/// uppercase letter or `Mn` for a combining mark.
+///
+/// Unassigned codepoints are `Cn`.
+pub fn general_category(c: char) -> &'static str {
+    lookup(GENERAL_CATEGORIES, c)
+        .map(|(_, _, category)| *category)
+        .unwrap_or("Cn")
+}
+
+/// The name of the script a character belongs to, such as `Latin`.
+///
+/// Characters shared between scripts, like digits and punctuation, are
+/// `Common`; combining marks are `Inherited`, and characters that belong to
+/// no script are `Unknown`.
+pub fn script(c: char) -> &'static str {
+    lookup(SCRIPTS, c)
+        .map(|(_, _, name)| *name)
+        .unwrap_or("Unknown")
+}
+
+/// The block a character belongs to, if it is in one.
+pub fn block(c: char) -> Option<Block> {
+    lookup(BLOCKS, c).map(|&(first, last, name)| Block { first, last, name })
+}
+
+/// Find the range containing `c` in a sorted table of ranges.
+fn lookup(
+    table: &'static [(u32, u32, &'static str)],
+    c: char,
+) -> Option<&'static (u32, u32, &'static str)> {
+    let cp = c as u32;
+    table
+        .binary_search_by(|(start, end, _)| {
+            if cp < *start {
+                std::cmp::Ordering::Greater
+            } else if cp > *end {
+                std::cmp::Ordering::Less
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .ok()
+        .map(|idx| &table[idx])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categories_and_scripts() {
+        assert_eq!(general_category('A'), "Lu");
+        assert_eq!(general_category('ß'), "Ll");
+        assert_eq!(general_category('7'), "Nd");
+        assert_eq!(general_category('\u{0301}'), "Mn");
+        assert_eq!(general_category('('), "Ps");
+        assert_eq!(general_category('€'), "Sc");
+        assert_eq!(general_category(' '), "Zs");
+        assert_eq!(general_category('½'), "No");
+        assert_eq!(general_category('中'), "Lo");
+        assert_eq!(general_category('\u{0378}'), "Cn");
+
+        assert_eq!(script('a'), "Latin");
+        assert_eq!(script('Ж'), "Cyrillic");
+        assert_eq!(script('λ'), "Greek");
+        assert_eq!(script('7'), "Common");
+        assert_eq!(script('\u{0301}'), "Inherited");
+        assert_eq!(script('ء'), "Arabic");
+        assert_eq!(script('\u{10300}'), "Old Italic");
+        assert_eq!(script('\u{0378}'), "Unknown");
+        assert!(SCRIPTS.windows(2).all(|w| w[0].1 < w[1].0));
+        assert!(GENERAL_CATEGORIES.windows(2).all(|w| w[0].1 < w[1].0));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(name('a').unwrap(), "LATIN SMALL LETTER A");
+        assert_eq!(name('б').unwrap(), "CYRILLIC SMALL LETTER BE");
+        assert_eq!(name('中').unwrap(), "CJK UNIFIED IDEOGRAPH-4E2D");
+        assert_eq!(name('한').unwrap(), "HANGUL SYLLABLE HAN");
+        assert_eq!(name('\u{AC00}').unwrap(), "HANGUL SYLLABLE GA");
+        assert!(name('\n').is_none());
+        assert!(name('\u{E000}').is_none());
+    }
+
+    #[test]
+    fn blocks() {
+        assert_eq!(block('A').unwrap().name, "Basic Latin");
+        assert_eq!(block('é').unwrap().name, "Latin-1 Supplement");
+        let cyrillic = block('Ж').unwrap();
+        assert_eq!((cyrillic.first, cyrillic.last), (0x0400, 0x04FF));
+        assert_eq!(block('\u{0860}').unwrap().name, "Syriac Supplement");
+        assert!(block('\u{2FE0}').is_none());
+        assert!(BLOCKS.windows(2).all(|w| w[0].1 < w[1].0));
+    }
+}
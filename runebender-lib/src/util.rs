@@ -4,6 +4,9 @@ use std::convert::TryFrom;
 
 use druid::kurbo::{Size, Vec2};
 
+use crate::charset::{Template, TEMPLATES};
+use crate::data::GLYPH_ORDER_KEY;
+
 /// Unwrap an optional, printing a message and returning if it is missing.
 ///
 /// This should generate less code than unwrap? Honestly it's a total
@@ -40,6 +43,12 @@ pub(crate) fn compute_scale(pre: Size, post: Size) -> Vec2 {
 
 /// temporary; creates a new blank  font with some placeholder glyphs.
 pub fn create_blank_font() -> norad::Ufo {
+    create_font_from_template(&TEMPLATES[0])
+}
+
+/// Create a new font with an empty glyph for each glyph in `template`, in
+/// the template's order.
+pub fn create_font_from_template(template: &Template) -> norad::Ufo {
     let mut ufo = norad::Ufo::new();
     ufo.font_info = norad::FontInfo {
         family_name: Some("Untitled".into()),
@@ -53,13 +62,21 @@ pub fn create_blank_font() -> norad::Ufo {
     }
     .into();
 
+    let glyphs = template.glyphs();
+    let order = glyphs
+        .iter()
+        .map(|spec| plist::Value::String(spec.name.to_string()))
+        .collect();
+    let mut lib = plist::Dictionary::new();
+    lib.insert(GLYPH_ORDER_KEY.into(), plist::Value::Array(order));
+    ufo.lib = Some(lib);
+
     let layer = ufo.get_default_layer_mut().unwrap();
-    ('a'..='z')
+    glyphs
         .into_iter()
-        .chain('A'..='Z')
-        .map(|chr| {
-            let mut glyph = norad::Glyph::new_named(chr.to_string());
-            glyph.codepoints = Some(vec![chr]);
+        .map(|spec| {
+            let mut glyph = norad::Glyph::new_named(spec.name);
+            glyph.codepoints = Some(spec.codepoints);
             glyph
         })
         .for_each(|glyph| layer.insert_glyph(glyph));
@@ -0,0 +1,146 @@
+//! A panel for adding glyphs from a typed list, or from a character set or
+//! encoding file.
+//!
+//! This is intended to be shown as a modal panel.
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label, LineBreaking, RadioGroup, TextBox};
+use druid::{commands, Color, Data, FileDialogOptions, HotKey, KbKey, Lens, WidgetExt, WidgetPod};
+
+use crate::charset::{parse_list, ListKind};
+use crate::consts::cmd::{ADD_GLYPHS, ADD_GLYPHS_FROM_FILE};
+use crate::data::Workspace;
+use crate::menus::CHARSET_FILE_TYPE;
+use crate::theme;
+use crate::widgets::ModalHost;
+
+const WIDTH: f64 = 420.0;
+const LIST_HEIGHT: f64 = 120.0;
+
+/// The list being edited in the panel.
+#[derive(Debug, Clone, Data, Lens)]
+struct AddGlyphs {
+    text: String,
+    kind: ListKind,
+    /// The reason the list could not be parsed, if it couldn't.
+    error: String,
+}
+
+/// The panel keeps its own state, since nothing is changed in the font
+/// until one of its buttons is pressed.
+struct AddGlyphsPanel {
+    state: AddGlyphs,
+    inner: WidgetPod<AddGlyphs, Box<dyn Widget<AddGlyphs>>>,
+}
+
+pub fn add_glyphs_panel() -> impl Widget<Workspace> {
+    let kind = RadioGroup::new(vec![
+        ("Characters", ListKind::Characters),
+        ("Glyph names or codepoints", ListKind::GlyphNames),
+    ])
+    .lens(AddGlyphs::kind);
+    let list = TextBox::multiline()
+        .with_placeholder("abc äöü or a.sc uni0431 U+00E9")
+        .lens(AddGlyphs::text)
+        .fix_size(WIDTH - 32.0, LIST_HEIGHT);
+    let error = Label::new(|data: &AddGlyphs, _: &Env| data.error.clone())
+        .with_font(theme::UI_DETAIL_FONT)
+        .with_text_color(theme::ERROR_COLOR)
+        .with_line_break_mode(LineBreaking::WordWrap);
+
+    let load = Button::new("Load File…").on_click(|ctx, _, _| {
+        let options = FileDialogOptions::new()
+            .allowed_types(vec![CHARSET_FILE_TYPE])
+            .accept_command(ADD_GLYPHS_FROM_FILE);
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+        ctx.submit_command(commands::SHOW_OPEN_PANEL.with(options));
+    });
+    let cancel = Button::new("Cancel").on_click(|ctx, _, _| {
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+    });
+    let add = Button::new("Add").on_click(|ctx, data: &mut AddGlyphs, _| {
+        match parse_list(&data.text, data.kind) {
+            Ok(glyphs) => {
+                ctx.submit_command(ADD_GLYPHS.with(glyphs));
+                ctx.submit_command(ModalHost::DISMISS_MODAL);
+            }
+            Err(e) => data.error = e.to_string(),
+        }
+    });
+
+    let inner = Flex::column()
+        .with_child(Label::new("Add Glyphs"))
+        .with_spacer(16.0)
+        .with_child(kind)
+        .with_default_spacer()
+        .with_child(list)
+        .with_default_spacer()
+        .with_child(error)
+        .with_spacer(16.0)
+        .with_child(
+            Flex::row()
+                .with_child(load)
+                .with_flex_spacer(1.0)
+                .with_child(cancel)
+                .with_default_spacer()
+                .with_child(add),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .fix_width(WIDTH)
+        .padding(16.0)
+        .background(Color::WHITE);
+
+    AddGlyphsPanel {
+        state: AddGlyphs {
+            text: String::new(),
+            kind: ListKind::Characters,
+            error: String::new(),
+        },
+        inner: WidgetPod::new(inner.boxed()),
+    }
+}
+
+impl Widget<Workspace> for AddGlyphsPanel {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut Workspace, env: &Env) {
+        if let Event::KeyDown(k) = event {
+            if HotKey::new(None, KbKey::Escape).matches(k) {
+                ctx.submit_command(ModalHost::DISMISS_MODAL);
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.inner.event(ctx, event, &mut self.state, env);
+        ctx.request_update();
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &Workspace,
+        env: &Env,
+    ) {
+        self.inner.lifecycle(ctx, event, &self.state, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &Workspace, _data: &Workspace, env: &Env) {
+        self.inner.update(ctx, &self.state, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Workspace,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, &self.state, env);
+        self.inner
+            .set_layout_rect(ctx, &self.state, env, size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &Workspace, env: &Env) {
+        self.inner.paint(ctx, &self.state, env);
+    }
+}
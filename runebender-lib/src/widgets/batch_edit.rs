@@ -0,0 +1,174 @@
+//! A panel for transforming the selected glyphs, or setting their advance.
+//!
+//! This is intended to be shown as a modal panel.
+
+use druid::kurbo::{Affine, Vec2};
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label};
+use druid::{Color, Data, HotKey, KbKey, Lens, WidgetExt, WidgetPod};
+
+use crate::consts::cmd::{SET_ADVANCE_WIDTH, TRANSFORM_GLYPHS};
+use crate::data::Workspace;
+use crate::theme;
+use crate::widgets::{EditableLabel, ModalHost};
+
+const FIELD_WIDTH: f64 = 60.0;
+
+/// The values being edited in the panel.
+#[derive(Debug, Clone, Data, Lens)]
+struct BatchEdit {
+    offset_x: f64,
+    offset_y: f64,
+    /// The scale, as a percentage.
+    scale: f64,
+    /// The counter-clockwise rotation, in degrees.
+    rotation: f64,
+    advance: f64,
+}
+
+/// The panel keeps its own state, since nothing is changed in the font
+/// until one of its buttons is pressed.
+struct BatchEditPanel {
+    state: BatchEdit,
+    inner: WidgetPod<BatchEdit, Box<dyn Widget<BatchEdit>>>,
+}
+
+pub fn batch_edit_panel(glyph_count: usize) -> impl Widget<Workspace> {
+    let title = match glyph_count {
+        1 => "Edit 1 glyph".to_string(),
+        n => format!("Edit {} glyphs", n),
+    };
+
+    let transform = Button::new("Transform").on_click(|ctx, data: &mut BatchEdit, _| {
+        ctx.submit_command(TRANSFORM_GLYPHS.with(data.affine()));
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+    });
+    let set_advance = Button::new("Set Advance").on_click(|ctx, data: &mut BatchEdit, _| {
+        ctx.submit_command(SET_ADVANCE_WIDTH.with(data.advance.max(0.0)));
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+    });
+    let cancel = Button::new("Cancel").on_click(|ctx, _, _| {
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+    });
+
+    let inner = Flex::column()
+        .with_child(Label::new(title))
+        .with_spacer(16.0)
+        .with_child(
+            Flex::row()
+                .with_child(field("x", BatchEdit::offset_x))
+                .with_default_spacer()
+                .with_child(field("y", BatchEdit::offset_y))
+                .with_default_spacer()
+                .with_child(field("scale %", BatchEdit::scale))
+                .with_default_spacer()
+                .with_child(field("rotate °", BatchEdit::rotation))
+                .with_flex_spacer(1.0)
+                .with_child(transform),
+        )
+        .with_default_spacer()
+        .with_child(
+            Flex::row()
+                .with_child(field("advance", BatchEdit::advance))
+                .with_flex_spacer(1.0)
+                .with_child(set_advance),
+        )
+        .with_spacer(16.0)
+        .with_child(Flex::row().with_flex_spacer(1.0).with_child(cancel))
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .fix_width(420.)
+        .padding(16.0)
+        .background(Color::WHITE);
+
+    BatchEditPanel {
+        state: BatchEdit {
+            offset_x: 0.0,
+            offset_y: 0.0,
+            scale: 100.0,
+            rotation: 0.0,
+            advance: 0.0,
+        },
+        inner: WidgetPod::new(inner.boxed()),
+    }
+}
+
+fn field(label: &str, lens: impl Lens<BatchEdit, f64> + 'static) -> impl Widget<BatchEdit> {
+    Flex::column()
+        .with_child(
+            Label::new(label)
+                .with_font(theme::UI_DETAIL_FONT)
+                .with_text_color(theme::SECONDARY_TEXT_COLOR),
+        )
+        .with_child(
+            EditableLabel::parse()
+                .with_font(theme::UI_DETAIL_FONT)
+                .lens(lens)
+                .fix_width(FIELD_WIDTH),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+}
+
+impl BatchEdit {
+    /// The transform to apply, around the center of each glyph.
+    fn affine(&self) -> Affine {
+        let scale = self.scale / 100.0;
+        Affine::translate(Vec2::new(self.offset_x, self.offset_y))
+            * Affine::rotate(self.rotation.to_radians())
+            * Affine::scale(scale)
+    }
+}
+
+impl Widget<Workspace> for BatchEditPanel {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut Workspace, env: &Env) {
+        if let Event::KeyDown(k) = event {
+            if HotKey::new(None, KbKey::Escape).matches(k) {
+                ctx.submit_command(ModalHost::DISMISS_MODAL);
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.inner.event(ctx, event, &mut self.state, env);
+        ctx.request_update();
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Workspace,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            // start with the advance of the most recently selected glyph
+            self.state.advance = data
+                .selected
+                .as_ref()
+                .and_then(|name| data.font.ufo.get_glyph(name))
+                .and_then(|glyph| glyph.advance.as_ref())
+                .map(|advance| advance.width as f64)
+                .unwrap_or_default();
+        }
+        self.inner.lifecycle(ctx, event, &self.state, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &Workspace, _data: &Workspace, env: &Env) {
+        self.inner.update(ctx, &self.state, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Workspace,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, &self.state, env);
+        self.inner
+            .set_layout_rect(ctx, &self.state, env, size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &Workspace, env: &Env) {
+        self.inner.paint(ctx, &self.state, env);
+    }
+}
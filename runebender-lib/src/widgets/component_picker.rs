@@ -0,0 +1,296 @@
+//! A modal for choosing a glyph to add as a component.
+
+use druid::kurbo::{Affine, Rect};
+use druid::widget::prelude::*;
+use druid::widget::TextBox;
+use druid::{HotKey, KbKey, Point, Selector, TextLayout, WidgetExt, WidgetPod};
+use norad::GlyphName;
+
+use crate::consts;
+use crate::data::Workspace;
+use crate::glyph_filter::parse_codepoint;
+use crate::theme;
+use crate::widgets::ModalHost;
+
+const WIDTH: f64 = 360.0;
+const PADDING: f64 = 12.0;
+const ROW_HEIGHT: f64 = 40.0;
+const PREVIEW_SIZE: f64 = 32.0;
+/// The most results we show at once.
+const MAX_RESULTS: usize = 10;
+
+/// Sent to the picker once it is added, so that it can focus the search field.
+const FOCUS_SEARCH: Selector = Selector::new("runebender.component-picker-focus-search");
+
+/// A search field and a list of matching glyphs; choosing one inserts it as
+/// a component in `glyph`.
+///
+/// Glyphs that can't be used as components of `glyph`, such as ones that
+/// contain it, are not listed.
+pub fn component_picker(glyph: GlyphName) -> impl Widget<Workspace> {
+    ComponentPicker {
+        glyph,
+        query: String::new(),
+        search: WidgetPod::new(
+            TextBox::new()
+                .with_placeholder("Glyph name or unicode")
+                .boxed(),
+        ),
+        results: Vec::new(),
+        rows: Vec::new(),
+        selected: 0,
+    }
+    .background(theme::GLYPH_LIST_BACKGROUND)
+}
+
+struct ComponentPicker {
+    glyph: GlyphName,
+    query: String,
+    search: WidgetPod<String, Box<dyn Widget<String>>>,
+    results: Vec<GlyphName>,
+    rows: Vec<TextLayout<String>>,
+    /// The index of the highlighted result.
+    selected: usize,
+}
+
+impl ComponentPicker {
+    fn refresh(&mut self, data: &Workspace) {
+        let mut matches: Vec<_> = data
+            .font
+            .ufo
+            .iter_names()
+            .filter_map(|name| {
+                let glyph = data.font.ufo.get_glyph(&name)?;
+                let codepoints = glyph.codepoints.as_deref().unwrap_or_default();
+                match_rank(&name, codepoints, &self.query).map(|rank| (rank, name))
+            })
+            .collect();
+        matches.sort();
+
+        self.results = matches
+            .into_iter()
+            .map(|(_, name)| name)
+            .filter(|name| data.component_problem(&self.glyph, name).is_none())
+            .take(MAX_RESULTS)
+            .collect();
+        self.rows = self
+            .results
+            .iter()
+            .map(|name| {
+                let codepoint = data
+                    .font
+                    .ufo
+                    .get_glyph(name)
+                    .and_then(|g| g.codepoints.as_ref())
+                    .and_then(|cps| cps.first().copied());
+                let text = match codepoint {
+                    Some(cp) => format!("{}  U+{:04X}", name, cp as u32),
+                    None => name.to_string(),
+                };
+                let mut layout = TextLayout::from_text(text);
+                layout.set_text_color(theme::PRIMARY_TEXT_COLOR);
+                layout
+            })
+            .collect();
+        if self.rows.is_empty() {
+            let mut layout = TextLayout::from_text("No matching glyphs".to_string());
+            layout.set_text_color(theme::SECONDARY_TEXT_COLOR);
+            self.rows.push(layout);
+        }
+        self.selected = 0;
+    }
+
+    fn choose(&self, ctx: &mut EventCtx, idx: usize) {
+        if let Some(name) = self.results.get(idx) {
+            ctx.submit_command(consts::cmd::INSERT_COMPONENT.with(name.clone()));
+            ctx.submit_command(ModalHost::DISMISS_MODAL);
+        }
+    }
+
+    fn list_origin(&self) -> Point {
+        Point::new(0.0, self.search.layout_rect().max_y() + PADDING)
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        let y = pos.y - self.list_origin().y;
+        if y < 0.0 {
+            return None;
+        }
+        Some((y / ROW_HEIGHT) as usize).filter(|idx| *idx < self.results.len())
+    }
+}
+
+/// How well a glyph matches the search query; lower is better.
+///
+/// The query can be part of a glyph name, a character, or a codepoint
+/// written like `U+00C1`, `uni00C1`, `0xC1` or just `00C1`.
+fn match_rank(name: &str, codepoints: &[char], query: &str) -> Option<u8> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Some(3);
+    }
+    let mut chars = query.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if codepoints.contains(&c) {
+            return Some(0);
+        }
+    }
+    if let Some(c) = parse_codepoint(query) {
+        if codepoints.contains(&c) {
+            return Some(0);
+        }
+    }
+    if name == query {
+        return Some(0);
+    }
+    let name = name.to_lowercase();
+    let query = query.to_lowercase();
+    if name.starts_with(&query) {
+        Some(1)
+    } else if name.contains(&query) {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+impl Widget<Workspace> for ComponentPicker {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Workspace, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(FOCUS_SEARCH) => {
+                ctx.set_focus(self.search.id());
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(k) if HotKey::new(None, KbKey::Escape).matches(k) => {
+                ctx.submit_command(ModalHost::DISMISS_MODAL);
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(k) if HotKey::new(None, KbKey::Enter).matches(k) => {
+                self.choose(ctx, self.selected);
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(k) if HotKey::new(None, KbKey::ArrowDown).matches(k) => {
+                self.selected = (self.selected + 1).min(self.results.len().saturating_sub(1));
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(k) if HotKey::new(None, KbKey::ArrowUp).matches(k) => {
+                self.selected = self.selected.saturating_sub(1);
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(m) => {
+                if let Some(idx) = self.row_at(m.pos) {
+                    if idx != self.selected {
+                        self.selected = idx;
+                        ctx.request_paint();
+                    }
+                }
+            }
+            Event::MouseDown(m) => {
+                if let Some(idx) = self.row_at(m.pos) {
+                    self.choose(ctx, idx);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            _ => (),
+        }
+
+        let old_query = self.query.clone();
+        self.search.event(ctx, event, &mut self.query, env);
+        if self.query != old_query {
+            self.refresh(data);
+            ctx.request_layout();
+        }
+        ctx.request_update();
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Workspace,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.refresh(data);
+            ctx.submit_command(FOCUS_SEARCH.to(ctx.widget_id()));
+        }
+        self.search.lifecycle(ctx, event, &self.query, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Workspace, data: &Workspace, env: &Env) {
+        if !old_data.font.same(&data.font) {
+            self.refresh(data);
+            ctx.request_layout();
+        }
+        self.search.update(ctx, &self.query, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        _bc: &BoxConstraints,
+        _data: &Workspace,
+        env: &Env,
+    ) -> Size {
+        let search_width = WIDTH - PADDING * 2.0;
+        let search_bc = BoxConstraints::new(
+            Size::new(search_width, 0.0),
+            Size::new(search_width, f64::INFINITY),
+        );
+        let search_size = self.search.layout(ctx, &search_bc, &self.query, env);
+        let search_rect = Rect::from_origin_size((PADDING, PADDING), search_size);
+        self.search
+            .set_layout_rect(ctx, &self.query, env, search_rect);
+
+        for row in self.rows.iter_mut() {
+            row.rebuild_if_needed(ctx.text(), env);
+        }
+        let list_height = MAX_RESULTS as f64 * ROW_HEIGHT;
+        Size::new(WIDTH, search_rect.max_y() + PADDING + list_height + PADDING)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Workspace, env: &Env) {
+        self.search.paint(ctx, &self.query, env);
+
+        let origin = self.list_origin();
+        let upm = data.units_per_em();
+        let scale = PREVIEW_SIZE / upm;
+        let glyph_color = env.get(theme::PRIMARY_TEXT_COLOR);
+        for (i, row) in self.rows.iter().enumerate() {
+            let top = origin.y + i as f64 * ROW_HEIGHT;
+            if i == self.selected && i < self.results.len() {
+                let highlight = Rect::new(0.0, top, WIDTH, top + ROW_HEIGHT);
+                ctx.fill(highlight, &env.get(druid::theme::SELECTION_COLOR));
+            }
+
+            if let Some(name) = self.results.get(i) {
+                if let Some(bez) = data.get_bezier(name) {
+                    let advance = data
+                        .font
+                        .ufo
+                        .get_glyph(name)
+                        .and_then(|g| g.advance.as_ref())
+                        .map(|a| a.width as f64)
+                        .unwrap_or(upm / 2.0);
+                    // center the glyph horizontally; the baseline is at 80%
+                    let x = PADDING + (PREVIEW_SIZE - advance * scale).max(0.0) / 2.0;
+                    let baseline = top + (ROW_HEIGHT - PREVIEW_SIZE) / 2.0 + PREVIEW_SIZE * 0.8;
+                    let affine = Affine::new([scale, 0.0, 0.0, -scale, x, baseline]);
+                    ctx.fill(affine * &*bez, &glyph_color);
+                }
+            }
+
+            let text_x = PADDING * 2.0 + PREVIEW_SIZE;
+            let text_y = top + (ROW_HEIGHT - row.size().height) / 2.0;
+            row.draw(ctx, (text_x, text_y));
+        }
+    }
+}
@@ -1,20 +1,36 @@
 //! Controller widgets
 
 use druid::widget::{prelude::*, Controller};
-use druid::{InternalLifeCycle, LensExt, Rect, WidgetExt, WidgetPod};
+use druid::{Data, InternalLifeCycle, LensExt, Rect, WidgetExt, WidgetPod};
 
 use crate::consts;
-use crate::data::{AppState, EditorState};
+use crate::data::{AppState, EditorState, WorkspaceId};
 use crate::edit_session::EditSession;
 use crate::menus;
-use crate::widgets::{CoordPane, FloatingPanel, GlyphPane, Toolbar};
+use crate::tools::ToolId;
+use crate::widgets::{CoordPane, FloatingPanel, GlyphPane, IssuePane, Toolbar};
 
 /// the distance from the edge of a floating panel to the edge of the window.
 const FLOATING_PANEL_PADDING: f64 = 20.0;
 
 /// A widget that wraps all root widgets
-#[derive(Debug, Default)]
-pub struct RootWindowController;
+#[derive(Debug)]
+pub struct RootWindowController {
+    /// The font this window belongs to.
+    workspace: WorkspaceId,
+}
+
+impl RootWindowController {
+    pub fn new(workspace: WorkspaceId) -> Self {
+        RootWindowController { workspace }
+    }
+
+    fn make_menu(&self, data: &AppState) -> Option<druid::MenuDesc<AppState>> {
+        data.workspaces
+            .get(&self.workspace)
+            .map(|ws| menus::make_menu(ws, &data.preferences))
+    }
+}
 
 impl<W: Widget<AppState>> Controller<AppState, W> for RootWindowController {
     fn event(
@@ -27,8 +43,9 @@ impl<W: Widget<AppState>> Controller<AppState, W> for RootWindowController {
     ) {
         match event {
             Event::Command(cmd) if cmd.is(consts::cmd::REBUILD_MENUS) => {
-                let menu = menus::make_menu(data);
-                ctx.set_menu(menu);
+                if let Some(menu) = self.make_menu(data) {
+                    ctx.set_menu(menu);
+                }
             }
             other => child.event(ctx, other, data, env),
         }
@@ -42,9 +59,25 @@ impl<W: Widget<AppState>> Controller<AppState, W> for RootWindowController {
         data: &AppState,
         env: &Env,
     ) {
-        if old_data.workspace.selected.is_none() != data.workspace.selected.is_none() {
-            let menu = menus::make_menu(data);
-            ctx.set_menu(menu);
+        let has_selection = |data: &AppState| {
+            data.workspaces
+                .get(&self.workspace)
+                .map(|ws| !ws.selection.is_empty())
+                .unwrap_or(false)
+        };
+        let recent_files_changed = !old_data
+            .preferences
+            .recent_files
+            .same(&data.preferences.recent_files);
+        let grid_sort =
+            |data: &AppState| data.workspaces.get(&self.workspace).map(|ws| ws.grid_sort);
+        if has_selection(old_data) != has_selection(data)
+            || grid_sort(old_data) != grid_sort(data)
+            || recent_files_changed
+        {
+            if let Some(menu) = self.make_menu(data) {
+                ctx.set_menu(menu);
+            }
         }
         child.update(ctx, old_data, data, env);
     }
@@ -54,15 +87,19 @@ impl<W: Widget<AppState>> Controller<AppState, W> for RootWindowController {
 //TODO: we could combine this with controller above if we wanted?
 pub struct EditorController<W> {
     inner: W,
+    /// The tool selected when the editor opens.
+    initial_tool: ToolId,
     toolbar: WidgetPod<(), FloatingPanel<Toolbar>>,
     coord_panel: WidgetPod<EditorState, FloatingPanel<Box<dyn Widget<EditorState>>>>,
     glyph_panel: WidgetPod<EditorState, FloatingPanel<Box<dyn Widget<EditorState>>>>,
+    issue_panel: WidgetPod<EditorState, FloatingPanel<IssuePane>>,
 }
 
 impl<W> EditorController<W> {
-    pub fn new(inner: W) -> Self {
+    pub fn new(inner: W, initial_tool: ToolId) -> Self {
         EditorController {
             inner,
+            initial_tool,
             toolbar: WidgetPod::new(FloatingPanel::new(Toolbar::default())),
             coord_panel: WidgetPod::new(FloatingPanel::new(
                 CoordPane::new()
@@ -70,6 +107,7 @@ impl<W> EditorController<W> {
                     .boxed(),
             )),
             glyph_panel: WidgetPod::new(FloatingPanel::new(GlyphPane::new().boxed())),
+            issue_panel: WidgetPod::new(FloatingPanel::new(IssuePane::new())),
         }
     }
 }
@@ -89,6 +127,7 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
         self.toolbar.event(ctx, event, &mut (), env);
         self.coord_panel.event(ctx, event, data, env);
         self.glyph_panel.event(ctx, event, data, env);
+        self.issue_panel.event(ctx, event, data, env);
         if !ctx.is_handled() {
             self.inner.event(ctx, event, data, env);
         }
@@ -101,6 +140,10 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
         data: &EditorState,
         env: &Env,
     ) {
+        if let LifeCycle::WidgetAdded = event {
+            let cmd = consts::cmd::SET_TOOL.with(self.initial_tool);
+            ctx.submit_command(cmd.to(ctx.widget_id()));
+        }
         //HACK: we don't have 'ambient focus', so after the coord panel takes
         //focus, and then finishes editing, we need to tell the editor to
         //take focus back again so that it can handle keyboard input.
@@ -111,6 +154,7 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
         self.toolbar.lifecycle(ctx, event, &(), env);
         self.coord_panel.lifecycle(ctx, event, data, env);
         self.glyph_panel.lifecycle(ctx, event, data, env);
+        self.issue_panel.lifecycle(ctx, event, data, env);
         self.inner.lifecycle(ctx, event, data, env);
     }
 
@@ -123,6 +167,7 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
     ) {
         self.coord_panel.update(ctx, data, env);
         self.glyph_panel.update(ctx, data, env);
+        self.issue_panel.update(ctx, data, env);
         self.inner.update(ctx, old_data, data, env);
     }
 
@@ -155,6 +200,14 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
         );
         let frame = Rect::from_origin_size(orig, size);
         self.glyph_panel.set_layout_rect(ctx, data, env, frame);
+
+        let size = self.issue_panel.layout(ctx, &child_bc, data, env);
+        let orig = (
+            our_size.width - size.width - FLOATING_PANEL_PADDING,
+            FLOATING_PANEL_PADDING,
+        );
+        let frame = Rect::from_origin_size(orig, size);
+        self.issue_panel.set_layout_rect(ctx, data, env, frame);
         our_size
     }
 
@@ -162,6 +215,7 @@ impl<W: Widget<EditorState>> Widget<EditorState> for EditorController<W> {
         self.inner.paint(ctx, data, env);
         self.coord_panel.paint(ctx, data, env);
         self.glyph_panel.paint(ctx, data, env);
+        self.issue_panel.paint(ctx, data, env);
         self.toolbar.paint(ctx, &(), env);
     }
 }
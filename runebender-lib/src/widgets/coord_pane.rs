@@ -1,10 +1,11 @@
 //! The floating panel that displays the coordinate of the currently
-//! selected point.
+//! selected point, or the transform of the selected component.
 
 use druid::kurbo::Circle;
 use druid::widget::{prelude::*, Controller, CrossAxisAlignment, Either, Flex, Label, SizedBox};
-use druid::{Color, FontDescriptor, FontFamily, FontStyle, Point, WidgetExt};
+use druid::{Color, FontDescriptor, FontFamily, FontStyle, Lens, LensExt, Point, WidgetExt};
 
+use crate::component::ComponentTransform;
 use crate::design_space::{DPoint, DVec2};
 use crate::edit_session::CoordinateSelection;
 use crate::quadrant::Quadrant;
@@ -37,7 +38,11 @@ impl<W: Widget<CoordinateSelection>> Controller<CoordinateSelection, W> for Coor
 
         // if another edit has occured in the coordpanel, we turn it into
         // a command so that the Editor can update undo state:
-        if child_data.frame.origin() != data.frame.origin() {
+        if child_data.component != data.component {
+            if let Some(transform) = child_data.component {
+                ctx.submit_command(consts::cmd::SET_COMPONENT_TRANSFORM.with(transform));
+            }
+        } else if child_data.frame.origin() != data.frame.origin() {
             let delta = child_data.frame.origin() - data.frame.origin();
             ctx.submit_command(consts::cmd::NUDGE_SELECTION.with(DVec2::from_raw(delta)));
         } else if child_data.frame.size() != data.frame.size() {
@@ -51,7 +56,7 @@ impl<W: Widget<CoordinateSelection>> Controller<CoordinateSelection, W> for Coor
         }
 
         // suppress clicks so that the editor doesn't handle them.
-        if matches!(event,Event::MouseUp(_) | Event::MouseDown(_)) {
+        if matches!(event, Event::MouseUp(_) | Event::MouseDown(_)) {
             ctx.set_handled();
         }
     }
@@ -202,6 +207,64 @@ fn build_widget() -> impl Widget<CoordinateSelection> {
         .with_child(bbox_info)
         .padding(4.0);
 
+    let selection_editor = Either::new(
+        |d: &CoordinateSelection, _| d.component.is_some(),
+        component_editor(),
+        picker_and_editor,
+    );
+
     // if we have any points selected, show the numerical adjust widget, else an empty widget
-    Either::new(|d, _| d.count != 0, picker_and_editor, SizedBox::empty())
+    Either::new(|d, _| d.count != 0, selection_editor, SizedBox::empty())
+}
+
+/// The fields for editing the transform of a single selected component.
+fn component_editor() -> impl Widget<CoordinateSelection> {
+    let offset = Flex::column()
+        .with_child(component_field("x", ComponentTransform::offset_x))
+        .with_child(component_field("y", ComponentTransform::offset_y));
+    let scale = Flex::column()
+        .with_child(component_field("w%", ComponentTransform::scale_x))
+        .with_child(component_field("h%", ComponentTransform::scale_y));
+    let rotation = Flex::column().with_child(component_field("°", ComponentTransform::rotation));
+
+    // the default is never shown; this widget is only visible if a component
+    // is selected.
+    let component_lens = CoordinateSelection::component.map(
+        |component: &Option<ComponentTransform>| component.unwrap_or_default(),
+        |component: &mut Option<ComponentTransform>, new: ComponentTransform| {
+            *component = Some(new)
+        },
+    );
+
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(offset)
+        .with_spacer(4.0)
+        .with_child(scale)
+        .with_spacer(4.0)
+        .with_child(rotation)
+        .lens(component_lens)
+        .padding(4.0)
+}
+
+fn component_field(
+    label: &str,
+    lens: impl Lens<ComponentTransform, f64> + 'static,
+) -> impl Widget<ComponentTransform> {
+    let label_font: FontDescriptor =
+        FontDescriptor::new(FontFamily::SERIF).with_style(FontStyle::Italic);
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Baseline)
+        .with_child(
+            Label::new(label)
+                .with_font(label_font)
+                .with_text_color(theme::SECONDARY_TEXT_COLOR),
+        )
+        .with_spacer(2.0)
+        .with_child(
+            EditableLabel::parse()
+                .with_font(theme::UI_DETAIL_FONT)
+                .lens(lens)
+                .fix_width(40.0),
+        )
 }
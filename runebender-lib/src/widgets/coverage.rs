@@ -0,0 +1,338 @@
+//! A window showing which Unicode blocks and scripts a font covers.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label, LineBreaking, Painter, Scroll};
+use druid::{
+    commands, FileDialogOptions, FileInfo, Point, Selector, WidgetExt, WidgetPod, WindowId,
+};
+
+use crate::charset::{parse_charset_file, TEMPLATES};
+use crate::consts::cmd::{ADD_GLYPHS_FOR_CHARS, NOTIFY};
+use crate::coverage::{Coverage, GroupCoverage, Status, Target};
+use crate::data::{FontObject, Notification, Workspace};
+use crate::menus::CHARSET_FILE_TYPE;
+use crate::theme;
+use crate::unicode;
+
+const STATUS_DOT_SIZE: f64 = 8.0;
+/// The most missing characters we list for a block; a block such as CJK
+/// Unified Ideographs can be missing tens of thousands.
+const MAX_LISTED: usize = 256;
+
+/// Sent by the buttons in the coverage window to change the characters the
+/// font is checked against.
+///
+/// The argument **must** be the new `Target`.
+const SET_TARGET: Selector<Target> = Selector::new("runebender.coverage-set-target");
+
+/// Sent when the user has chosen a character set file to check the font
+/// against.
+///
+/// The argument **must** be the `FileInfo` from the open panel.
+const TARGET_FROM_FILE: Selector<FileInfo> = Selector::new("runebender.coverage-target-from-file");
+
+/// Sent by a block's 'Show' and 'Hide' buttons, to list or stop listing the
+/// characters missing from that block.
+///
+/// The argument **must** be the name of the block.
+const TOGGLE_BLOCK: Selector<&'static str> = Selector::new("runebender.coverage-toggle-block");
+
+/// The contents of a coverage window.
+///
+/// Unlike the QA report, coverage is kept up to date as glyphs are added;
+/// adding glyphs sends commands to `font_window`.
+pub fn coverage_view(font_window: Option<WindowId>) -> impl Widget<Workspace> {
+    CoverageView {
+        font: None,
+        font_window,
+        target: Target::default(),
+        expanded: BTreeSet::new(),
+        inner: WidgetPod::new(Flex::column().boxed()),
+    }
+}
+
+/// Rebuilds its contents whenever the font or the target changes.
+struct CoverageView {
+    font: Option<Arc<FontObject>>,
+    font_window: Option<WindowId>,
+    target: Target,
+    /// The blocks whose missing characters are listed; the others are
+    /// collapsed, so that we don't build a label for every character.
+    expanded: BTreeSet<&'static str>,
+    inner: WidgetPod<Workspace, Box<dyn Widget<Workspace>>>,
+}
+
+impl CoverageView {
+    fn rebuild(&mut self, data: &Workspace) {
+        let coverage = Coverage::for_workspace(data, &self.target);
+        let list = coverage_list(&coverage, &self.target, &self.expanded, self.font_window);
+        self.inner = WidgetPod::new(list.boxed());
+        self.font = Some(data.font.clone());
+    }
+}
+
+impl Widget<Workspace> for CoverageView {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Workspace, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(SET_TARGET) => {
+                self.target = cmd.get_unchecked(SET_TARGET).clone();
+                self.rebuild(data);
+                ctx.children_changed();
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(TOGGLE_BLOCK) => {
+                let block = cmd.get_unchecked(TOGGLE_BLOCK);
+                if !self.expanded.remove(block) {
+                    self.expanded.insert(block);
+                }
+                self.rebuild(data);
+                ctx.children_changed();
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(TARGET_FROM_FILE) => {
+                let info = cmd.get_unchecked(TARGET_FROM_FILE);
+                let glyphs = std::fs::read_to_string(info.path())
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| parse_charset_file(&text).map_err(|e| e.to_string()));
+                match glyphs {
+                    Ok(glyphs) => {
+                        let name = info
+                            .path()
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        ctx.submit_command(SET_TARGET.with(Target::charset(name, &glyphs)));
+                    }
+                    Err(e) => match self.font_window {
+                        Some(window) => {
+                            let notification = Notification::error(format!(
+                                "Failed to read {}",
+                                info.path().display()
+                            ))
+                            .with_details(e);
+                            ctx.submit_command(NOTIFY.with(notification).to(window));
+                        }
+                        None => log::error!("failed to read {}: '{}'", info.path().display(), e),
+                    },
+                }
+                ctx.set_handled();
+            }
+            _ => self.inner.event(ctx, event, data, env),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Workspace,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rebuild(data);
+        }
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &Workspace, data: &Workspace, env: &Env) {
+        let changed = match &self.font {
+            Some(font) => !Arc::ptr_eq(font, &data.font),
+            None => true,
+        };
+        if changed {
+            self.rebuild(data);
+            ctx.children_changed();
+        } else {
+            self.inner.update(ctx, data, env);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Workspace,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, data, env, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Workspace, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+}
+
+fn coverage_list(
+    coverage: &Coverage,
+    target: &Target,
+    expanded: &BTreeSet<&'static str>,
+    font_window: Option<WindowId>,
+) -> impl Widget<Workspace> {
+    let (present, missing) = coverage.totals();
+    let summary = format!(
+        "{} characters, {} missing from {}.",
+        present,
+        missing,
+        target.title()
+    );
+
+    let mut column = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(target_buttons(target))
+        .with_default_spacer()
+        .with_child(Label::new(summary).with_text_color(theme::SECONDARY_TEXT_COLOR));
+    if let (Some(window), false) = (font_window, missing == 0) {
+        column.add_default_spacer();
+        column.add_child(add_button(
+            "Add All Missing Glyphs".into(),
+            coverage.all_missing(),
+            window,
+        ));
+    }
+
+    column.add_spacer(16.0);
+    column.add_child(Label::new("Scripts").with_text_color(theme::PRIMARY_TEXT_COLOR));
+    for group in &coverage.scripts {
+        column.add_child(group_row(group, None, None));
+    }
+
+    column.add_spacer(16.0);
+    column.add_child(Label::new("Blocks").with_text_color(theme::PRIMARY_TEXT_COLOR));
+    for group in &coverage.blocks {
+        let is_expanded = expanded.contains(group.name);
+        column.add_child(group_row(group, Some(is_expanded), font_window));
+        if !is_expanded {
+            continue;
+        }
+        for chr in group.missing.iter().take(MAX_LISTED) {
+            let name = unicode::name(*chr).unwrap_or_default();
+            column.add_child(missing_row(format!(
+                "U+{:04X} {} {}",
+                *chr as u32, chr, name
+            )));
+        }
+        if group.missing.len() > MAX_LISTED {
+            let more = group.missing.len() - MAX_LISTED;
+            column.add_child(missing_row(format!("…and {} more", more)));
+        }
+    }
+
+    Scroll::new(column.padding(16.0))
+        .vertical()
+        .expand()
+        .background(theme::GLYPH_LIST_BACKGROUND)
+}
+
+/// Buttons to check the font against the standard characters, a template,
+/// or a character set file; the current target's button is marked.
+fn target_buttons(current: &Target) -> impl Widget<Workspace> {
+    let mut row = Flex::row();
+    let targets = std::iter::once(("Standard", Target::Standard)).chain(
+        TEMPLATES
+            .iter()
+            .map(|template| (template.name, Target::Template(*template))),
+    );
+    for (label, target) in targets {
+        let label = if &target == current {
+            format!("• {}", label)
+        } else {
+            label.to_string()
+        };
+        row.add_child(
+            Button::new(label)
+                .on_click(move |ctx, _, _| ctx.submit_command(SET_TARGET.with(target.clone()))),
+        );
+        row.add_spacer(4.0);
+    }
+    row.add_child(Button::new("Load Charset…").on_click(|ctx, _, _| {
+        let options = FileDialogOptions::new()
+            .allowed_types(vec![CHARSET_FILE_TYPE])
+            .accept_command(TARGET_FROM_FILE);
+        ctx.submit_command(commands::SHOW_OPEN_PANEL.with(options));
+    }));
+    row
+}
+
+/// A status dot, the group's name and counts, and optionally buttons to
+/// list the group's missing characters, to check every character in the
+/// group's block, and to add the group's missing glyphs.
+///
+/// `expanded` is whether the missing characters are listed, for groups
+/// that can list them.
+fn group_row(
+    group: &GroupCoverage,
+    expanded: Option<bool>,
+    font_window: Option<WindowId>,
+) -> impl Widget<Workspace> {
+    let text = match group.status() {
+        Status::Complete => format!("{} ({})", group.name, group.present),
+        _ => format!(
+            "{} ({} of {})",
+            group.name,
+            group.present,
+            group.present + group.missing.len()
+        ),
+    };
+    let mut row = Flex::row()
+        .with_child(status_dot(group.status()))
+        .with_spacer(8.0)
+        .with_child(Label::new(text).with_text_color(theme::PRIMARY_TEXT_COLOR));
+    row.add_flex_spacer(1.0);
+    if let (Some(expanded), false) = (expanded, group.missing.is_empty()) {
+        let name = group.name;
+        let label = if expanded { "Hide" } else { "Show" };
+        row.add_child(
+            Button::new(label)
+                .on_click(move |ctx, _, _| ctx.submit_command(TOGGLE_BLOCK.with(name))),
+        );
+        row.add_spacer(4.0);
+    }
+    if let Some(block) = group.block {
+        row.add_child(
+            Button::new("Check All").on_click(move |ctx, _, _| {
+                ctx.submit_command(SET_TARGET.with(Target::Block(block)))
+            }),
+        );
+    }
+    if let (Some(window), false) = (font_window, group.missing.is_empty()) {
+        row.add_spacer(4.0);
+        row.add_child(add_button(
+            format!("Add {}", group.missing.len()),
+            group.missing.clone(),
+            window,
+        ));
+    }
+    row.padding((0.0, 2.0))
+}
+
+fn missing_row(text: String) -> impl Widget<Workspace> {
+    Label::new(text)
+        .with_font(theme::UI_DETAIL_FONT)
+        .with_text_color(theme::SECONDARY_TEXT_COLOR)
+        .with_line_break_mode(LineBreaking::WordWrap)
+        .padding((24.0, 1.0))
+}
+
+fn status_dot(status: Status) -> impl Widget<Workspace> {
+    Painter::new(move |ctx, _: &Workspace, env| {
+        let color = match status {
+            Status::Complete => env.get(theme::SECONDARY_TEXT_COLOR),
+            Status::Partial => env.get(theme::WARNING_COLOR),
+            Status::Missing => env.get(theme::ERROR_COLOR),
+        };
+        let rect = ctx.size().to_rect();
+        ctx.fill(rect.to_ellipse(), &color);
+    })
+    .fix_size(STATUS_DOT_SIZE, STATUS_DOT_SIZE)
+}
+
+fn add_button(label: String, chars: Vec<char>, window: WindowId) -> impl Widget<Workspace> {
+    Button::new(label).on_click(move |ctx, _, _| {
+        ctx.submit_command(ADD_GLYPHS_FOR_CHARS.with(chars.clone()).to(window));
+    })
+}
@@ -0,0 +1,80 @@
+//! A prompt shown when deleting glyphs that other glyphs use as components.
+//!
+//! This is intended to be shown as a modal panel.
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label, LineBreaking};
+use druid::{Color, WidgetExt};
+use norad::GlyphName;
+
+use crate::consts::cmd::DELETE_GLYPHS;
+use crate::data::Workspace;
+use crate::theme;
+use crate::widgets::ModalHost;
+
+/// The most dependent glyphs we list by name.
+const MAX_LISTED: usize = 8;
+
+pub fn delete_glyph_prompt(
+    glyphs: Vec<GlyphName>,
+    dependents: Vec<GlyphName>,
+) -> impl Widget<Workspace> {
+    let title = match glyphs.as_slice() {
+        [glyph] => format!("Delete “{}”?", glyph),
+        glyphs => format!("Delete {} glyphs?", glyphs.len()),
+    };
+    let title = Label::new(title);
+    let subject = if glyphs.len() == 1 {
+        "It is"
+    } else {
+        "They are"
+    };
+
+    let mut names = dependents
+        .iter()
+        .take(MAX_LISTED)
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if dependents.len() > MAX_LISTED {
+        names.push_str(&format!(" and {} more", dependents.len() - MAX_LISTED));
+    }
+    let detail = match dependents.len() {
+        1 => format!("{} used as a component in {}.", subject, names),
+        n => format!(
+            "{} used as a component in {} glyphs: {}.",
+            subject, n, names
+        ),
+    };
+    let detail = Label::new(format!(
+        "{} These components will be decomposed, keeping their outlines.",
+        detail
+    ))
+    .with_line_break_mode(LineBreaking::WordWrap)
+    .with_text_color(theme::SECONDARY_TEXT_COLOR);
+
+    let cancel = Button::new("Cancel").on_click(|ctx, _, _| {
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+    });
+    let delete = Button::new("Decompose and Delete").on_click(move |ctx, _, _| {
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+        ctx.submit_command(DELETE_GLYPHS.with(glyphs.clone()));
+    });
+
+    Flex::column()
+        .with_child(title)
+        .with_default_spacer()
+        .with_child(detail)
+        .with_spacer(16.0)
+        .with_child(
+            Flex::row()
+                .with_flex_spacer(1.0)
+                .with_child(cancel)
+                .with_default_spacer()
+                .with_child(delete),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .fix_width(360.)
+        .padding(16.0)
+        .background(Color::WHITE)
+}
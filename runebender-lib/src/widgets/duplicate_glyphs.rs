@@ -0,0 +1,166 @@
+//! A panel for duplicating the selected glyphs under a new suffix.
+//!
+//! This is intended to be shown as a modal panel.
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label, LineBreaking, TextBox};
+use druid::{Color, Data, HotKey, KbKey, Lens, WidgetExt, WidgetPod};
+
+use crate::consts::cmd::DUPLICATE_GLYPHS;
+use crate::data::Workspace;
+use crate::glyph_names::is_valid_glyph_name;
+use crate::theme;
+use crate::widgets::ModalHost;
+
+const WIDTH: f64 = 420.0;
+const SUFFIX_WIDTH: f64 = 120.0;
+
+/// The suffix being edited in the panel.
+#[derive(Debug, Clone, Data, Lens)]
+struct DuplicateGlyphs {
+    suffix: String,
+    /// The first stylistic set that isn't used in the font, like `ss02`.
+    next_set: String,
+    /// The reason the suffix can't be used, if it can't.
+    error: String,
+}
+
+/// The panel keeps its own state, since nothing is changed in the font
+/// until one of its buttons is pressed.
+struct DuplicateGlyphsPanel {
+    state: DuplicateGlyphs,
+    inner: WidgetPod<DuplicateGlyphs, Box<dyn Widget<DuplicateGlyphs>>>,
+}
+
+pub fn duplicate_glyphs_panel(glyph_count: usize) -> impl Widget<Workspace> {
+    let title = match glyph_count {
+        1 => "Duplicate 1 glyph".to_string(),
+        n => format!("Duplicate {} glyphs", n),
+    };
+    let help = "Copies are not encoded. Components that use another duplicated \
+                glyph use its copy instead, so a set of glyphs can be made into \
+                a stylistic set.";
+
+    let suffix = Flex::row()
+        .with_child(Label::new("suffix").with_text_color(theme::SECONDARY_TEXT_COLOR))
+        .with_default_spacer()
+        .with_child(
+            TextBox::new()
+                .lens(DuplicateGlyphs::suffix)
+                .fix_width(SUFFIX_WIDTH),
+        )
+        .with_default_spacer()
+        .with_child(suffix_button("alt"))
+        .with_default_spacer()
+        .with_child(suffix_button("sc"))
+        .with_default_spacer()
+        .with_child(
+            Button::new(|data: &DuplicateGlyphs, _: &Env| format!(".{}", data.next_set))
+                .on_click(|_, data: &mut DuplicateGlyphs, _| data.suffix = data.next_set.clone()),
+        );
+    let error = Label::new(|data: &DuplicateGlyphs, _: &Env| data.error.clone())
+        .with_font(theme::UI_DETAIL_FONT)
+        .with_text_color(theme::ERROR_COLOR);
+
+    let cancel = Button::new("Cancel").on_click(|ctx, _, _| {
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+    });
+    let duplicate = Button::new("Duplicate").on_click(|ctx, data: &mut DuplicateGlyphs, _| {
+        let suffix = data.suffix.trim().trim_start_matches('.');
+        if suffix.is_empty() || !is_valid_glyph_name(suffix) {
+            data.error = "Suffixes can only include a-z, A-Z, 0-9, '.', '_'.".into();
+            return;
+        }
+        ctx.submit_command(DUPLICATE_GLYPHS.with(suffix.to_string()));
+        ctx.submit_command(ModalHost::DISMISS_MODAL);
+    });
+
+    let inner = Flex::column()
+        .with_child(Label::new(title))
+        .with_spacer(16.0)
+        .with_child(suffix)
+        .with_default_spacer()
+        .with_child(
+            Label::new(help)
+                .with_font(theme::UI_DETAIL_FONT)
+                .with_text_color(theme::SECONDARY_TEXT_COLOR)
+                .with_line_break_mode(LineBreaking::WordWrap),
+        )
+        .with_default_spacer()
+        .with_child(error)
+        .with_spacer(16.0)
+        .with_child(
+            Flex::row()
+                .with_flex_spacer(1.0)
+                .with_child(cancel)
+                .with_default_spacer()
+                .with_child(duplicate),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .fix_width(WIDTH)
+        .padding(16.0)
+        .background(Color::WHITE);
+
+    DuplicateGlyphsPanel {
+        state: DuplicateGlyphs {
+            suffix: "alt".into(),
+            next_set: "ss01".into(),
+            error: String::new(),
+        },
+        inner: WidgetPod::new(inner.boxed()),
+    }
+}
+
+fn suffix_button(suffix: &'static str) -> impl Widget<DuplicateGlyphs> {
+    Button::new(format!(".{}", suffix)).on_click(move |_, data: &mut DuplicateGlyphs, _| {
+        data.suffix = suffix.to_string();
+    })
+}
+
+impl Widget<Workspace> for DuplicateGlyphsPanel {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut Workspace, env: &Env) {
+        if let Event::KeyDown(k) = event {
+            if HotKey::new(None, KbKey::Escape).matches(k) {
+                ctx.submit_command(ModalHost::DISMISS_MODAL);
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.inner.event(ctx, event, &mut self.state, env);
+        ctx.request_update();
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Workspace,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.state.next_set = data.next_stylistic_set();
+        }
+        self.inner.lifecycle(ctx, event, &self.state, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &Workspace, _data: &Workspace, env: &Env) {
+        self.inner.update(ctx, &self.state, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Workspace,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, &self.state, env);
+        self.inner
+            .set_layout_rect(ctx, &self.state, env, size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &Workspace, env: &Env) {
+        self.inner.paint(ctx, &self.state, env);
+    }
+}
@@ -2,11 +2,14 @@
 
 use std::sync::Arc;
 
+use druid::kurbo::Vec2;
 use druid::widget::prelude::*;
-use druid::{Application, Clipboard, ClipboardFormat, Command, ContextMenu, Data, KbKey};
+use druid::{Application, Clipboard, ClipboardFormat, Command, ContextMenu, Data, KbKey, Selector};
+use norad::GlyphName;
 
 use crate::consts::{self, CANVAS_SIZE};
-use crate::data::EditorState;
+use crate::data::{EditorState, Notification};
+use crate::design_space::{DPoint, DVec2};
 use crate::draw;
 use crate::edit_session::EditSession;
 use crate::mouse::{Mouse, TaggedEvent};
@@ -14,6 +17,10 @@ use crate::theme;
 use crate::tools::{EditType, Select, Tool};
 use crate::undo::UndoState;
 
+/// Sent to ourselves when the outlines our components point to have changed.
+const REFRESH_COMPONENT_BOUNDS: Selector =
+    Selector::new("runebender.editor-refresh-component-bounds");
+
 /// The root widget of the glyph editor window.
 pub struct Editor {
     mouse: Mouse,
@@ -116,7 +123,12 @@ impl Editor {
         }
     }
 
-    fn do_paste(&self, session: &mut EditSession, clipboard: &Clipboard) -> Option<EditType> {
+    fn do_paste(
+        &self,
+        ctx: &mut EventCtx,
+        session: &mut EditSession,
+        clipboard: &Clipboard,
+    ) -> Option<EditType> {
         let paste_types = [
             crate::consts::GLYPHS_APP_PASTEBOARD_TYPE,
             ClipboardFormat::PDF,
@@ -124,10 +136,10 @@ impl Editor {
         ];
         if let Some(match_) = clipboard.preferred_format(&paste_types) {
             let paths = match (match_, clipboard.get_format(match_)) {
-                (_, None) => {
-                    log::warn!("no data returned for declared clipboard format {}", match_);
-                    return None;
-                }
+                (_, None) => Err(format!(
+                    "no data returned for declared clipboard format {}",
+                    match_
+                )),
                 (crate::consts::GLYPHS_APP_PASTEBOARD_TYPE, Some(data)) => {
                     match String::from_utf8(data) {
                         Ok(s) => crate::clipboard::from_glyphs_plist_string(s),
@@ -135,17 +147,46 @@ impl Editor {
                     }
                 }
                 (ClipboardFormat::PDF, Some(data)) => crate::clipboard::from_pdf_data(data),
-                _ => None,
+                (other, Some(_)) => Err(format!("pasting {} is not supported", other)),
             };
-            if let Some(paths) = paths {
-                session.paste_paths(paths);
-                return Some(EditType::Normal);
+            match paths {
+                Ok(paths) => {
+                    session.paste_paths(paths);
+                    return Some(EditType::Normal);
+                }
+                Err(e) => {
+                    let notification = Notification::warning("Paste failed").with_details(e);
+                    ctx.submit_command(consts::cmd::NOTIFY.with(notification));
+                }
             }
         }
 
         None
     }
 
+    fn insert_component(
+        &mut self,
+        ctx: &mut EventCtx,
+        base: &GlyphName,
+        data: &mut EditorState,
+    ) -> Option<EditType> {
+        if let Some(problem) = data.font.component_problem(&data.session.name, base) {
+            let message = format!("Can't add component '{}'", base);
+            let notification = Notification::error(message).with_details(problem.description());
+            ctx.submit_command(consts::cmd::NOTIFY.with(notification));
+            return None;
+        }
+        // line the component up on its anchors if we can, else at the origin
+        let offset = data
+            .font
+            .component_placement(&data.session.glyph, base, None)
+            .unwrap_or(Vec2::ZERO);
+        data.session_mut()
+            .add_component(base.clone(), DVec2::from_raw(offset));
+        data.refresh_component_bounds();
+        Some(EditType::Normal)
+    }
+
     /// handle a `Command`. Returns a bool indicating whether the command was
     /// handled at all, and an optional `EditType` if this command did work
     /// that should go on the undo stack.
@@ -182,6 +223,7 @@ impl Editor {
                     let saved_viewport = data.session.viewport;
                     data.session = prev.clone();
                     data.session_mut().viewport = saved_viewport;
+                    data.refresh_component_bounds();
                 }
             }
             c if c.is(druid::commands::REDO) => {
@@ -189,6 +231,7 @@ impl Editor {
                     let saved_viewport = data.session.viewport;
                     data.session = next.clone();
                     data.session_mut().viewport = saved_viewport;
+                    data.refresh_component_bounds();
                 }
             }
             c if c.is(consts::cmd::ALIGN_SELECTION) => {
@@ -216,6 +259,43 @@ impl Editor {
                 data.session_mut().reverse_contours();
                 return (true, Some(EditType::Normal));
             }
+            c if c.is(consts::cmd::DECOMPOSE_COMPONENTS) => {
+                if data.decompose_components() > 0 {
+                    return (true, Some(EditType::Normal));
+                }
+            }
+            c if c.is(consts::cmd::TRANSFORM_GLYPH) => {
+                let affine = c.get_unchecked(consts::cmd::TRANSFORM_GLYPH);
+                data.transform_glyph(*affine);
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::SET_GLYPH_ADVANCE) => {
+                let width = c.get_unchecked(consts::cmd::SET_GLYPH_ADVANCE);
+                data.session_mut().set_advance_width(*width);
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::DECOMPOSE_GLYPH) => {
+                if data.decompose_all_components() > 0 {
+                    return (true, Some(EditType::Normal));
+                }
+            }
+            c if c.is(consts::cmd::FLIP_HORIZONTAL) || c.is(consts::cmd::FLIP_VERTICAL) => {
+                let scale = if c.is(consts::cmd::FLIP_HORIZONTAL) {
+                    Vec2::new(-1.0, 1.0)
+                } else {
+                    Vec2::new(1.0, -1.0)
+                };
+                let center = data.session.selection_dpoint_bbox().center();
+                data.session_mut()
+                    .scale_selection(scale, DPoint::from_raw(center));
+                return (true, Some(EditType::Normal));
+            }
+            c if c.is(consts::cmd::SET_COMPONENT_TRANSFORM) => {
+                let transform = c.get_unchecked(consts::cmd::SET_COMPONENT_TRANSFORM);
+                data.session_mut()
+                    .set_selected_component_transform(transform);
+                return (true, Some(EditType::Normal));
+            }
             // all unhandled commands:
             _ => return (false, None),
         }
@@ -237,6 +317,7 @@ impl Widget<EditorState> for Editor {
         let rect = (CANVAS_SIZE * data.session.viewport.zoom).to_rect();
         ctx.fill(rect, &env.get(theme::GLYPH_LIST_BACKGROUND));
 
+        let issues = data.issues();
         draw::draw_session(
             ctx,
             env,
@@ -245,6 +326,7 @@ impl Widget<EditorState> for Editor {
             &data.metrics,
             &data.session,
             &data.font,
+            &issues,
             self.draw_filled_outlines,
         );
 
@@ -262,10 +344,19 @@ impl Widget<EditorState> for Editor {
     }
 
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut EditorState, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(REFRESH_COMPONENT_BOUNDS) {
+                data.refresh_component_bounds();
+                ctx.set_handled();
+                ctx.request_paint();
+                return;
+            }
+        }
         // we request_paint if selection changes after this event;
         let pre_selection = data.session.selection.clone();
         let pre_paths = data.session.paths.clone();
         let pre_components = data.session.components.clone();
+        let pre_guides = data.session.guides.clone();
 
         let edit = match event {
             Event::WindowConnected => {
@@ -277,6 +368,9 @@ impl Widget<EditorState> for Editor {
                     ctx.request_focus();
                     ctx.set_handled();
                     None
+                } else if let Some(base) = cmd.get(consts::cmd::INSERT_COMPONENT) {
+                    ctx.set_handled();
+                    self.insert_component(ctx, base, data)
                 } else if let Some(tool) = cmd.get(consts::cmd::SET_TOOL) {
                     let tool = crate::tools::tool_for_id(tool).unwrap();
                     self.set_tool(tool);
@@ -299,7 +393,7 @@ impl Widget<EditorState> for Editor {
             Event::MouseUp(m) => self.send_mouse(ctx, TaggedEvent::Up(m.clone()), data, env),
             Event::MouseMove(m) => self.send_mouse(ctx, TaggedEvent::Moved(m.clone()), data, env),
             Event::MouseDown(m) => self.send_mouse(ctx, TaggedEvent::Down(m.clone()), data, env),
-            Event::Paste(clipboard) => self.do_paste(data.session_mut(), clipboard),
+            Event::Paste(clipboard) => self.do_paste(ctx, data.session_mut(), clipboard),
             _ => None,
         };
 
@@ -308,7 +402,10 @@ impl Widget<EditorState> for Editor {
             ctx.request_paint();
         }
 
-        if !pre_paths.same(&data.session.paths) || !pre_components.same(&data.session.components) {
+        if !pre_paths.same(&data.session.paths)
+            || !pre_components.same(&data.session.components)
+            || !pre_guides.same(&data.session.guides)
+        {
             data.session_mut().rebuild_glyph();
         }
     }
@@ -319,5 +416,10 @@ impl Widget<EditorState> for Editor {
         if !old.same(new) {
             ctx.request_paint();
         }
+        // the bounds are needed for hit testing, so update them if the
+        // glyphs used as components might have been edited.
+        if !new.session.components.is_empty() && new.font.beziers_changed(&old.font) {
+            ctx.submit_command(REFRESH_COMPONENT_BOUNDS.to(ctx.widget_id()));
+        }
     }
 }
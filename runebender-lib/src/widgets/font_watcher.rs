@@ -0,0 +1,173 @@
+//! A controller that watches the font's files for changes.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use druid::widget::{prelude::*, Controller};
+use druid::{ExtEventSink, Selector};
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::consts::cmd::NOTIFY;
+use crate::data::{Notification, Workspace};
+
+/// Sent by the watcher thread when files in the font directory change.
+///
+/// The argument **must** be a `Vec<PathBuf>` of the changed files.
+const FONT_FILES_CHANGED: Selector<Vec<PathBuf>> = Selector::new("runebender.font-files-changed");
+
+const WATCH_DEBOUNCE_DURATION: Duration = Duration::from_millis(500);
+
+/// Watches the directory of the current font, and reloads files that are
+/// changed by other programs.
+#[derive(Default)]
+pub struct FontWatcher {
+    sink: Option<ExtEventSink>,
+    widget_id: Option<WidgetId>,
+    // the watcher stops when this is dropped.
+    watcher: Option<RecommendedWatcher>,
+}
+
+impl FontWatcher {
+    fn watch(&mut self, path: Option<&Path>) {
+        self.watcher = None;
+        let (sink, widget_id, path) = match (self.sink.clone(), self.widget_id, path) {
+            (Some(sink), Some(id), Some(path)) => (sink, id, path),
+            _ => return,
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = match notify::watcher(tx, WATCH_DEBOUNCE_DURATION) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::error!("failed to create font watcher: '{}'", e);
+                return;
+            }
+        };
+        if let Err(e) = watcher.watch(path, RecursiveMode::Recursive) {
+            log::error!(
+                "font watcher failed to watch path '{}': '{}'",
+                path.to_string_lossy(),
+                e
+            );
+            return;
+        }
+        self.watcher = Some(watcher);
+        std::thread::spawn(move || forward_changes(rx, sink, widget_id));
+    }
+}
+
+impl<W: Widget<Workspace>> Controller<Workspace, W> for FontWatcher {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Workspace,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd) if cmd.is(FONT_FILES_CHANGED) => {
+                let paths = cmd.get(FONT_FILES_CHANGED).unwrap();
+                let report = data.reload_changed_files(paths);
+                if !report.reloaded.is_empty() {
+                    log::info!("reloaded {} glyphs from disk", report.reloaded.len());
+                }
+                if !report.conflicts.is_empty() {
+                    let names: Vec<_> = report.conflicts.iter().map(|n| n.to_string()).collect();
+                    let notification =
+                        Notification::warning("Glyphs with unsaved changes were modified on disk")
+                            .with_details(names.join(", "));
+                    ctx.submit_command(NOTIFY.with(notification));
+                }
+                if report.info_conflict {
+                    let notification = Notification::warning(
+                        "Font info with unsaved changes was modified on disk",
+                    );
+                    ctx.submit_command(NOTIFY.with(notification));
+                }
+                if !report.plist_conflicts.is_empty() {
+                    let notification = Notification::warning(
+                        "Font data with unsaved changes was modified on disk",
+                    )
+                    .with_details(report.plist_conflicts.join("\n"));
+                    ctx.submit_command(NOTIFY.with(notification));
+                }
+                if !report.errors.is_empty() {
+                    let notification = Notification::error("Failed to reload changes from disk")
+                        .with_details(report.errors.join("\n"));
+                    ctx.submit_command(NOTIFY.with(notification));
+                }
+                ctx.set_handled();
+            }
+            other => child.event(ctx, other, data, env),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Workspace,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.sink = Some(ctx.get_external_handle());
+            self.widget_id = Some(ctx.widget_id());
+            self.watch(data.font.path.as_deref());
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &Workspace,
+        data: &Workspace,
+        env: &Env,
+    ) {
+        if old_data.font.path != data.font.path {
+            self.watch(data.font.path.as_deref());
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+/// Runs on the watcher thread, forwarding batches of changed paths to the
+/// widget until the watcher is dropped.
+fn forward_changes(rx: mpsc::Receiver<DebouncedEvent>, sink: ExtEventSink, target: WidgetId) {
+    while let Ok(event) = rx.recv() {
+        let mut paths = Vec::new();
+        collect_paths(event, &mut paths);
+        // if a bunch of files changed at once (a git checkout, say) we want to
+        // reload them together.
+        while let Ok(event) = rx.try_recv() {
+            collect_paths(event, &mut paths);
+        }
+        if paths.is_empty() {
+            continue;
+        }
+        if sink
+            .submit_command(FONT_FILES_CHANGED, paths, target)
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+fn collect_paths(event: DebouncedEvent, paths: &mut Vec<PathBuf>) {
+    match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Remove(path) => paths.push(path),
+        DebouncedEvent::Rename(from, to) => {
+            paths.push(from);
+            paths.push(to);
+        }
+        DebouncedEvent::Error(e, path) => log::warn!("font watcher error {:?}: '{}'", path, e),
+        _ => (),
+    }
+}
@@ -0,0 +1,136 @@
+//! The root widget for a font's main window, with the glyph grid.
+
+use druid::kurbo::Line;
+use druid::widget::prelude::*;
+use druid::widget::{Button, Checkbox, Either, Flex, Label, Painter, Scroll, Slider, TextBox};
+use druid::WidgetExt;
+
+use super::grid::list_header;
+use super::preferences::SavePreferences;
+use crate::data::{AppState, Workspace, WorkspaceId};
+use crate::glyph_filter::GlyphFilter;
+use crate::preferences::{
+    Preferences, Theme, GLYPH_GRID_LIST_MODE, MAX_GLYPH_GRID_CELL_SIZE, MIN_GLYPH_GRID_CELL_SIZE,
+};
+use crate::theme;
+use crate::widgets::{
+    font_info, status_bar, FontWatcher, GlyphGrid, ModalHost, RootWindowController, Sidebar,
+};
+
+/// The contents of the main window for the font with the given id.
+pub fn font_window(id: WorkspaceId, theme: Theme) -> impl Widget<AppState> {
+    // paint a line under the top title bar
+    let hline_painter = Painter::new(|ctx, _: &Workspace, env| {
+        let rect = ctx.size().to_rect();
+        let max_y = rect.height() - 0.5;
+        let line = Line::new((0.0, max_y), (rect.width(), max_y));
+
+        ctx.fill(rect, &env.get(theme::GLYPH_LIST_BACKGROUND));
+        ctx.stroke(line, &env.get(theme::SIDEBAR_EDGE_STROKE), 1.0);
+    });
+
+    let label = Label::new(|data: &Workspace, _: &Env| {
+        format!("{} {}", data.info.family_name, data.info.style_name)
+    });
+
+    let button = Button::new("(edit)").on_click(move |ctx, _data, _env| {
+        let cmd = ModalHost::make_modal_command(move || font_info().lens(AppState::workspace(id)));
+        ctx.submit_command(cmd);
+    });
+
+    let main_view = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(label)
+                .with_spacer(8.0)
+                .with_child(button)
+                .padding(5.0)
+                .center()
+                .fix_height(40.)
+                .expand_width()
+                .background(hline_painter),
+        )
+        .with_flex_child(
+            Flex::row()
+                .with_child(Sidebar::new().fix_width(180.))
+                .with_flex_child(
+                    Flex::column()
+                        .with_child(search_bar())
+                        .with_child(Either::new(
+                            |_, env| env.get(GLYPH_GRID_LIST_MODE),
+                            list_header(),
+                            Flex::row(),
+                        ))
+                        .with_flex_child(
+                            Scroll::new(GlyphGrid::new()).vertical().expand_width(),
+                            1.0,
+                        ),
+                    1.0,
+                ),
+            1.,
+        );
+
+    let main_view = main_view
+        .controller(FontWatcher::default())
+        .lens(AppState::workspace(id));
+
+    theme::wrap_in_theme_loader(
+        theme,
+        ModalHost::new(
+            Flex::column()
+                .with_flex_child(main_view, 1.0)
+                .with_child(
+                    grid_view_bar()
+                        .lens(AppState::preferences)
+                        .controller(SavePreferences::new()),
+                )
+                .with_child(status_bar(id)),
+        )
+        .controller(RootWindowController::new(id)),
+    )
+}
+
+/// The search field and quick filters above the glyph grid.
+fn search_bar() -> impl Widget<Workspace> {
+    let search = TextBox::new()
+        .with_placeholder("Search name, character, /regex/, cat:Lu, script:latin, tag:done")
+        .lens(GlyphFilter::query)
+        .expand_width();
+
+    Flex::row()
+        .with_flex_child(search, 1.0)
+        .with_default_spacer()
+        .with_child(Checkbox::new("Empty").lens(GlyphFilter::empty))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Composites").lens(GlyphFilter::composites))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Has Errors").lens(GlyphFilter::has_errors))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Modified").lens(GlyphFilter::modified))
+        .padding(8.0)
+        .lens(Workspace::grid_filter)
+}
+
+/// Controls for how the glyph grid is shown; these are preferences, so they
+/// apply to every font.
+fn grid_view_bar() -> impl Widget<Preferences> {
+    let list_mode = |data: &Preferences, _: &Env| data.glyph_grid_list_mode;
+    let zoom = Flex::row()
+        .with_child(Label::new("Size").with_text_color(theme::SECONDARY_TEXT_COLOR))
+        .with_default_spacer()
+        .with_child(
+            Slider::new()
+                .with_range(MIN_GLYPH_GRID_CELL_SIZE, MAX_GLYPH_GRID_CELL_SIZE)
+                .lens(Preferences::glyph_grid_cell_size)
+                .fix_width(160.0),
+        )
+        .with_default_spacer()
+        .with_child(Checkbox::new("Labels").lens(Preferences::glyph_grid_labels));
+
+    Flex::row()
+        .with_child(Either::new(list_mode, Flex::row(), zoom))
+        .with_flex_spacer(1.0)
+        .with_child(Checkbox::new("List").lens(Preferences::glyph_grid_list_mode))
+        .padding((8.0, 4.0))
+        .background(theme::GLYPH_LIST_BACKGROUND)
+}
@@ -1,244 +1,710 @@
 //! The top-level widget for the main glyph list window.
+//!
+//! Fonts can have many thousands of glyphs, so the grid does not have a
+//! widget for each cell; it only paints the cells that are visible, and
+//! caches each glyph's scaled outline until its path in the `BezCache`
+//! changes.
+//!
+//! For very large fonts the grid can also be shown as a list, with a row of
+//! text for each glyph.
 
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
 use std::sync::Arc;
 
-use druid::kurbo::{Affine, Rect, Shape, Size};
-//use druid::piet::{
-//FontBuilder, PietText, PietTextLayout, RenderContext, Text, TextLayout, TextLayoutBuilder,
-//};
+use druid::kurbo::{Affine, BezPath, Circle, Line, Point, Rect, Shape, Size};
+use druid::piet::PietText;
 use druid::widget::prelude::*;
-use druid::{Data, Insets, TextLayout, WidgetExt, WidgetPod};
+use druid::widget::{CrossAxisAlignment, Flex, Label};
+use druid::{Color, ContextMenu, Data, Insets, Key, MouseEvent, TextLayout, WidgetExt};
+use norad::GlyphName;
 
 use crate::app_delegate::EDIT_GLYPH;
-use crate::data::{GridGlyph, Workspace};
+use crate::data::{GridFilterCache, GridGlyph, Workspace};
+use crate::glyph_filter::GridSort;
+use crate::menus;
+use crate::preferences::{GLYPH_GRID_CELL_SIZE, GLYPH_GRID_LABELS, GLYPH_GRID_LIST_MODE};
 use crate::theme;
-use crate::widgets::Maybe;
 
-const GLYPH_SIZE: f64 = 128.;
+/// The height of the labels under each cell, when they are shown.
+const LABEL_HEIGHT: f64 = 34.0;
+/// The height of each row in list mode.
+const LIST_ROW_HEIGHT: f64 = 24.0;
+/// In list mode, the x position of the name column, leaving room for the
+/// mark color.
+const LIST_NAME_X: f64 = 24.0;
+/// In list mode, the width of each of the columns after the name.
+const LIST_COLUMN_WIDTH: f64 = 90.0;
 
 #[derive(Default)]
 pub struct GlyphGrid {
-    children: Vec<WidgetPod<Workspace, Box<dyn Widget<Workspace>>>>,
+    /// The glyphs shown, in order.
+    names: Vec<GlyphName>,
+    filter_cache: GridFilterCache,
+    /// The index of each glyph in `names`.
+    indices: HashMap<GlyphName, usize>,
+    geometry: GridGeometry,
+    thumbnails: ThumbnailCache,
+    labels: HashMap<GlyphName, CellText>,
+    show_labels: bool,
+    list_mode: bool,
+    drag: Option<DragSelection>,
+    /// A click on an already selected glyph; if the mouse is released without
+    /// dragging, only that glyph is selected.
+    pending_click: Option<GlyphName>,
+    /// While dragging selected glyphs to reorder them, the index in `names`
+    /// they will be moved to.
+    reorder: Option<usize>,
+}
+
+/// The position of each cell in the grid.
+///
+/// In list mode there is a single column, and each cell is a row.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GridGeometry {
+    pub cell: Size,
+    pub columns: usize,
+    pub count: usize,
+}
+
+/// Scaled glyph outlines, ready to be drawn in a grid cell.
+#[derive(Debug, Default)]
+pub struct ThumbnailCache {
+    cell_size: f64,
+    upm: f64,
+    entries: HashMap<GlyphName, Thumbnail>,
+}
+
+#[derive(Debug)]
+struct Thumbnail {
+    /// The path this was built from, or `None` for the placeholder.
+    source: Option<Arc<BezPath>>,
+    /// The outline, positioned in a cell whose origin is (0, 0).
+    path: BezPath,
+}
+
+/// The text drawn in a cell, built the first time the cell is painted.
+struct CellText {
+    name: TextLayout<Arc<str>>,
+    unicode: TextLayout<Arc<str>>,
+    advance: TextLayout<Arc<str>>,
+    components: TextLayout<Arc<str>>,
+}
+
+/// An in-progress rubber band selection.
+struct DragSelection {
+    start: Point,
+    current: Point,
+    /// The selection before the drag started, which the drag adds to.
+    previous: Arc<BTreeSet<GlyphName>>,
 }
 
 impl GlyphGrid {
-    fn update_children(&mut self, data: &Workspace) {
-        self.children.clear();
-        for key in data.font.ufo.iter_names() {
-            let widget = Maybe::or_empty(GridInner::new);
-            self.children.push(WidgetPod::new(
-                widget.lens(Workspace::glyph_grid(key)).boxed(),
-            ));
+    pub fn new() -> GlyphGrid {
+        Default::default()
+    }
+
+    fn update_names(&mut self, data: &Workspace) {
+        self.names = data.filtered_glyph_names_cached(&mut self.filter_cache);
+        self.indices = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.clone(), idx))
+            .collect();
+        // labels show the advance and unicode, which may have changed
+        self.labels.clear();
+        self.geometry.count = self.names.len();
+    }
+
+    /// Request a repaint of the cell showing `name`, if it is in the grid.
+    fn invalidate_cell(&self, ctx: &mut UpdateCtx, name: &GlyphName) {
+        if let Some(idx) = self.indices.get(name) {
+            ctx.request_paint_rect(self.geometry.cell_rect(*idx));
         }
     }
-}
 
-impl Widget<Workspace> for GlyphGrid {
-    fn paint(&mut self, ctx: &mut PaintCtx, data: &Workspace, env: &Env) {
-        ctx.render_ctx.clear(env.get(theme::GLYPH_LIST_BACKGROUND));
-        let row_len = 1.0_f64.max(ctx.size().width / GLYPH_SIZE).floor() as usize;
-        let _row_count = if self.children.is_empty() {
-            0
+    fn mouse_down(&mut self, ctx: &mut EventCtx, m: &MouseEvent, data: &mut Workspace) {
+        let name = self
+            .geometry
+            .index_at(m.pos)
+            .map(|idx| self.names[idx].clone());
+        let toggle = m.mods.meta() || m.mods.ctrl();
+        match name {
+            Some(name) if m.count == 2 => {
+                ctx.submit_command(EDIT_GLYPH.with(name));
+            }
+            Some(name) if m.mods.shift() && data.selected.is_some() => {
+                self.select_range(data, &name);
+            }
+            Some(name) if toggle => {
+                let selection = Arc::make_mut(&mut data.selection);
+                if selection.remove(&name) {
+                    // don't leave a deselected glyph as the primary selection
+                    if data.selected.as_ref() == Some(&name) {
+                        data.selected = selection.iter().next().cloned();
+                    }
+                } else {
+                    selection.insert(name.clone());
+                    data.selected = Some(name);
+                }
+            }
+            Some(name) if data.selection.contains(&name) => {
+                data.selected = Some(name.clone());
+                self.pending_click = Some(name);
+            }
+            Some(name) => {
+                data.select_only(Some(name));
+                self.start_drag(m.pos, Default::default());
+            }
+            None => {
+                if !(toggle || m.mods.shift()) {
+                    data.select_only(None);
+                }
+                self.start_drag(m.pos, data.selection.clone());
+            }
+        }
+    }
+
+    /// Where selected glyphs dropped at `pos` should be inserted.
+    fn insertion_index(&self, pos: Point) -> Option<usize> {
+        let idx = self.geometry.index_at(pos)?;
+        let rect = self.geometry.cell_rect(idx);
+        let after = if self.list_mode {
+            pos.y > rect.center().y
         } else {
-            self.children.len() / row_len + 1
+            pos.x > rect.center().x
         };
+        Some(if after { idx + 1 } else { idx })
+    }
 
-        for child in &mut self.children {
-            child.paint(ctx, data, env);
-        }
+    /// Move the selected glyphs to `idx`, updating the glyph order.
+    fn finish_reorder(&self, idx: usize, data: &mut Workspace) {
+        let before = self.names[idx..]
+            .iter()
+            .find(|name| !data.selection.contains(*name))
+            .cloned();
+        let selected = data.selected_glyphs();
+        data.move_glyphs(&selected, before.as_ref());
     }
 
-    fn layout(
-        &mut self,
-        ctx: &mut LayoutCtx,
-        bc: &BoxConstraints,
-        data: &Workspace,
-        env: &Env,
-    ) -> Size {
-        let width = (bc.max().width / GLYPH_SIZE).floor() * GLYPH_SIZE;
-        let mut x: f64 = 0.;
-        let mut y: f64 = 0.;
+    /// Select every glyph between the last selected glyph and `name`.
+    fn select_range(&self, data: &mut Workspace, name: &GlyphName) {
+        let anchor = match data.selected.as_ref().and_then(|n| self.indices.get(n)) {
+            Some(idx) => *idx,
+            None => return data.select_only(Some(name.clone())),
+        };
+        let idx = self.indices.get(name).copied().unwrap_or(anchor);
+        let range = anchor.min(idx)..=anchor.max(idx);
+        data.selection = Arc::new(self.names[range].iter().cloned().collect());
+    }
 
-        let child_bc = BoxConstraints::tight(Size::new(GLYPH_SIZE, GLYPH_SIZE));
+    fn start_drag(&mut self, pos: Point, previous: Arc<BTreeSet<GlyphName>>) {
+        self.drag = Some(DragSelection {
+            start: pos,
+            current: pos,
+            previous,
+        });
+    }
 
-        for child in &mut self.children {
-            if x > 0. && x + GLYPH_SIZE > width {
-                y += GLYPH_SIZE;
-                x = 0.;
-            }
-            child.layout(ctx, &child_bc, data, env);
-            let rect = Rect::from_origin_size((x, y), (GLYPH_SIZE, GLYPH_SIZE));
-            child.set_layout_rect(ctx, data, env, rect);
-            x += GLYPH_SIZE;
+    fn update_drag(&mut self, pos: Point, data: &mut Workspace) {
+        let drag = match self.drag.as_mut() {
+            Some(drag) => drag,
+            None => return,
+        };
+        drag.current = pos;
+        let mut selection = (*drag.previous).clone();
+        for idx in self.geometry.cells_in(drag.rect()) {
+            selection.insert(self.names[idx].clone());
+        }
+        if selection != *data.selection {
+            data.selection = Arc::new(selection);
         }
-        Size::new(width, y + GLYPH_SIZE)
     }
 
-    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Workspace, env: &Env) {
-        for child in &mut self.children {
-            child.event(ctx, event, data, env);
-        }
+    fn cell_text(&mut self, glyph: &GridGlyph, text: &mut PietText, env: &Env) -> &CellText {
+        let cell_text = self
+            .labels
+            .entry(glyph.name.clone())
+            .or_insert_with(|| CellText::new(glyph));
+        cell_text.rebuild_if_needed(text, env);
+        cell_text
     }
 
-    fn lifecycle(
-        &mut self,
-        ctx: &mut LifeCycleCtx,
-        event: &LifeCycle,
-        data: &Workspace,
-        env: &Env,
-    ) {
-        if let LifeCycle::WidgetAdded = event {
-            if self.children.is_empty() {
-                self.update_children(data);
-            }
+    fn paint_cell(&mut self, ctx: &mut PaintCtx, idx: usize, data: &Workspace, env: &Env) {
+        let name = &self.names[idx];
+        let glyph = data.grid_glyph(name);
+        let cell_size = self.geometry.cell.width;
+        let geom = Size::new(cell_size, cell_size).to_rect();
+
+        let glyph_rect: Rect = geom - Insets::uniform(5.0);
+        let rounded = glyph_rect.to_rounded_rect(5.0);
+        ctx.fill(rounded, &env.get(theme::GLYPH_GRID_CELL_BACKGROUND_COLOR));
+        ctx.stroke(rounded, &env.get(theme::GLYPH_GRID_CELL_OUTLINE_COLOR), 2.0);
+        if let Some(color) = glyph.mark_color.as_ref() {
+            ctx.fill(rounded, &color.clone().with_alpha(0.4));
+        }
+        if glyph.is_selected {
+            ctx.fill(rounded, &env.get(theme::FOCUS_BACKGROUND_COLOR));
+            ctx.stroke(rounded, &env.get(theme::FOCUS_OUTLINE_COLOR), 4.0);
+        }
+        let glyph_color = if glyph.is_placeholder {
+            env.get(theme::PLACEHOLDER_GLYPH_COLOR)
+        } else {
+            env.get(theme::PRIMARY_TEXT_COLOR)
+        };
+
+        ctx.fill(self.thumbnails.get(data, name), &glyph_color);
+
+        if glyph.is_modified {
+            let center = (glyph_rect.x1 - 10.0, glyph_rect.y0 + 10.0);
+            let dot = Circle::new(center, 4.0);
+            ctx.fill(dot, &env.get(theme::MODIFIED_MARKER_COLOR));
         }
 
-        for child in &mut self.children {
-            child.lifecycle(ctx, event, data, env);
+        let show_labels = self.show_labels;
+        let text = self.cell_text(&glyph, ctx.text(), env);
+        let name_size = text.name.size();
+        let xpos = geom.x0 + (geom.width() - name_size.width) / 2.0;
+        if show_labels {
+            // the name, and then the unicode and advance, under the cell
+            let ypos = geom.max_y();
+            text.name.draw(ctx, (xpos, ypos));
+            let ypos = ypos + name_size.height;
+            text.unicode.draw(ctx, (glyph_rect.x0, ypos));
+            let advance_x = glyph_rect.x1 - text.advance.size().width;
+            text.advance.draw(ctx, (advance_x, ypos));
+        } else {
+            let ypos = geom.max_y() - name_size.height;
+            text.name.draw(ctx, (xpos, ypos - 8.0));
         }
     }
 
-    fn update(&mut self, ctx: &mut UpdateCtx, old: &Workspace, new: &Workspace, env: &Env) {
-        //eprintln!("grid update generation {}, {}", old.cache.generation.get(), new.cache.generation.get());
-        if !old.font.same(&new.font) {
-            //eprintln!("old font changed");
-            self.update_children(new);
-            ctx.children_changed();
-            ctx.request_paint();
-        } else {
-            //eprintln!("cache same {}", old.cache.same(&new.cache));
-            for child in &mut self.children {
-                child.update(ctx, new, env);
-            }
+    fn paint_row(&mut self, ctx: &mut PaintCtx, idx: usize, data: &Workspace, env: &Env) {
+        let glyph = data.grid_glyph(&self.names[idx]);
+        let rect = self.geometry.cell.to_rect();
+        if glyph.is_selected {
+            ctx.fill(rect, &env.get(theme::FOCUS_BACKGROUND_COLOR));
+        } else if idx % 2 == 1 {
+            ctx.fill(rect, &env.get(theme::GLYPH_GRID_CELL_BACKGROUND_COLOR));
+        }
+        if let Some(color) = glyph.mark_color.as_ref() {
+            let swatch = Rect::from_center_size((LIST_NAME_X / 2.0, rect.center().y), (12.0, 12.0));
+            ctx.fill(swatch.to_rounded_rect(2.0), color);
+        }
+        if glyph.is_modified {
+            let dot = Circle::new((rect.x1 - 12.0, rect.center().y), 4.0);
+            ctx.fill(dot, &env.get(theme::MODIFIED_MARKER_COLOR));
         }
+
+        let columns = list_columns(rect.width());
+        let text = self.cell_text(&glyph, ctx.text(), env);
+        let ypos = (rect.height() - text.name.size().height) / 2.0;
+        ctx.with_save(|ctx| {
+            ctx.clip(Rect::new(0.0, 0.0, columns[0] - 8.0, rect.height()));
+            text.name.draw(ctx, (LIST_NAME_X, ypos));
+        });
+        text.unicode.draw(ctx, (columns[0], ypos));
+        text.advance.draw(ctx, (columns[1], ypos));
+        text.components.draw(ctx, (columns[2], ypos));
     }
 }
 
-impl GlyphGrid {
-    pub fn new() -> GlyphGrid {
-        Default::default()
+/// In list mode, the x positions of the unicode, advance and component
+/// columns, in a grid of the given width.
+fn list_columns(width: f64) -> [f64; 3] {
+    let last = (width - LIST_COLUMN_WIDTH).max(LIST_NAME_X + 3.0 * LIST_COLUMN_WIDTH);
+    [
+        last - 2.0 * LIST_COLUMN_WIDTH,
+        last - LIST_COLUMN_WIDTH,
+        last,
+    ]
+}
+
+/// The titles of the columns in list mode, to be shown above the grid.
+pub(crate) fn list_header<T: Data>() -> impl Widget<T> {
+    let title = |text: &str| {
+        Label::new(text)
+            .with_font(theme::UI_DETAIL_FONT)
+            .with_text_color(theme::SECONDARY_TEXT_COLOR)
+    };
+    Flex::row()
+        .with_spacer(LIST_NAME_X)
+        .with_flex_child(title("Name").expand_width(), 1.0)
+        .with_child(title("Unicode").fix_width(LIST_COLUMN_WIDTH))
+        .with_child(title("Advance").fix_width(LIST_COLUMN_WIDTH))
+        .with_child(title("Components").fix_width(LIST_COLUMN_WIDTH))
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .fix_height(LIST_ROW_HEIGHT)
+        .background(theme::GLYPH_LIST_BACKGROUND)
+}
+
+impl CellText {
+    fn new(glyph: &GridGlyph) -> Self {
+        let unicode = glyph
+            .codepoint
+            .map(|c| format!("U+{:04X}", c as u32))
+            .unwrap_or_default();
+        let components = match glyph.component_count {
+            0 => String::new(),
+            n => n.to_string(),
+        };
+        CellText {
+            name: text_layout(glyph.name.clone(), theme::PRIMARY_TEXT_COLOR),
+            unicode: text_layout(unicode, theme::SECONDARY_TEXT_COLOR),
+            advance: text_layout(
+                glyph.advance.round().to_string(),
+                theme::SECONDARY_TEXT_COLOR,
+            ),
+            components: text_layout(components, theme::SECONDARY_TEXT_COLOR),
+        }
+    }
+
+    fn layouts(&mut self) -> [&mut TextLayout<Arc<str>>; 4] {
+        [
+            &mut self.name,
+            &mut self.unicode,
+            &mut self.advance,
+            &mut self.components,
+        ]
+    }
+
+    fn rebuild_if_needed(&mut self, text: &mut PietText, env: &Env) {
+        for layout in self.layouts().iter_mut() {
+            layout.rebuild_if_needed(text, env);
+        }
+    }
+
+    fn needs_rebuild_after_update(&mut self, ctx: &mut UpdateCtx) -> bool {
+        // every layout needs to see the update, so this doesn't short-circuit
+        self.layouts().iter_mut().fold(false, |needs, layout| {
+            layout.needs_rebuild_after_update(ctx) | needs
+        })
     }
 }
 
-#[derive(Debug, Clone)]
-struct GridInner {
-    text: TextLayout<Arc<str>>,
+fn text_layout(text: impl Into<Arc<str>>, color: Key<Color>) -> TextLayout<Arc<str>> {
+    let mut layout = TextLayout::new();
+    layout.set_text(text.into());
+    layout.set_font(theme::UI_DETAIL_FONT);
+    layout.set_text_color(color);
+    layout
 }
 
-impl GridInner {
-    fn new() -> Self {
-        GridInner {
-            text: TextLayout::new(),
+impl GridGeometry {
+    pub fn new(width: f64, cell: Size, count: usize) -> Self {
+        let columns = ((width / cell.width).floor() as usize).max(1);
+        GridGeometry {
+            cell,
+            columns,
+            count,
         }
     }
+
+    /// The number of rows; an empty grid still has one.
+    pub fn rows(&self) -> usize {
+        self.count.saturating_sub(1) / self.columns.max(1) + 1
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(
+            self.columns as f64 * self.cell.width,
+            self.rows() as f64 * self.cell.height,
+        )
+    }
+
+    pub fn cell_rect(&self, idx: usize) -> Rect {
+        // before the first layout there are no columns
+        let columns = self.columns.max(1);
+        let x = (idx % columns) as f64 * self.cell.width;
+        let y = (idx / columns) as f64 * self.cell.height;
+        Rect::from_origin_size((x, y), self.cell)
+    }
+
+    /// The index of the cell at `pos`, if any.
+    pub fn index_at(&self, pos: Point) -> Option<usize> {
+        if pos.x < 0.0 || pos.y < 0.0 {
+            return None;
+        }
+        let col = (pos.x / self.cell.width) as usize;
+        let row = (pos.y / self.cell.height) as usize;
+        Some(row * self.columns + col).filter(|idx| col < self.columns && *idx < self.count)
+    }
+
+    /// The indices of the cells in the rows that intersect `rect`.
+    pub fn visible_range(&self, rect: Rect) -> Range<usize> {
+        let first_row = (rect.y0 / self.cell.height).floor().max(0.0) as usize;
+        let last_row = (rect.y1 / self.cell.height).ceil().max(0.0) as usize;
+        let start = (first_row * self.columns).min(self.count);
+        let end = (last_row * self.columns).min(self.count);
+        start..end.max(start)
+    }
+
+    /// The indices of the cells that touch `rect`, including those that only
+    /// share an edge with it.
+    pub fn cells_in(&self, rect: Rect) -> impl Iterator<Item = usize> + '_ {
+        let first_col = (rect.x0 / self.cell.width).floor().max(0.0) as usize;
+        let last_col = ((rect.x1 / self.cell.width).floor().max(0.0) as usize)
+            .min(self.columns.saturating_sub(1));
+        let first_row = (rect.y0 / self.cell.height).floor().max(0.0) as usize;
+        let last_row = (rect.y1 / self.cell.height).floor().max(0.0) as usize;
+        (first_row..=last_row)
+            .flat_map(move |row| (first_col..=last_col).map(move |col| row * self.columns + col))
+            .filter(move |idx| *idx < self.count)
+    }
 }
 
-impl Widget<GridGlyph> for GridInner {
-    fn paint(&mut self, ctx: &mut PaintCtx, data: &GridGlyph, env: &Env) {
-        let path = data.outline.clone();
-        let bb = path.bounding_box();
-        let geom = ctx.size().to_rect();
-        let scale = geom.height() as f64 / data.upm;
-        let scale = scale * 0.75; // some margins around glyphs
-        let scaled_width = bb.width() * scale as f64;
-        let l_pad = ((geom.width() as f64 - scaled_width) / 2.).round();
-        let baseline = (geom.height() * 0.29) as f64;
-        let affine = Affine::new([
-            scale as f64,
-            0.0,
-            0.0,
-            -scale as f64,
-            l_pad,
-            geom.height() - baseline,
-        ]);
+impl ThumbnailCache {
+    pub fn new() -> Self {
+        Default::default()
+    }
 
-        let glyph_rect: Rect = geom - Insets::uniform(5.0);
-        let rounded = glyph_rect.to_rounded_rect(5.0);
-        ctx.fill(rounded, &env.get(theme::GLYPH_GRID_CELL_BACKGROUND_COLOR));
-        ctx.stroke(rounded, &env.get(theme::GLYPH_GRID_CELL_OUTLINE_COLOR), 2.0);
-        if ctx.is_active() || data.is_selected {
-            ctx.fill(rounded, &env.get(theme::FOCUS_BACKGROUND_COLOR));
-            ctx.stroke(rounded, &env.get(theme::FOCUS_OUTLINE_COLOR), 4.0);
+    /// Set the size of the cells and the font's units per em, discarding
+    /// every thumbnail if either has changed.
+    #[allow(clippy::float_cmp)]
+    pub fn set_scale(&mut self, cell_size: f64, upm: f64) {
+        if cell_size != self.cell_size || upm != self.upm {
+            self.cell_size = cell_size;
+            self.upm = upm;
+            self.entries.clear();
         }
-        let glyph_color = if data.is_placeholder {
-            env.get(theme::PLACEHOLDER_GLYPH_COLOR)
-        } else {
-            env.get(theme::PRIMARY_TEXT_COLOR)
+    }
+
+    /// The outline of the named glyph, scaled and positioned in a cell.
+    ///
+    /// This is only rebuilt if the glyph's path in the `BezCache` has
+    /// changed since it was last drawn.
+    pub fn get(&mut self, data: &Workspace, name: &GlyphName) -> &BezPath {
+        let source = data.get_bezier(name);
+        let stale = match self.entries.get(name) {
+            Some(thumb) => !same_source(&thumb.source, &source),
+            None => true,
         };
+        if stale {
+            let outline = source
+                .clone()
+                .unwrap_or_else(|| data.font.placeholder().clone());
+            let path = self.affine(&outline) * &*outline;
+            self.entries
+                .insert(name.clone(), Thumbnail { source, path });
+        }
+        &self.entries[name].path
+    }
+
+    /// Discard the thumbnails of glyphs whose paths have changed or which
+    /// have been removed, returning their names.
+    pub fn remove_stale(&mut self, data: &Workspace) -> Vec<GlyphName> {
+        let stale: Vec<GlyphName> = self
+            .entries
+            .iter()
+            .filter(|(name, thumb)| !same_source(&thumb.source, &data.get_bezier(name)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in &stale {
+            self.entries.remove(name);
+        }
+        stale
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 
-        ctx.render_ctx.fill(affine * &*path, &glyph_color);
+    fn affine(&self, outline: &BezPath) -> Affine {
+        let bb = outline.bounding_box();
+        let scale = self.cell_size / self.upm;
+        let scale = scale * 0.75; // some margins around glyphs
+        let scaled_width = bb.width() * scale;
+        let l_pad = ((self.cell_size - scaled_width) / 2.).round();
+        let baseline = self.cell_size * 0.29;
+        Affine::new([scale, 0.0, 0.0, -scale, l_pad, self.cell_size - baseline])
+    }
+}
 
-        let text_size = self.text.size();
+/// Paths in the `BezCache` are replaced, never mutated, so a changed path
+/// is a different `Arc`.
+fn same_source(a: &Option<Arc<BezPath>>, b: &Option<Arc<BezPath>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
 
-        let xpos = geom.x0 + (geom.width() - text_size.width) / 2.0;
-        let ypos = geom.max_y() - text_size.height;
+impl DragSelection {
+    fn rect(&self) -> Rect {
+        Rect::from_points(self.start, self.current)
+    }
+}
+
+impl Widget<Workspace> for GlyphGrid {
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Workspace, env: &Env) {
+        let region = ctx.region().bounding_box();
+        ctx.fill(region, &env.get(theme::GLYPH_LIST_BACKGROUND));
+        self.thumbnails
+            .set_scale(self.geometry.cell.width, data.units_per_em());
 
-        self.text.draw(ctx, (xpos, ypos - 8.0));
+        for idx in self.geometry.visible_range(region) {
+            let origin = self.geometry.cell_rect(idx).origin();
+            ctx.with_save(|ctx| {
+                ctx.transform(Affine::translate(origin.to_vec2()));
+                ctx.clip(self.geometry.cell.to_rect());
+                if self.list_mode {
+                    self.paint_row(ctx, idx, data, env);
+                } else {
+                    self.paint_cell(ctx, idx, data, env);
+                }
+            });
+        }
+
+        if let Some(drag) = self.drag.as_ref() {
+            let rect = drag.rect();
+            ctx.fill(rect, &env.get(theme::FOCUS_BACKGROUND_COLOR));
+            ctx.stroke(rect, &env.get(theme::FOCUS_OUTLINE_COLOR), 1.0);
+        }
+
+        // a line where dragged glyphs will be dropped
+        let marker = self.reorder.and_then(|idx| {
+            if idx < self.names.len() {
+                Some((self.geometry.cell_rect(idx), false))
+            } else {
+                self.names
+                    .len()
+                    .checked_sub(1)
+                    .map(|last| (self.geometry.cell_rect(last), true))
+            }
+        });
+        if let Some((rect, after)) = marker {
+            let line = match (self.list_mode, after) {
+                (true, false) => Line::new((rect.x0, rect.y0), (rect.x1, rect.y0)),
+                (true, true) => Line::new((rect.x0, rect.y1), (rect.x1, rect.y1)),
+                (false, false) => Line::new((rect.x0, rect.y0), (rect.x0, rect.y1)),
+                (false, true) => Line::new((rect.x1, rect.y0), (rect.x1, rect.y1)),
+            };
+            ctx.stroke(line, &env.get(theme::FOCUS_OUTLINE_COLOR), 3.0);
+        }
     }
 
     fn layout(
         &mut self,
-        ctx: &mut LayoutCtx,
+        _ctx: &mut LayoutCtx,
         bc: &BoxConstraints,
-        _: &GridGlyph,
+        _data: &Workspace,
         env: &Env,
     ) -> Size {
-        self.text.rebuild_if_needed(ctx.text(), env);
-        bc.max()
+        let width = bc.max().width;
+        self.show_labels = env.get(GLYPH_GRID_LABELS);
+        self.list_mode = env.get(GLYPH_GRID_LIST_MODE);
+        let cell = if self.list_mode {
+            Size::new(width, LIST_ROW_HEIGHT)
+        } else {
+            let cell_size = env.get(GLYPH_GRID_CELL_SIZE);
+            let label_height = if self.show_labels { LABEL_HEIGHT } else { 0.0 };
+            Size::new(cell_size, cell_size + label_height)
+        };
+        self.geometry = GridGeometry::new(width, cell, self.names.len());
+        self.geometry.size()
     }
 
-    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut GridGlyph, _env: &Env) {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Workspace, _env: &Env) {
         match event {
+            Event::MouseDown(m) if m.button.is_right() => {
+                // the menu acts on the selection, so include the clicked glyph
+                if let Some(idx) = self.geometry.index_at(m.pos) {
+                    let name = &self.names[idx];
+                    if !data.selection.contains(name) {
+                        data.select_only(Some(name.clone()));
+                    }
+                }
+                if !data.selection.is_empty() {
+                    let menu = menus::make_grid_context_menu(data);
+                    ctx.show_context_menu(ContextMenu::new(menu, m.window_pos));
+                }
+                ctx.request_paint();
+            }
             Event::MouseDown(m) => {
                 ctx.set_active(true);
+                self.mouse_down(ctx, m, data);
                 ctx.request_paint();
-                if m.count == 1 {
-                    data.is_selected = true;
-                } else if m.count == 2 {
-                    ctx.submit_command(EDIT_GLYPH.with(data.name.clone()));
-                }
             }
-            Event::MouseUp(_) => {
-                if ctx.is_active() {
-                    ctx.set_active(false);
+            Event::MouseMove(m) if ctx.is_active() => {
+                if self.pending_click.is_some() || self.reorder.is_some() {
+                    // dragging selected glyphs keeps the selection, and moves
+                    // them if the grid is in glyph order.
+                    self.pending_click = None;
+                    if data.grid_sort == GridSort::GlyphOrder {
+                        if let Some(idx) = self.insertion_index(m.pos) {
+                            self.reorder = Some(idx);
+                            ctx.request_paint();
+                        }
+                    }
+                } else if self.drag.is_some() {
+                    self.update_drag(m.pos, data);
                     ctx.request_paint();
                 }
             }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                if let Some(name) = self.pending_click.take() {
+                    data.select_only(Some(name));
+                }
+                if let Some(idx) = self.reorder.take() {
+                    self.finish_reorder(idx, data);
+                }
+                self.drag = None;
+                ctx.request_paint();
+            }
             _ => (),
         }
     }
 
     fn lifecycle(
         &mut self,
-        ctx: &mut LifeCycleCtx,
+        _ctx: &mut LifeCycleCtx,
         event: &LifeCycle,
-        data: &GridGlyph,
-        env: &Env,
+        data: &Workspace,
+        _env: &Env,
     ) {
-        match event {
-            LifeCycle::HotChanged(_) => ctx.request_paint(),
-            LifeCycle::WidgetAdded => {
-                self.text.set_text(data.name.clone());
-                self.text.set_font(theme::UI_DETAIL_FONT);
-                self.text.set_text_color(theme::PRIMARY_TEXT_COLOR);
-                self.text.rebuild_if_needed(ctx.text(), env);
-            }
-            _ => (),
+        if let LifeCycle::WidgetAdded = event {
+            self.update_names(data);
         }
     }
 
-    fn update(&mut self, ctx: &mut UpdateCtx, old: &GridGlyph, new: &GridGlyph, _env: &Env) {
-        if !old.same(new) {
+    fn update(&mut self, ctx: &mut UpdateCtx, old: &Workspace, new: &Workspace, _env: &Env) {
+        if !old.font.same(&new.font)
+            || !old.grid_filter.same(&new.grid_filter)
+            || old.grid_sort != new.grid_sort
+        {
+            self.update_names(new);
+            ctx.request_layout();
             ctx.request_paint();
-            // I don't know if our name can change? but if it can we need to rebuild our text
-            // object.
-            if !old.name.same(&new.name) {
-                self.text.set_text(new.name.clone());
-                ctx.request_layout();
+        } else if !old.same(new) {
+            // only repaint the cells whose outlines or selection changed.
+            for name in self.thumbnails.remove_stale(new) {
+                self.labels.remove(&name);
+                self.invalidate_cell(ctx, &name);
+            }
+            if !old.selection.same(&new.selection) {
+                for name in old.selection.symmetric_difference(&new.selection) {
+                    self.invalidate_cell(ctx, name);
+                }
             }
         }
-        if self.text.needs_rebuild_after_update(ctx) {
+        for text in self.labels.values_mut() {
+            if text.needs_rebuild_after_update(ctx) {
+                ctx.request_paint();
+            }
+        }
+        if ctx.env_key_changed(&GLYPH_GRID_CELL_SIZE)
+            || ctx.env_key_changed(&GLYPH_GRID_LABELS)
+            || ctx.env_key_changed(&GLYPH_GRID_LIST_MODE)
+        {
             ctx.request_layout();
+            ctx.request_paint();
         }
     }
 }
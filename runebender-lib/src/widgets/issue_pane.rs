@@ -0,0 +1,144 @@
+//! The floating panel that lists problems found in the glyph's outlines.
+
+use std::sync::Arc;
+
+use druid::kurbo::Circle;
+use druid::widget::prelude::*;
+use druid::{Point, TextLayout};
+
+use crate::data::EditorState;
+use crate::qa::Issue;
+use crate::theme;
+
+const ROW_HEIGHT: f64 = 18.0;
+const PANEL_WIDTH: f64 = 200.0;
+const PADDING: f64 = 8.0;
+/// The maximum number of rows we show; any more are summarized.
+const MAX_ROWS: usize = 12;
+
+/// A list of outline problems; clicking one selects the points involved.
+#[derive(Default)]
+pub struct IssuePane {
+    issues: Arc<Vec<Issue>>,
+    rows: Vec<TextLayout<String>>,
+}
+
+impl IssuePane {
+    pub fn new() -> Self {
+        IssuePane::default()
+    }
+
+    /// Recheck the outlines, returning `true` if the issues have changed.
+    fn refresh(&mut self, data: &EditorState) -> bool {
+        let issues = data.issues();
+        if Arc::ptr_eq(&issues, &self.issues) {
+            return false;
+        }
+
+        let mut rows: Vec<_> = issues
+            .iter()
+            .take(MAX_ROWS)
+            .map(|issue| make_row(issue.kind.description().to_string()))
+            .collect();
+        if issues.len() > MAX_ROWS {
+            rows.push(make_row(format!("…and {} more", issues.len() - MAX_ROWS)));
+        }
+        self.rows = rows;
+        self.issues = issues;
+        true
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        let idx = ((pos.y - PADDING) / ROW_HEIGHT).floor();
+        if idx < 0.0 {
+            return None;
+        }
+        Some(idx as usize).filter(|idx| *idx < self.issues.len().min(MAX_ROWS))
+    }
+}
+
+fn make_row(text: String) -> TextLayout<String> {
+    let mut layout = TextLayout::from_text(text);
+    layout.set_font(theme::UI_DETAIL_FONT);
+    layout.set_text_color(theme::PRIMARY_TEXT_COLOR);
+    layout
+}
+
+impl Widget<EditorState> for IssuePane {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut EditorState, _env: &Env) {
+        if self.issues.is_empty() {
+            return;
+        }
+        match event {
+            Event::MouseDown(m) => {
+                if let Some(idx) = self.row_at(m.pos) {
+                    let issue = &self.issues[idx];
+                    let session = data.session_mut();
+                    session.selection.clear();
+                    for id in issue.points.iter() {
+                        session.selection.insert(*id);
+                    }
+                }
+                // suppress clicks so that the editor doesn't handle them.
+                ctx.set_handled();
+            }
+            Event::MouseUp(_) => ctx.set_handled(),
+            _ => (),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &EditorState,
+        _env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.refresh(data);
+            ctx.request_layout();
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &EditorState,
+        data: &EditorState,
+        _env: &Env,
+    ) {
+        if !old_data.session.paths.same(&data.session.paths) && self.refresh(data) {
+            ctx.request_layout();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &EditorState,
+        env: &Env,
+    ) -> Size {
+        if self.rows.is_empty() {
+            return Size::ZERO;
+        }
+        for row in self.rows.iter_mut() {
+            row.rebuild_if_needed(ctx.text(), env);
+        }
+        let height = self.rows.len() as f64 * ROW_HEIGHT + PADDING * 2.0;
+        bc.constrain(Size::new(PANEL_WIDTH, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &EditorState, env: &Env) {
+        let marker = env.get(theme::QA_MARKER_COLOR);
+        for (i, row) in self.rows.iter().enumerate() {
+            let y = PADDING + i as f64 * ROW_HEIGHT;
+            if i < self.issues.len().min(MAX_ROWS) {
+                let dot = Circle::new((PADDING + 4.0, y + ROW_HEIGHT / 2.0), 4.0);
+                ctx.fill(dot, &marker);
+            }
+            let text_y = y + (ROW_HEIGHT - row.size().height) / 2.0;
+            row.draw(ctx, (PADDING * 2.0 + 8.0, text_y));
+        }
+    }
+}
@@ -1,29 +1,55 @@
 //! Druid `Widget`s.
 
+mod add_glyphs;
+mod batch_edit;
+mod component_picker;
 mod controller;
 mod coord_pane;
+mod coverage;
+mod delete_glyph;
+mod duplicate_glyphs;
 mod editable_label;
 mod editor;
+mod font_watcher;
+mod font_window;
 mod fontinfo;
 mod glyph;
 mod glyph_pane;
 mod grid;
+mod issue_pane;
 mod maybe;
 mod modal_host;
+mod preferences;
+mod qa_report;
 mod scroll_zoom;
 mod sidebar;
+mod status_bar;
 mod toolbar;
+mod unsaved_changes;
 
+pub use add_glyphs::add_glyphs_panel;
+pub use batch_edit::batch_edit_panel;
+pub use component_picker::component_picker;
 pub use controller::{EditorController, RootWindowController};
 pub use coord_pane::CoordPane;
+pub use coverage::coverage_view;
+pub use delete_glyph::delete_glyph_prompt;
+pub use duplicate_glyphs::duplicate_glyphs_panel;
 pub use editable_label::EditableLabel;
 pub use editor::Editor;
+pub use font_watcher::FontWatcher;
+pub use font_window::font_window;
 pub use fontinfo::font_info;
 pub use glyph::GlyphPainter;
 pub use glyph_pane::GlyphPane;
-pub use grid::GlyphGrid;
+pub use grid::{GlyphGrid, GridGeometry, ThumbnailCache};
+pub use issue_pane::IssuePane;
 use maybe::Maybe;
 pub use modal_host::ModalHost;
+pub use preferences::preferences_window;
+pub use qa_report::qa_report;
 pub use scroll_zoom::ScrollZoom;
 pub use sidebar::Sidebar;
+pub use status_bar::status_bar;
 pub use toolbar::{FloatingPanel, Toolbar};
+pub use unsaved_changes::unsaved_changes_prompt;
@@ -0,0 +1,165 @@
+//! The preferences window.
+
+use std::time::Duration;
+
+use druid::widget::prelude::*;
+use druid::widget::{Checkbox, Controller, CrossAxisAlignment, Flex, Label, RadioGroup};
+use druid::{Data, Lens, LensExt, TimerToken, WidgetExt};
+
+use crate::consts::cmd::NOTIFY;
+use crate::data::{AppState, Notification};
+use crate::preferences::{Preferences, Theme, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH};
+use crate::theme;
+use crate::widgets::EditableLabel;
+
+/// How long the preferences must go unchanged before they are saved, so that
+/// dragging a slider doesn't write the file on every step.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// The tools that can be selected when an editor opens.
+const TOOLS: &[&str] = &[
+    "Select",
+    "Pen",
+    "Preview",
+    "Rectangle",
+    "Ellipse",
+    "Knife",
+    "Measure",
+];
+
+/// The contents of the preferences window.
+///
+/// Changes are saved shortly after they are made.
+pub fn preferences_window() -> impl Widget<AppState> {
+    preferences_panel()
+        .lens(AppState::preferences)
+        .controller(SavePreferences::new())
+}
+
+/// Writes the preferences to disk once they have stopped changing.
+///
+/// Changes still waiting to be saved when the window closes are saved by
+/// the app delegate.
+pub(crate) struct SavePreferences {
+    timer: TimerToken,
+}
+
+impl SavePreferences {
+    pub(crate) fn new() -> Self {
+        SavePreferences {
+            timer: TimerToken::INVALID,
+        }
+    }
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for SavePreferences {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                self.timer = TimerToken::INVALID;
+                if let Err(e) = data.preferences.save() {
+                    let notification =
+                        Notification::error("Failed to save preferences").with_details(e);
+                    ctx.submit_command(NOTIFY.with(notification));
+                }
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &AppState,
+        data: &AppState,
+        env: &Env,
+    ) {
+        if !old_data.preferences.same(&data.preferences) {
+            // restarting the timer means we only save after the last change
+            self.timer = ctx.request_timer(SAVE_DELAY);
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+fn preferences_panel() -> impl Widget<Preferences> {
+    let theme = RadioGroup::new(vec![("Light", Theme::Light), ("Dark", Theme::Dark)])
+        .lens(Preferences::theme);
+
+    let default_tool = RadioGroup::new(
+        TOOLS
+            .iter()
+            .map(|name| (*name, name.to_string()))
+            .collect::<Vec<_>>(),
+    )
+    .lens(Preferences::default_tool);
+
+    Flex::column()
+        .with_child(section_title("Theme"))
+        .with_child(theme)
+        .with_spacer(16.0)
+        .with_child(section_title("Default tool"))
+        .with_child(default_tool)
+        .with_spacer(16.0)
+        .with_child(section_title("Nudge distance"))
+        .with_child(number_row("Arrow keys:", Preferences::nudge_small))
+        .with_child(number_row("With shift:", Preferences::nudge_medium))
+        .with_child(number_row("With cmd:", Preferences::nudge_large))
+        .with_spacer(16.0)
+        .with_child(section_title("Snapping"))
+        .with_child(Checkbox::new("Snap dragged points to grid").lens(Preferences::snap_to_grid))
+        .with_child(number_row("Grid spacing:", Preferences::snap_grid_size))
+        .with_spacer(16.0)
+        .with_child(section_title("Windows"))
+        .with_child(number_row(
+            "Glyph grid cell size:",
+            Preferences::glyph_grid_cell_size,
+        ))
+        .with_child(number_row(
+            "New window width:",
+            Preferences::window_width.map(
+                |width| *width,
+                |width, new: f64| *width = new.max(MIN_WINDOW_WIDTH),
+            ),
+        ))
+        .with_child(number_row(
+            "New window height:",
+            Preferences::window_height.map(
+                |height| *height,
+                |height, new: f64| *height = new.max(MIN_WINDOW_HEIGHT),
+            ),
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .padding(16.0)
+        .expand()
+        .background(theme::GLYPH_LIST_BACKGROUND)
+}
+
+fn section_title(title: &str) -> impl Widget<Preferences> {
+    Label::new(title)
+        .with_text_color(theme::PRIMARY_TEXT_COLOR)
+        .padding((0.0, 0.0, 0.0, 4.0))
+}
+
+fn number_row(
+    title: &str,
+    lens: impl Lens<Preferences, f64> + 'static,
+) -> impl Widget<Preferences> {
+    Flex::row()
+        .with_child(
+            Label::new(title)
+                .with_text_color(theme::SECONDARY_TEXT_COLOR)
+                .fix_width(160.0),
+        )
+        .with_default_spacer()
+        .with_child(EditableLabel::parse().lens(lens).fix_width(80.0))
+}
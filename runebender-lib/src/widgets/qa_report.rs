@@ -0,0 +1,65 @@
+//! A window showing the QA report for a font.
+
+use druid::widget::prelude::*;
+use druid::widget::{CrossAxisAlignment, Flex, Label, LineBreaking, Scroll};
+use druid::{WidgetExt, WindowId};
+use norad::GlyphName;
+
+use crate::app_delegate::EDIT_GLYPH;
+use crate::data::AppState;
+use crate::report::FontReport;
+use crate::theme;
+
+/// The contents of a QA report window.
+///
+/// The report is a snapshot, taken when the window was opened. Clicking a
+/// glyph opens it in an editor belonging to `font_window`.
+pub fn qa_report(report: &FontReport, font_window: Option<WindowId>) -> impl Widget<AppState> {
+    let summary = if report.is_empty() {
+        format!("No problems found in {} glyphs.", report.glyph_count)
+    } else {
+        format!(
+            "{} problems found in {} glyphs.",
+            report.items.len(),
+            report.glyph_count
+        )
+    };
+
+    let mut column = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new(report.font.as_str()).with_text_color(theme::PRIMARY_TEXT_COLOR))
+        .with_child(Label::new(summary).with_text_color(theme::SECONDARY_TEXT_COLOR));
+
+    for (category, items) in report.by_category() {
+        column.add_spacer(16.0);
+        column.add_child(
+            Label::new(format!("{} ({})", category.title(), items.len()))
+                .with_text_color(theme::PRIMARY_TEXT_COLOR),
+        );
+        for item in items {
+            let text = match &item.glyph {
+                Some(glyph) => format!("{}: {}", glyph, item.message),
+                None => item.message.clone(),
+            };
+            let label = Label::new(text)
+                .with_font(theme::UI_DETAIL_FONT)
+                .with_text_color(theme::SECONDARY_TEXT_COLOR)
+                .with_line_break_mode(LineBreaking::WordWrap)
+                .padding((8.0, 2.0));
+            match (&item.glyph, font_window) {
+                (Some(glyph), Some(window)) => {
+                    let glyph: GlyphName = glyph.as_str().into();
+                    column.add_child(label.on_click(move |ctx, _, _| {
+                        ctx.submit_command(EDIT_GLYPH.with(glyph.clone()).to(window));
+                    }));
+                }
+                _ => column.add_child(label),
+            }
+        }
+    }
+
+    Scroll::new(column.padding(16.0))
+        .vertical()
+        .expand()
+        .background(theme::GLYPH_LIST_BACKGROUND)
+}
@@ -6,11 +6,12 @@ use druid::{
     PaintCtx, Rect, RenderContext, Size, UpdateCtx, Widget, WidgetPod,
 };
 
-use druid::widget::{Controller, Flex, Label, SizedBox, WidgetExt};
+use druid::widget::{Controller, Flex, Label, Painter, SizedBox, WidgetExt};
 
 use norad::GlyphName;
 
 use crate::data::{GlyphDetail, Workspace};
+use crate::glyph_lib::MARK_COLORS;
 use crate::theme;
 use crate::widgets::{EditableLabel, GlyphPainter, Maybe};
 
@@ -20,6 +21,8 @@ const SELECTED_GLYPH_HEIGHT: f64 = 100.0;
 // So that accents don't paint too much over other widgets
 const GLYPH_TOP_PADDING: f64 = SELECTED_GLYPH_HEIGHT * 0.2;
 
+const MARK_COLOR_SWATCH_SIZE: f64 = 14.0;
+
 pub struct Sidebar {
     selected_glyph: WidgetPod<Workspace, Box<dyn Widget<Workspace>>>,
 }
@@ -60,6 +63,25 @@ fn selected_glyph_widget() -> impl Widget<GlyphDetail> {
                 .fix_width(45.)
                 .lens(GlyphDetail::advance),
         )
+        .with_spacer(8.0)
+        .with_child(mark_color_picker().lens(GlyphDetail::mark_color))
+        .with_spacer(4.0)
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Label::new("tags")
+                        .with_text_color(theme::SECONDARY_TEXT_COLOR)
+                        .with_font(theme::UI_DETAIL_FONT),
+                )
+                .with_default_spacer()
+                .with_child(
+                    EditableLabel::new(|tags: &String, _: &_| tags.clone(), |s| Some(s.into()))
+                        .with_placeholder("none")
+                        .with_font(theme::UI_DETAIL_FONT)
+                        .lens(GlyphDetail::tags)
+                        .fix_width(120.),
+                ),
+        )
         .with_child(
             Flex::row()
                 .with_child(
@@ -77,6 +99,42 @@ fn selected_glyph_widget() -> impl Widget<GlyphDetail> {
         )
 }
 
+/// A row of swatches for setting the glyph's mark color.
+fn mark_color_picker() -> impl Widget<Option<Color>> {
+    MARK_COLORS.iter().fold(
+        Flex::row().with_child(mark_color_swatch(None)),
+        |row, (_, color)| {
+            row.with_spacer(4.0)
+                .with_child(mark_color_swatch(Some(color.clone())))
+        },
+    )
+}
+
+fn mark_color_swatch(color: Option<Color>) -> impl Widget<Option<Color>> {
+    let new_color = color.clone();
+    Painter::new(move |ctx, data: &Option<Color>, env| {
+        let rect = ctx.size().to_rect().inset(-1.0);
+        let rounded = rect.to_rounded_rect(3.0);
+        match color.as_ref() {
+            Some(color) => ctx.fill(rounded, color),
+            // 'no color' is an empty box with a slash through it
+            None => {
+                let slash = Line::new((rect.x0, rect.y1), (rect.x1, rect.y0));
+                ctx.stroke(slash, &env.get(theme::SECONDARY_TEXT_COLOR), 1.0);
+            }
+        }
+        let is_current =
+            data.as_ref().map(Color::as_rgba_u32) == color.as_ref().map(Color::as_rgba_u32);
+        if is_current {
+            ctx.stroke(rounded, &env.get(theme::FOCUS_OUTLINE_COLOR), 2.0);
+        } else {
+            ctx.stroke(rounded, &env.get(theme::SIDEBAR_EDGE_STROKE), 1.0);
+        }
+    })
+    .fix_size(MARK_COLOR_SWATCH_SIZE, MARK_COLOR_SWATCH_SIZE)
+    .on_click(move |_, data: &mut Option<Color>, _| *data = new_color.clone())
+}
+
 impl Sidebar {
     pub fn new() -> Sidebar {
         Sidebar {
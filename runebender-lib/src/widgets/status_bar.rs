@@ -0,0 +1,140 @@
+//! A bar along the bottom of the main window that shows notifications.
+
+use druid::kurbo::{Circle, Line};
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Either, Flex, Label, LineBreaking, Painter};
+use druid::{Color, Key, WidgetExt};
+
+use crate::data::{AppState, Notification, NotificationKind, WorkspaceId};
+use crate::theme;
+use crate::widgets::ModalHost;
+
+const STATUS_BAR_HEIGHT: f64 = 32.0;
+
+/// Shows the most recent notification for the font with the given id, if
+/// any, with buttons to see more details or dismiss it.
+pub fn status_bar(id: WorkspaceId) -> impl Widget<AppState> {
+    let background = Painter::new(|ctx, _: &AppState, env| {
+        let rect = ctx.size().to_rect();
+        let line = Line::new((0.0, 0.5), (rect.width(), 0.5));
+        ctx.fill(rect, &env.get(theme::SIDEBAR_BACKGROUND));
+        ctx.stroke(line, &env.get(theme::SIDEBAR_EDGE_STROKE), 1.0);
+    });
+
+    let message = Label::new(move |data: &AppState, _: &Env| {
+        let latest = data.notifications_for(id).last();
+        let message = latest.map(|n| n.message.to_string()).unwrap_or_default();
+        match data.notifications_for(id).count() {
+            0 | 1 => message,
+            n => format!("{} (+{} more)", message, n - 1),
+        }
+    })
+    .with_text_color(theme::PRIMARY_TEXT_COLOR);
+
+    let bar = Flex::row()
+        .with_child(
+            Painter::new(move |ctx, data: &AppState, env| {
+                if let Some(latest) = data.notifications_for(id).last() {
+                    let rect = ctx.size().to_rect();
+                    let circle = Circle::new(rect.center(), 4.0);
+                    ctx.fill(circle, &env.get(color_for_kind(latest.kind)));
+                }
+            })
+            .fix_size(12.0, 12.0),
+        )
+        .with_default_spacer()
+        .with_flex_child(message.expand_width(), 1.0)
+        .with_child(Button::new("Details…").on_click(move |ctx, _, _| {
+            ctx.submit_command(ModalHost::make_modal_command(move || {
+                notification_details(id)
+            }));
+        }))
+        .with_default_spacer()
+        .with_child(
+            Button::new("Dismiss").on_click(move |_, data: &mut AppState, _| {
+                data.dismiss_notification(id);
+            }),
+        )
+        .padding((8.0, 0.0))
+        .fix_height(STATUS_BAR_HEIGHT)
+        .background(background);
+
+    Either::new(
+        move |data: &AppState, _| data.notifications_for(id).next().is_none(),
+        Flex::row(),
+        bar,
+    )
+}
+
+/// A modal showing the full text of the most recent notification.
+fn notification_details(id: WorkspaceId) -> impl Widget<AppState> {
+    let message = Label::new(move |data: &AppState, _: &Env| {
+        data.notifications_for(id)
+            .last()
+            .map(|n| n.message.to_string())
+            .unwrap_or_default()
+    });
+
+    let details = Label::new(move |data: &AppState, _: &Env| {
+        data.notifications_for(id)
+            .last()
+            .and_then(|n| n.details.as_ref())
+            .map(|d| d.to_string())
+            .unwrap_or_default()
+    })
+    .with_line_break_mode(LineBreaking::WordWrap)
+    .with_text_color(theme::SECONDARY_TEXT_COLOR)
+    .with_font(theme::UI_DETAIL_FONT);
+
+    let retry = Either::new(
+        move |data: &AppState, _| {
+            data.notifications_for(id)
+                .last()
+                .map(|n| n.retry.is_some())
+                .unwrap_or(false)
+        },
+        Button::new("Retry").on_click(move |ctx, data: &mut AppState, _| {
+            if let Some(Notification {
+                retry: Some(retry), ..
+            }) = data.dismiss_notification(id)
+            {
+                ctx.submit_command(retry);
+            }
+            ctx.submit_command(ModalHost::DISMISS_MODAL);
+        }),
+        Flex::row(),
+    );
+
+    Flex::column()
+        .with_child(message)
+        .with_default_spacer()
+        .with_child(details)
+        .with_flex_spacer(1.0)
+        .with_child(
+            Flex::row()
+                .with_child(retry)
+                .with_default_spacer()
+                .with_child(
+                    Button::new("Dismiss").on_click(move |ctx, data: &mut AppState, _| {
+                        data.dismiss_notification(id);
+                        ctx.submit_command(ModalHost::DISMISS_MODAL);
+                    }),
+                )
+                .with_default_spacer()
+                .with_child(
+                    Button::new("Close")
+                        .on_click(|ctx, _, _| ctx.submit_command(ModalHost::DISMISS_MODAL)),
+                ),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .fix_size(420., 200.)
+        .padding(16.0)
+        .background(Color::WHITE)
+}
+
+fn color_for_kind(kind: NotificationKind) -> Key<Color> {
+    match kind {
+        NotificationKind::Warning => theme::WARNING_COLOR,
+        NotificationKind::Error => theme::ERROR_COLOR,
+    }
+}
@@ -190,7 +190,8 @@ impl<T: Data, W: Widget<T>> Widget<T> for FloatingPanel<W> {
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        if self.hide_panel {
+        // an empty panel (such as an issue list with no issues) draws nothing
+        if self.hide_panel || ctx.size() == Size::ZERO {
             return;
         }
         let frame = ctx.size().to_rect();
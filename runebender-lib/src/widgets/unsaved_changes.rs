@@ -0,0 +1,54 @@
+//! A prompt shown when closing a font with unsaved changes.
+//!
+//! This is intended to be shown as a modal panel.
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label};
+use druid::{Color, WidgetExt};
+
+use crate::consts::cmd::{UnsavedChangesChoice, UNSAVED_CHANGES_RESPONSE};
+use crate::data::Workspace;
+use crate::theme;
+
+pub fn unsaved_changes_prompt() -> impl Widget<Workspace> {
+    let title = Label::new(|data: &Workspace, _: &Env| {
+        format!(
+            "Save changes to {} {}?",
+            data.info.family_name, data.info.style_name
+        )
+    });
+
+    let detail = Label::new(|data: &Workspace, _: &Env| {
+        let count = data.iter_modified_glyphs().count();
+        match count {
+            0 => "Your changes will be lost if you don't save them.".to_string(),
+            1 => "1 glyph has unsaved changes.".to_string(),
+            n => format!("{} glyphs have unsaved changes.", n),
+        }
+    })
+    .with_text_color(theme::SECONDARY_TEXT_COLOR);
+
+    Flex::column()
+        .with_child(title)
+        .with_default_spacer()
+        .with_child(detail)
+        .with_spacer(16.0)
+        .with_child(
+            Flex::row()
+                .with_child(choice_button("Don't Save", UnsavedChangesChoice::Discard))
+                .with_flex_spacer(1.0)
+                .with_child(choice_button("Cancel", UnsavedChangesChoice::Cancel))
+                .with_default_spacer()
+                .with_child(choice_button("Save", UnsavedChangesChoice::Save)),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .fix_width(360.)
+        .padding(16.0)
+        .background(Color::WHITE)
+}
+
+fn choice_button(title: &str, choice: UnsavedChangesChoice) -> impl Widget<Workspace> {
+    Button::new(title).on_click(move |ctx, _, _| {
+        ctx.submit_command(UNSAVED_CHANGES_RESPONSE.with(choice));
+    })
+}
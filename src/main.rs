@@ -1,20 +1,21 @@
 //! A font editor.
 
-use druid::kurbo::Line;
-use druid::widget::{Button, Flex, Label, Painter, Scroll, WidgetExt};
-use druid::{AppLauncher, Env, LocalizedString, RenderContext, Size, Widget, WindowDesc};
+use druid::AppLauncher;
 
 use runebender_lib::data::{AppState, Workspace};
-use runebender_lib::widgets::{self, GlyphGrid, ModalHost, RootWindowController, Sidebar};
-use runebender_lib::{menus, theme, Delegate};
+use runebender_lib::preferences::Preferences;
+use runebender_lib::report::FontReport;
+use runebender_lib::{make_font_window, theme, Delegate};
 
 fn main() {
-    let state = get_initial_state();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--qa-report") {
+        std::process::exit(run_qa_report(&args[1..]));
+    }
 
-    let main_window = WindowDesc::new(make_ui)
-        .title(LocalizedString::new("Runebender"))
-        .menu(menus::make_menu(&state))
-        .window_size(Size::new(900.0, 800.0));
+    let mut state = get_initial_state();
+    let id = *state.workspaces.keys().next().unwrap();
+    let main_window = make_font_window(&mut state, id);
 
     AppLauncher::with_window(main_window)
         .delegate(Delegate::default())
@@ -24,52 +25,6 @@ fn main() {
         .expect("launch failed");
 }
 
-fn make_ui() -> impl Widget<AppState> {
-    // paint a line under the top title bar
-    let hline_painter = Painter::new(|ctx, _: &Workspace, env| {
-        let rect = ctx.size().to_rect();
-        let max_y = rect.height() - 0.5;
-        let line = Line::new((0.0, max_y), (rect.width(), max_y));
-
-        ctx.fill(rect, &env.get(theme::GLYPH_LIST_BACKGROUND));
-        ctx.stroke(line, &env.get(theme::SIDEBAR_EDGE_STROKE), 1.0);
-    });
-
-    let label = Label::new(|data: &Workspace, _: &Env| {
-        format!("{} {}", data.info.family_name, data.info.style_name)
-    });
-
-    let button = Button::new("(edit)").on_click(|ctx, _data, _env| {
-        let cmd = ModalHost::make_modal_command(crate::widgets::font_info);
-        ctx.submit_command(cmd);
-    });
-
-    let main_view = Flex::column()
-        .with_child(
-            Flex::row()
-                .with_child(label)
-                .with_spacer(8.0)
-                .with_child(button)
-                .padding(5.0)
-                .center()
-                .fix_height(40.)
-                .expand_width()
-                .background(hline_painter),
-        )
-        .with_flex_child(
-            Flex::row()
-                .with_child(Sidebar::new().fix_width(180.))
-                .with_flex_child(Scroll::new(GlyphGrid::new()).vertical().expand_width(), 1.0),
-            1.,
-        );
-
-    crate::theme::wrap_in_theme_loader(
-        ModalHost::new(main_view)
-            .lens(AppState::workspace)
-            .controller(RootWindowController::default()),
-    )
-}
-
 /// If there was an argument passed at the command line, try to open it as a .ufo
 /// file, otherwise return blank state.
 fn get_initial_state() -> AppState {
@@ -88,7 +43,87 @@ fn get_initial_state() -> AppState {
         (runebender_lib::create_blank_font(), None)
     };
 
+    let mut preferences = Preferences::load();
+    if let Some(path) = path.as_ref() {
+        preferences.add_recent_file(path);
+        if let Err(e) = preferences.save() {
+            eprintln!("Failed to save preferences: '{}'", e);
+        }
+    }
+
     let mut workspace = Workspace::default();
     workspace.set_file(font_file, path);
-    AppState { workspace }
+    let mut state = AppState {
+        preferences,
+        ..Default::default()
+    };
+    let id = state.add_workspace(workspace);
+    state.warn_about_broken_components(id);
+    state
+}
+
+/// Write a QA report for a font, without opening a window.
+///
+/// usage: `runebender --qa-report [--format json|html] [--output FILE] FONT.ufo`
+///
+/// Returns the exit code: 0 if no problems were found, 1 if there were
+/// problems, and 2 if the font could not be checked.
+fn run_qa_report(args: &[String]) -> i32 {
+    let mut format = "json";
+    let mut output = None;
+    let mut font_path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next().map(String::as_str).unwrap_or(""),
+            "--output" | "-o" => output = args.next(),
+            path => font_path = Some(path),
+        }
+    }
+
+    let font_path = match font_path {
+        Some(path) => path,
+        None => {
+            eprintln!(
+                "usage: runebender --qa-report [--format json|html] [--output FILE] FONT.ufo"
+            );
+            return 2;
+        }
+    };
+    let ufo = match norad::Ufo::load(font_path) {
+        Ok(ufo) => ufo,
+        Err(e) => {
+            eprintln!("Failed to load '{}': '{}'", font_path, e);
+            return 2;
+        }
+    };
+
+    let report = FontReport::for_ufo(&ufo);
+    let text = match format {
+        "json" => report.to_json(),
+        "html" => report.to_html(),
+        other => {
+            eprintln!(
+                "Unknown report format '{}'; expected 'json' or 'html'",
+                other
+            );
+            return 2;
+        }
+    };
+
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, text) {
+                eprintln!("Failed to write report to '{}': '{}'", path, e);
+                return 2;
+            }
+        }
+        None => println!("{}", text),
+    }
+
+    if report.is_empty() {
+        0
+    } else {
+        1
+    }
 }